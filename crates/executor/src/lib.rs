@@ -1,14 +1,20 @@
+use bindings::wasi::clocks::monotonic_clock;
 use bindings::wasi::io;
+use std::cell::{Cell, RefCell};
 use std::future::Future;
 use std::mem;
-use std::sync::{Arc, Mutex};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
 
 /// Module containing the generated WIT bindings.
 pub mod bindings {
     wit_bindgen::generate!({
         world: "imports",
-        path: "io.wit",
+        path: "wit",
     });
 }
 
@@ -20,57 +26,565 @@ impl std::fmt::Display for io::streams::Error {
 
 impl std::error::Error for io::streams::Error {}
 
-static WAKERS: Mutex<Vec<(io::poll::Pollable, Waker)>> = Mutex::new(Vec::new());
+pub mod sync;
+
+thread_local! {
+    /// The pollables being waited on, and the wakers to wake when they are ready.
+    static WAKERS: RefCell<Vec<(Rc<io::poll::Pollable>, Waker)>> = const { RefCell::new(Vec::new()) };
+}
 
 /// Push a Pollable and Waker to WAKERS.
 pub fn push_waker(pollable: io::poll::Pollable, waker: Waker) {
-    WAKERS.lock().unwrap().push((pollable, waker));
+    WAKERS.with(|wakers| wakers.borrow_mut().push((Rc::new(pollable), waker)));
+}
+
+#[doc(hidden)]
+/// The number of pollables being waited on, for tests.
+pub fn __registered_pollables() -> usize {
+    WAKERS.with(|wakers| wakers.borrow().len())
+}
+
+/// A future which is ready when a [`Pollable`](io::poll::Pollable) is, for awaiting readiness
+/// from host interfaces which have no async wrapper in the SDK.
+///
+/// ```ignore
+/// let stream = socket.input_stream()?;
+/// PollableFuture::new(stream.subscribe()).await;
+/// let bytes = stream.read(4096)?;
+/// ```
+///
+/// The pollable is kept by the future, and stops being waited on when the future is dropped,
+/// so it can be dropped before the resource it belongs to.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct PollableFuture {
+    pollable: Rc<io::poll::Pollable>,
+}
+
+impl PollableFuture {
+    /// Wait for `pollable` to be ready.
+    pub fn new(pollable: io::poll::Pollable) -> Self {
+        Self {
+            pollable: Rc::new(pollable),
+        }
+    }
+
+    /// The pollable, which is no longer waited on.
+    pub fn into_inner(self) -> io::poll::Pollable {
+        self.unregister();
+        let pollable = self.pollable.clone();
+        drop(self);
+        Rc::into_inner(pollable).expect("pollable should no longer be registered")
+    }
+
+    fn unregister(&self) {
+        WAKERS.with(|wakers| {
+            wakers
+                .borrow_mut()
+                .retain(|(pollable, _)| !Rc::ptr_eq(pollable, &self.pollable))
+        });
+    }
+}
+
+impl From<io::poll::Pollable> for PollableFuture {
+    fn from(pollable: io::poll::Pollable) -> Self {
+        Self::new(pollable)
+    }
+}
+
+impl Future for PollableFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // Replace any earlier registration, whose waker may be out of date.
+        self.unregister();
+        if self.pollable.ready() {
+            Poll::Ready(())
+        } else {
+            WAKERS.with(|wakers| {
+                wakers
+                    .borrow_mut()
+                    .push((self.pollable.clone(), cx.waker().clone()))
+            });
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for PollableFuture {
+    fn drop(&mut self) {
+        self.unregister();
+    }
+}
+
+/// Wait until `duration` has elapsed, without blocking other futures run by the executor.
+///
+/// Unlike `std::thread::sleep`, which blocks the whole component, this lets other futures,
+/// such as outgoing requests, make progress in the meantime.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        duration,
+        timer: None,
+    }
+}
+
+/// The future returned by [`sleep`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct Sleep {
+    duration: Duration,
+    /// The timer, started when first polled. It stops being waited on when the `Sleep` is
+    /// dropped.
+    timer: Option<PollableFuture>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let duration = self.duration;
+        let timer = self.timer.get_or_insert_with(|| {
+            let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+            PollableFuture::new(monotonic_clock::subscribe_duration(nanos))
+        });
+        Pin::new(timer).poll(cx)
+    }
+}
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+thread_local! {
+    /// The tasks spawned within each active call to [`run`], innermost last.
+    static TASKS: RefCell<Vec<Vec<Task>>> = const { RefCell::new(Vec::new()) };
+    /// How many calls to [`run`] are active.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Run `future` concurrently with the future passed to [`run`], returning a handle with which
+/// to await its output.
+///
+/// ```ignore
+/// let inventory = spawn_local(spin_sdk::http::send(inventory_request));
+/// let prices = spin_sdk::http::send(prices_request).await?;
+/// let inventory = inventory.await?;
+/// ```
+///
+/// Tasks make progress while [`run`] is running, whether or not their handles are awaited, and
+/// dropping a handle does not stop its task. Tasks which have not finished when [`run`]
+/// returns are dropped. A task spawned within a nested call to [`run`] belongs to that call,
+/// and tasks belonging to outer calls wait until it returns.
+pub fn spawn_local<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+{
+    let output = Rc::new(RefCell::new(None));
+    let slot = output.clone();
+    let task: Task = Box::pin(async move {
+        let value = future.await;
+        *slot.borrow_mut() = Some(value);
+    });
+    TASKS.with(|levels| {
+        let mut levels = levels.borrow_mut();
+        match levels.last_mut() {
+            Some(tasks) => tasks.push(task),
+            None => levels.push(vec![task]),
+        }
+    });
+    JoinHandle {
+        output,
+        taken: false,
+    }
+}
+
+/// A handle to a task started with [`spawn_local`], which resolves to the task's output.
+#[derive(Debug)]
+pub struct JoinHandle<T> {
+    output: Rc<RefCell<Option<T>>>,
+    taken: bool,
+}
+
+impl<T> JoinHandle<T> {
+    /// Whether the task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.taken || self.output.borrow().is_some()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<T> {
+        assert!(!self.taken, "`JoinHandle` polled after completion");
+        let output = self.output.borrow_mut().take();
+        match output {
+            Some(output) => {
+                self.taken = true;
+                Poll::Ready(output)
+            }
+            // `run` polls this again whenever a task finishes.
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Yield to the executor once, letting other futures and tasks make progress.
+///
+/// ```ignore
+/// for (i, row) in rows.iter().enumerate() {
+///     process(row);
+///     if i % 1000 == 0 {
+///         yield_now().await;
+///     }
+/// }
+/// ```
+///
+/// The executor checks for completed I/O before polling the caller again, so a long-running
+/// loop which yields regularly does not hold up other futures' requests and responses.
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+/// The future returned by [`yield_now`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        // Waking directly would have `run` poll again straight away. Waiting on a pollable
+        // which is already ready has it poll all the pending I/O first.
+        push_waker(monotonic_clock::subscribe_duration(0), cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Run `future`, giving up if it has not finished within `duration`.
+///
+/// ```ignore
+/// let response: Response = timeout(Duration::from_secs(5), spin_sdk::http::send(request))
+///     .await
+///     .map_err(|_| anyhow::anyhow!("upstream took too long"))??;
+/// ```
+///
+/// When the time runs out, `future` is dropped without being polled again.
+pub fn timeout<F: Future>(duration: Duration, future: F) -> Timeout<F> {
+    Timeout {
+        future,
+        sleep: sleep(duration),
+    }
+}
+
+/// The future returned by [`timeout`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct Timeout<F> {
+    future: F,
+    sleep: Sleep,
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is never moved out of the pinned `Timeout`.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        if let Poll::Ready(output) = future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match Pin::new(&mut this.sleep).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(Elapsed(()))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The error returned by [`timeout`] when the future did not finish in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+impl std::fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Copy every chunk from `stream` into `sink`, returning the number of bytes copied.
+///
+/// ```ignore
+/// let mut body = outgoing_request.take_body();
+/// let response = spin_sdk::http::send::<_, IncomingResponse>(outgoing_request);
+/// let copied = copy(incoming_request.into_body_stream(), &mut body).await?;
+/// ```
+///
+/// Each chunk is sent once the sink is ready for it, so a slow sink holds back reading from
+/// the stream rather than chunks piling up in memory. The sink is flushed at the end but not
+/// closed; pass it by value to drop it once the copy is done. Copying stops at the first error.
+pub async fn copy<S, K, B, E>(stream: S, sink: K) -> Result<u64, CopyError<E, K::Error>>
+where
+    S: futures::Stream<Item = Result<B, E>>,
+    K: futures::Sink<B>,
+    B: AsRef<[u8]>,
+{
+    use futures::{SinkExt, StreamExt};
+
+    futures::pin_mut!(stream, sink);
+    let mut copied = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(CopyError::Read)?;
+        let len = chunk.as_ref().len() as u64;
+        sink.feed(chunk).await.map_err(CopyError::Write)?;
+        copied += len;
+    }
+    sink.flush().await.map_err(CopyError::Write)?;
+    Ok(copied)
+}
+
+/// An error from [`copy`].
+#[derive(Debug)]
+pub enum CopyError<R, W> {
+    /// Reading from the stream failed.
+    Read(R),
+    /// Writing to the sink failed.
+    Write(W),
+}
+
+impl<R: std::fmt::Display, W: std::fmt::Display> std::fmt::Display for CopyError<R, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CopyError::Read(e) => write!(f, "error reading from stream: {e}"),
+            CopyError::Write(e) => write!(f, "error writing to sink: {e}"),
+        }
+    }
+}
+
+impl<R, W> std::error::Error for CopyError<R, W>
+where
+    R: std::error::Error + 'static,
+    W: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CopyError::Read(e) => Some(e),
+            CopyError::Write(e) => Some(e),
+        }
+    }
 }
 
 /// Run the specified future to completion blocking until it yields a result.
 ///
-/// Based on an executor using `wasi::io/poll/poll-list`,
+/// Based on an executor using `wasi::io/poll/poll-list`. The future, and any tasks it
+/// [spawns](spawn_local), are polled again as soon as they are woken, whether by a pollable
+/// registered with [`push_waker`] becoming ready or by anything else calling their waker, such
+/// as a [channel](sync). When nothing has been woken, `run` blocks until one of the registered
+/// pollables is ready.
+///
+/// `run` may be called from within a future it is running, in which case the inner call
+/// runs its future to completion before the outer call continues.
+///
+/// # Panics
+///
+/// Panics if the future is pending and nothing can wake it: no waker has been called and no
+/// pollables are registered.
 pub fn run<T>(future: impl Future<Output = T>) -> T {
     futures::pin_mut!(future);
-    struct DummyWaker;
+    /// Records that a future was woken by something other than a pollable, such as a channel.
+    struct FlagWaker(AtomicBool);
 
-    impl Wake for DummyWaker {
-        fn wake(self: Arc<Self>) {}
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::Relaxed);
+        }
     }
 
-    let waker = Arc::new(DummyWaker).into();
+    let woken = Arc::new(FlagWaker(AtomicBool::new(false)));
+    let waker = woken.clone().into();
 
-    loop {
-        match future.as_mut().poll(&mut Context::from_waker(&waker)) {
-            Poll::Pending => {
-                let mut new_wakers = Vec::new();
+    // Tasks spawned before the outermost call belong to it.
+    let outermost = DEPTH.with(|depth| depth.replace(depth.get() + 1)) == 0;
+    let level = TASKS.with(|levels| {
+        let mut levels = levels.borrow_mut();
+        if !outermost || levels.is_empty() {
+            levels.push(Vec::new());
+        }
+        levels.len() - 1
+    });
 
-                let wakers = mem::take::<Vec<_>>(&mut WAKERS.lock().unwrap());
+    let result = loop {
+        woken.0.store(false, Ordering::Relaxed);
+        if let Poll::Ready(result) = future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            break result;
+        }
 
-                assert!(!wakers.is_empty());
+        // A finished task, or a wake from a channel, may be what the future is waiting for, so
+        // poll it again before waiting on any pollables.
+        let finished = poll_tasks(level, &waker);
+        if finished || woken.0.load(Ordering::Relaxed) {
+            continue;
+        }
 
-                let pollables = wakers
-                    .iter()
-                    .map(|(pollable, _)| pollable)
-                    .collect::<Vec<_>>();
+        let wakers = WAKERS.with(|wakers| mem::take(&mut *wakers.borrow_mut()));
+        assert!(
+            !wakers.is_empty(),
+            "future passed to `spin_executor::run` is pending, but nothing can wake it"
+        );
 
-                let mut ready = vec![false; wakers.len()];
+        let pollables = wakers
+            .iter()
+            .map(|(pollable, _)| &**pollable)
+            .collect::<Vec<_>>();
 
-                for index in io::poll::poll(&pollables) {
-                    ready[usize::try_from(index).unwrap()] = true;
-                }
+        let mut ready = vec![false; wakers.len()];
 
-                for (ready, (pollable, waker)) in ready.into_iter().zip(wakers) {
-                    if ready {
-                        waker.wake()
-                    } else {
-                        new_wakers.push((pollable, waker));
-                    }
-                }
+        for index in io::poll::poll(&pollables) {
+            ready[usize::try_from(index).unwrap()] = true;
+        }
 
-                *WAKERS.lock().unwrap() = new_wakers;
+        let mut new_wakers = Vec::new();
+        for (ready, (pollable, waker)) in ready.into_iter().zip(wakers) {
+            if ready {
+                waker.wake()
+            } else {
+                new_wakers.push((pollable, waker));
             }
-            Poll::Ready(result) => break result,
         }
+
+        WAKERS.with(|wakers| *wakers.borrow_mut() = new_wakers);
+    };
+
+    DEPTH.with(|depth| depth.set(depth.get() - 1));
+    TASKS.with(|levels| levels.borrow_mut().truncate(level));
+    result
+}
+
+/// Poll each task spawned at `level` once, returning whether any finished.
+fn poll_tasks(level: usize, waker: &Waker) -> bool {
+    let mut tasks = TASKS.with(|levels| mem::take(&mut levels.borrow_mut()[level]));
+    let count = tasks.len();
+    tasks.retain_mut(|task| {
+        task.as_mut()
+            .poll(&mut Context::from_waker(waker))
+            .is_pending()
+    });
+    let finished = tasks.len() < count;
+    // Keep any tasks spawned by the tasks polled above.
+    TASKS.with(|levels| {
+        let spawned = &mut levels.borrow_mut()[level];
+        tasks.append(spawned);
+        *spawned = tasks;
+    });
+    finished
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_spawned_tasks() {
+        let sum = run(async {
+            let a = spawn_local(async { 1 });
+            let b = spawn_local(async {
+                let c = spawn_local(async { 2 });
+                c.await + 3
+            });
+            a.await + b.await
+        });
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn sends_between_tasks() {
+        let received = run(async {
+            let (sender, mut receiver) = sync::mpsc::channel();
+            let (done, finished) = sync::oneshot::channel();
+            spawn_local(async move {
+                for i in 0..3 {
+                    sender.send(i).unwrap();
+                }
+                done.send("done").unwrap();
+            });
+            let mut received = Vec::new();
+            while let Some(i) = receiver.recv().await {
+                received.push(i);
+            }
+            assert_eq!(finished.await, Ok("done"));
+            received
+        });
+        assert_eq!(received, [0, 1, 2]);
+    }
+
+    #[test]
+    fn copies_stream_to_sink() {
+        let chunks = [Ok::<_, ()>(vec![1, 2]), Ok(vec![3])];
+        let mut sink = Vec::<Vec<u8>>::new();
+        let copied = run(copy(futures::stream::iter(chunks), &mut sink));
+        assert_eq!(copied.unwrap(), 3);
+        assert_eq!(sink, [vec![1, 2], vec![3]]);
+
+        let chunks = [Ok(vec![1]), Err("broken"), Ok(vec![2])];
+        let copied = run(copy(futures::stream::iter(chunks), Vec::<Vec<u8>>::new()));
+        assert!(matches!(copied, Err(CopyError::Read("broken"))));
+    }
+
+    #[test]
+    fn runs_nested_and_self_woken_futures() {
+        let mut yielded = false;
+        let yield_once = std::future::poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        });
+        let outer = spawn_local(async { 1 });
+        let total = run(async {
+            yield_once.await;
+            let inner = run(async { spawn_local(async { 2 }).await });
+            outer.await + inner
+        });
+        assert_eq!(total, 3);
+        assert!(TASKS.with(|levels| levels.borrow().is_empty()));
+    }
+
+    #[test]
+    fn locks_across_awaits() {
+        let log = run(async {
+            let log = sync::Mutex::new(Vec::new());
+            let (sender, receiver) = sync::oneshot::channel();
+            let holder = async {
+                let mut log = log.lock().await;
+                log.push("first");
+                receiver.await.unwrap();
+                log.push("second");
+            };
+            let waiter = async {
+                sender.send(()).unwrap();
+                log.lock().await.push("third");
+            };
+            futures::future::join(holder, waiter).await;
+            log.into_inner()
+        });
+        assert_eq!(log, ["first", "second", "third"]);
+
+        let lock = sync::RwLock::new(1);
+        let read = lock.try_read().unwrap();
+        assert!(lock.try_read().is_some());
+        assert!(lock.try_write().is_none());
+        drop(read);
+        *lock.try_write().unwrap() += 1;
+        assert_eq!(*lock.try_read().unwrap(), 2);
     }
 }
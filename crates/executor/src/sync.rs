@@ -0,0 +1,14 @@
+//! Communication and synchronization between futures run by the executor.
+//!
+//! Components are single-threaded, so these types are neither `Send` nor `Sync`, and wake the
+//! waiting future directly rather than through a pollable.
+
+pub mod mpsc;
+mod mutex;
+pub mod oneshot;
+mod rwlock;
+mod semaphore;
+
+pub use mutex::{Mutex, MutexGuard};
+pub use rwlock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+pub use semaphore::{Semaphore, SemaphorePermit};
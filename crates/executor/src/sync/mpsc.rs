@@ -0,0 +1,153 @@
+//! A channel for sending a stream of values from any number of futures to one.
+//!
+//! ```ignore
+//! let (sender, mut receiver) = mpsc::channel();
+//! for url in urls {
+//!     let sender = sender.clone();
+//!     spawn_local(async move {
+//!         let _ = sender.send(fetch(url).await);
+//!     });
+//! }
+//! drop(sender);
+//! while let Some(page) = receiver.recv().await {
+//!     ...
+//! }
+//! ```
+//!
+//! The channel is unbounded: sending never waits, and values are queued until they are
+//! received.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// Create a channel for sending a stream of values.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        queue: VecDeque::new(),
+        waker: None,
+        senders: 1,
+        receiver_dropped: false,
+    }));
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+    senders: usize,
+    receiver_dropped: bool,
+}
+
+/// The sending half of a [`channel`], which can be cloned to send from several futures.
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Send `value` to the receiver, failing if the receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.receiver_dropped {
+            return Err(SendError(value));
+        }
+        shared.queue.push_back(value);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Whether the receiver has been dropped, so that sending would fail.
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().receiver_dropped
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.senders -= 1;
+        if shared.senders == 0 {
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// The receiving half of a [`channel`].
+///
+/// The receiver is also a [`Stream`](futures::Stream) of the values sent.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receive the next value, or `None` once every sender has been dropped and all the values
+    /// sent have been received.
+    pub async fn recv(&mut self) -> Option<T> {
+        futures::StreamExt::next(self).await
+    }
+
+    /// Receive the next value if one is waiting, without waiting for one.
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.shared.borrow_mut().queue.pop_front()
+    }
+}
+
+impl<T> futures::Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(value) = shared.queue.pop_front() {
+            Poll::Ready(Some(value))
+        } else if shared.senders == 0 {
+            Poll::Ready(None)
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.receiver_dropped = true;
+        shared.queue.clear();
+    }
+}
+
+/// The error returned by [`Sender::send`] when the receiver has been dropped, holding the
+/// value which could not be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> std::fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("receiver dropped")
+    }
+}
+
+impl<T: std::fmt::Debug> std::error::Error for SendError<T> {}
@@ -0,0 +1,92 @@
+use std::cell::{RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+
+use super::{Semaphore, SemaphorePermit};
+
+/// An async mutex, for state shared between futures which may be held across an `.await`.
+///
+/// ```ignore
+/// let cache = Mutex::new(HashMap::new());
+/// futures::future::join(
+///     async { cache.lock().await.insert("a", fetch("a").await) },
+///     async { cache.lock().await.insert("b", fetch("b").await) },
+/// )
+/// .await;
+/// ```
+///
+/// A `RefCell` is enough for state which is never borrowed across an `.await`.
+#[derive(Debug)]
+pub struct Mutex<T> {
+    semaphore: Semaphore,
+    value: RefCell<T>,
+}
+
+impl<T> Mutex<T> {
+    /// Create a mutex holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(1),
+            value: RefCell::new(value),
+        }
+    }
+
+    /// Wait until the mutex is unlocked, then lock it.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        let permit = self.semaphore.acquire().await;
+        self.guard(permit)
+    }
+
+    /// Lock the mutex if it is unlocked, without waiting.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        let permit = self.semaphore.try_acquire()?;
+        Some(self.guard(permit))
+    }
+
+    /// A mutable reference to the value, which needs no locking as the mutex is borrowed
+    /// mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// The value held by the mutex.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn guard<'a>(&'a self, permit: SemaphorePermit<'a>) -> MutexGuard<'a, T> {
+        MutexGuard {
+            // Only the holder of the permit borrows the value.
+            value: self.value.borrow_mut(),
+            _permit: permit,
+        }
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A lock on a [`Mutex`], which unlocks it when dropped.
+#[derive(Debug)]
+#[must_use = "the mutex is unlocked as soon as the guard is dropped"]
+pub struct MutexGuard<'a, T> {
+    // Declared first so that the value is released before the permit.
+    value: RefMut<'a, T>,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
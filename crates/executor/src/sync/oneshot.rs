@@ -0,0 +1,114 @@
+//! A channel for sending a single value from one future to another.
+//!
+//! ```ignore
+//! let (sender, receiver) = oneshot::channel();
+//! spawn_local(async move {
+//!     let body = read_body(request).await;
+//!     let _ = sender.send(body);
+//! });
+//! let body = receiver.await?;
+//! ```
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+/// Create a channel for sending a single value.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        value: None,
+        waker: None,
+        sender_dropped: false,
+        receiver_dropped: false,
+    }));
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+}
+
+/// The sending half of a [`channel`].
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Send `value` to the receiver, or return it if the receiver has been dropped.
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.receiver_dropped {
+            return Err(value);
+        }
+        shared.value = Some(value);
+        Ok(())
+    }
+
+    /// Whether the receiver has been dropped, so that sending would fail.
+    pub fn is_closed(&self) -> bool {
+        self.shared.borrow().receiver_dropped
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.sender_dropped = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving half of a [`channel`], which resolves to the value sent.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(value) = shared.value.take() {
+            Poll::Ready(Ok(value))
+        } else if shared.sender_dropped {
+            Poll::Ready(Err(RecvError(())))
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.borrow_mut().receiver_dropped = true;
+    }
+}
+
+/// The error returned by a [`Receiver`] when the sender was dropped without sending a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError(());
+
+impl std::fmt::Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("sender dropped without sending a value")
+    }
+}
+
+impl std::error::Error for RecvError {}
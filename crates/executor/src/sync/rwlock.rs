@@ -0,0 +1,130 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+
+use super::{Semaphore, SemaphorePermit};
+
+/// The most readers a lock may have at once.
+const MAX_READERS: usize = usize::MAX >> 3;
+
+/// An async reader-writer lock, allowing any number of readers or one writer at a time.
+///
+/// ```ignore
+/// let config = RwLock::new(load_config().await?);
+/// let timeout = config.read().await.timeout;
+/// config.write().await.timeout = Duration::from_secs(5);
+/// ```
+///
+/// Waiting writers do not hold back new readers, so a writer may wait as long as readers keep
+/// the lock held.
+#[derive(Debug)]
+pub struct RwLock<T> {
+    semaphore: Semaphore,
+    value: RefCell<T>,
+}
+
+impl<T> RwLock<T> {
+    /// Create a lock holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            semaphore: Semaphore::new(MAX_READERS),
+            value: RefCell::new(value),
+        }
+    }
+
+    /// Wait until there is no writer, then lock for reading.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        let permit = self.semaphore.acquire().await;
+        self.read_guard(permit)
+    }
+
+    /// Wait until there are no readers or writer, then lock for writing.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let permit = self.semaphore.acquire_many(MAX_READERS).await;
+        self.write_guard(permit)
+    }
+
+    /// Lock for reading if there is no writer, without waiting.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+        let permit = self.semaphore.try_acquire()?;
+        Some(self.read_guard(permit))
+    }
+
+    /// Lock for writing if there are no readers or writer, without waiting.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        let permit = self.semaphore.try_acquire_many(MAX_READERS)?;
+        Some(self.write_guard(permit))
+    }
+
+    /// A mutable reference to the value, which needs no locking as the lock is borrowed
+    /// mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// The value held by the lock.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn read_guard<'a>(&'a self, permit: SemaphorePermit<'a>) -> RwLockReadGuard<'a, T> {
+        RwLockReadGuard {
+            // No writer holds permits while a reader does.
+            value: self.value.borrow(),
+            _permit: permit,
+        }
+    }
+
+    fn write_guard<'a>(&'a self, permit: SemaphorePermit<'a>) -> RwLockWriteGuard<'a, T> {
+        RwLockWriteGuard {
+            // A writer holds every permit.
+            value: self.value.borrow_mut(),
+            _permit: permit,
+        }
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// A read lock on a [`RwLock`], which releases it when dropped.
+#[derive(Debug)]
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct RwLockReadGuard<'a, T> {
+    // Declared first so that the value is released before the permit.
+    value: Ref<'a, T>,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<T> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// A write lock on a [`RwLock`], which releases it when dropped.
+#[derive(Debug)]
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct RwLockWriteGuard<'a, T> {
+    // Declared first so that the value is released before the permit.
+    value: RefMut<'a, T>,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<T> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
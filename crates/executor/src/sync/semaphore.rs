@@ -0,0 +1,100 @@
+use std::cell::RefCell;
+use std::future::poll_fn;
+use std::task::{Poll, Waker};
+
+/// An async semaphore, limiting how many futures may hold a permit at once.
+///
+/// ```ignore
+/// let limit = Semaphore::new(4);
+/// let pages = futures::future::join_all(urls.iter().map(|url| async {
+///     let _permit = limit.acquire().await;
+///     fetch(url).await
+/// }))
+/// .await;
+/// ```
+///
+/// When permits are released, every waiting future is woken to try again, so permits are not
+/// handed out in any particular order.
+#[derive(Debug)]
+pub struct Semaphore {
+    state: RefCell<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    permits: usize,
+    waiters: Vec<Waker>,
+}
+
+impl Semaphore {
+    /// Create a semaphore with `permits` permits.
+    pub const fn new(permits: usize) -> Self {
+        Self {
+            state: RefCell::new(State {
+                permits,
+                waiters: Vec::new(),
+            }),
+        }
+    }
+
+    /// The number of permits not currently held.
+    pub fn available_permits(&self) -> usize {
+        self.state.borrow().permits
+    }
+
+    /// Add `n` permits.
+    pub fn add_permits(&self, n: usize) {
+        let mut state = self.state.borrow_mut();
+        state.permits += n;
+        state.waiters.drain(..).for_each(Waker::wake);
+    }
+
+    /// Wait for a permit.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.acquire_many(1).await
+    }
+
+    /// Wait for `n` permits, taking them all at once.
+    pub async fn acquire_many(&self, n: usize) -> SemaphorePermit<'_> {
+        poll_fn(|cx| match self.try_acquire_many(n) {
+            Some(permit) => Poll::Ready(permit),
+            None => {
+                self.state.borrow_mut().waiters.push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Take a permit if one is available, without waiting.
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire_many(1)
+    }
+
+    /// Take `n` permits if they are available, without waiting.
+    pub fn try_acquire_many(&self, n: usize) -> Option<SemaphorePermit<'_>> {
+        let mut state = self.state.borrow_mut();
+        if state.permits < n {
+            return None;
+        }
+        state.permits -= n;
+        Some(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+}
+
+/// Permits taken from a [`Semaphore`], which are returned to it when dropped.
+#[derive(Debug)]
+#[must_use = "the permits are returned as soon as they are dropped"]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(self.permits);
+    }
+}
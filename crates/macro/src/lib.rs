@@ -1,15 +1,36 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
+mod pg_enum;
+mod sqlite_query;
+
 const WIT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/wit");
 
 /// Generates the entrypoint to a Spin Redis component written in Rust.
+///
+/// The handler takes the message payload as `bytes::Bytes`, or as any type implementing
+/// `TryFrom<Vec<u8>>` such as `spin_sdk::redis::Json<T>`. It returns an `anyhow::Result<()>`,
+/// or anything else implementing `spin_sdk::redis::IntoOutcome` such as
+/// `anyhow::Result<spin_sdk::redis::Outcome>`.
+///
+/// The `inbound-redis` interface passes only the payload, not the channel it was published
+/// on. A component which needs to tell channels apart can be declared as a separate component
+/// for each channel in `spin.toml`.
+///
+/// A payload which cannot be converted to the handler's parameter type fails the message by
+/// default. With `#[redis_component(on_malformed = "skip")]` it is logged and acknowledged
+/// instead, without calling the handler.
 #[proc_macro_attribute]
-pub fn redis_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn redis_component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
     let func = syn::parse_macro_input!(item as syn::ItemFn);
     let func_name = &func.sig.ident;
     let await_postfix = func.sig.asyncness.map(|_| quote!(.await));
     let preamble = preamble(Export::Redis);
+    let on_malformed = match on_malformed(&args) {
+        Ok(on_malformed) => on_malformed,
+        Err(e) => return e.into_compile_error().into(),
+    };
 
     quote!(
         #func
@@ -20,11 +41,94 @@ pub fn redis_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
             impl self::preamble::exports::fermyon::spin::inbound_redis::Guest for preamble::Spin {
                 fn handle_message(msg: self::preamble::exports::fermyon::spin::inbound_redis::Payload) -> Result<(), self::preamble::fermyon::spin::redis_types::Error> {
                     ::spin_sdk::http::run(async move {
-                        match super::#func_name(msg.try_into().expect("cannot convert from Spin Redis payload"))#await_postfix {
+                        let msg = match ::std::convert::TryInto::try_into(msg) {
+                            Ok(msg) => msg,
+                            Err(e) => {
+                                eprintln!("malformed Redis payload: {}", e);
+                                return #on_malformed;
+                            }
+                        };
+                        if ::spin_sdk::redis::__private::acknowledge(super::#func_name(msg)#await_postfix) {
+                            Ok(())
+                        } else {
+                            Err(self::preamble::fermyon::spin::redis_types::Error::Error)
+                        }
+                    })
+                }
+            }
+        }
+    )
+        .into()
+}
+
+/// The result returned for a payload which cannot be converted, from the `on_malformed`
+/// argument of `redis_component`.
+fn on_malformed(args: &[syn::NestedMeta]) -> syn::Result<proc_macro2::TokenStream> {
+    let mut result = quote!(Err(
+        self::preamble::fermyon::spin::redis_types::Error::Error
+    ));
+    for arg in args {
+        match arg {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(value),
+                ..
+            })) if path.is_ident("on_malformed") => match value.value().as_str() {
+                "error" => {}
+                "skip" => result = quote!(Ok(())),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected `\"error\"` or `\"skip\"`",
+                    ))
+                }
+            },
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "expected `on_malformed = \"...\"`",
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Generates the entrypoint to a Spin MQTT component written in Rust.
+///
+/// The handler takes a `spin_sdk::mqtt::Message`, which carries the topic and payload of the
+/// received message, and returns an `anyhow::Result<()>`.
+///
+/// ```ignore
+/// #[mqtt_component]
+/// fn on_message(message: mqtt::Message) -> anyhow::Result<()> {
+///     println!("{}: {:?}", message.topic(), message.payload());
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn mqtt_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let func = syn::parse_macro_input!(item as syn::ItemFn);
+    let func_name = &func.sig.ident;
+    let await_postfix = func.sig.asyncness.map(|_| quote!(.await));
+    let preamble = preamble(Export::Mqtt);
+
+    quote!(
+        #func
+        mod __spin_mqtt {
+            mod preamble {
+                #preamble
+            }
+            use self::preamble::spin::mqtt_trigger::spin_mqtt_types::{Error, Metadata, Payload};
+            impl self::preamble::Guest for preamble::Spin {
+                fn handle_message(payload: Payload, metadata: Metadata) -> Result<(), Error> {
+                    let message = ::spin_sdk::mqtt::Message::new(metadata.topic, payload);
+                    ::spin_sdk::http::run(async move {
+                        match super::#func_name(message)#await_postfix {
                             Ok(()) => Ok(()),
                             Err(e) => {
                                 eprintln!("{}", e);
-                                Err(self::preamble::fermyon::spin::redis_types::Error::Error)
+                                Err(Error::Error)
                             },
                         }
                     })
@@ -32,7 +136,7 @@ pub fn redis_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
             }
         }
     )
-        .into()
+    .into()
 }
 
 /// The entrypoint to a WASI HTTP component written in Rust.
@@ -85,10 +189,30 @@ pub fn redis_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   // Your logic goes here
 /// }
 /// ```
+///
+/// ### Tracing
+///
+/// With `#[http_component(trace)]`, the handler runs within a `spin_sdk::observe::span` named after the function,
+/// which is written to standard error when the handler returns. Errors the handler returns are recorded on the span,
+/// and a response with a 5xx status marks it as failed. The request's `baggage` headers are available through
+/// `spin_sdk::observe::baggage` either way.
 #[proc_macro_attribute]
-pub fn http_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn http_component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
     let func = syn::parse_macro_input!(item as syn::ItemFn);
     let func_name = &func.sig.ident;
+    let trace = match trace(&args) {
+        Ok(trace) => trace,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let (open_span, record_status) = if trace {
+        (
+            quote!(let _span = ::spin_sdk::observe::span(::std::stringify!(#func_name));),
+            quote!(::spin_sdk::observe::__private::record_response_status(*response.status());),
+        )
+    } else {
+        (quote!(), quote!())
+    };
     let preamble = preamble(Export::WasiHttp);
     let is_native_wasi_http_handler = func.sig.inputs.len() == 2;
     let await_postfix = func.sig.asyncness.map(|_| quote!(.await));
@@ -108,7 +232,9 @@ pub fn http_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 fn handle(request: self::preamble::wasi::http::types::IncomingRequest, response_out: self::preamble::wasi::http::types::ResponseOutparam) {
                     let request: ::spin_sdk::http::IncomingRequest = ::std::convert::Into::into(request);
                     let response_out: ::spin_sdk::http::ResponseOutparam = ::std::convert::Into::into(response_out);
+                    ::spin_sdk::observe::__private::extract_baggage(&request);
                     ::spin_sdk::http::run(async move {
+                        #open_span
                         match ::spin_sdk::http::conversions::TryFromIncomingRequest::try_from_incoming_request(request).await {
                             ::std::result::Result::Ok(req) => #handler,
                             ::std::result::Result::Err(e) => handle_response(response_out, e).await,
@@ -119,6 +245,7 @@ pub fn http_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
             async fn handle_response<R: ::spin_sdk::http::IntoResponse>(response_out: ::spin_sdk::http::ResponseOutparam, resp: R) {
                 let mut response = ::spin_sdk::http::IntoResponse::into_response(resp);
+                #record_status
                 let body = ::std::mem::take(response.body_mut());
                 match ::std::convert::TryInto::try_into(response) {
                     ::std::result::Result::Ok(response) => {
@@ -155,20 +282,35 @@ pub fn http_component(_attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Whether `http_component` should trace the handler, from its `trace` argument.
+fn trace(args: &[syn::NestedMeta]) -> syn::Result<bool> {
+    let mut trace = false;
+    for arg in args {
+        match arg {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("trace") => trace = true,
+            other => return Err(syn::Error::new_spanned(other, "expected `trace`")),
+        }
+    }
+    Ok(trace)
+}
+
 #[derive(Copy, Clone)]
 enum Export {
     WasiHttp,
     Redis,
+    Mqtt,
 }
 
 fn preamble(export: Export) -> proc_macro2::TokenStream {
     let export_decl = match export {
         Export::WasiHttp => quote!("wasi:http/incoming-handler": Spin),
         Export::Redis => quote!("fermyon:spin/inbound-redis": Spin),
+        Export::Mqtt => quote!(world: Spin),
     };
     let world = match export {
         Export::WasiHttp => quote!("wasi-http-trigger"),
         Export::Redis => quote!("redis-trigger"),
+        Export::Mqtt => quote!("mqtt-trigger"),
     };
     quote! {
         #![allow(missing_docs)]
@@ -183,3 +325,200 @@ fn preamble(export: Export) -> proc_macro2::TokenStream {
         pub struct Spin;
     }
 }
+
+/// Derives `spin_sdk::rows::FromRow`, decoding a struct from a database row by column name.
+///
+/// The same derive works for rows from `spin_sdk::sqlite`, `spin_sdk::pg3` and
+/// `spin_sdk::mysql`. Fields can be customized with the `row` attribute:
+///
+/// * `#[row(rename = "column")]` reads the field from a differently named column.
+/// * `#[row(default)]` uses `Default::default()` when the column is missing or `NULL`.
+/// * `#[row(optional)]` allows the column to be missing from the result; the field must be
+///   an `Option`.
+///
+/// ```ignore
+/// use spin_sdk::rows::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i64,
+///     #[row(rename = "display_name")]
+///     name: String,
+///     #[row(optional)]
+///     email: Option<String>,
+/// }
+/// ```
+#[proc_macro_derive(FromRow, attributes(row))]
+pub fn derive_from_row(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+    from_row(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn from_row(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`FromRow` can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut inits = Vec::new();
+    let mut bounds = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let ty = &field.ty;
+        let mut column = ident.to_string().trim_start_matches("r#").to_owned();
+        let mut decode = quote!(required);
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("row")) {
+            let syn::Meta::List(list) = attr.parse_meta()? else {
+                return Err(syn::Error::new_spanned(attr, "expected `#[row(...)]`"));
+            };
+            for nested in list.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(name),
+                        ..
+                    })) if path.is_ident("rename") => column = name.value(),
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("default") => {
+                        decode = quote!(or_default);
+                        bounds.push(quote!(#ty: ::std::default::Default));
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("optional") => {
+                        decode = quote!(optional)
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `rename = \"...\"`, `default` or `optional`",
+                        ))
+                    }
+                }
+            }
+        }
+        inits.push(quote!(#ident: ::spin_sdk::rows::__private::#decode(row, #column)?));
+        bounds.push(quote!(#ty: ::spin_sdk::rows::DecodeValue<__R::Value>));
+    }
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics
+        .params
+        .push(syn::parse_quote!(__R: ::spin_sdk::rows::Row + ?::std::marker::Sized));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let predicates = where_clause.map(|w| &w.predicates);
+
+    Ok(quote! {
+        impl #impl_generics ::spin_sdk::rows::FromRow<__R> for #name #ty_generics
+        where
+            #(#bounds,)*
+            #predicates
+        {
+            fn from_row(row: &__R) -> ::std::result::Result<Self, ::spin_sdk::rows::FromRowError> {
+                ::std::result::Result::Ok(Self {
+                    #(#inits,)*
+                })
+            }
+        }
+    })
+}
+
+/// Derives `spin_sdk::pg3::PgEnum` for an enum of unit variants, along with
+/// `spin_sdk::pg3::Decode` and a conversion into `spin_sdk::pg3::ParameterValue`, so the enum
+/// can be read from and written to Postgres enum or text columns.
+///
+/// Each variant is stored as its name unless customized with the `pg_enum` attribute:
+///
+/// * `#[pg_enum(rename_all = "...")]` on the enum renames every variant, using one of
+///   `lowercase`, `UPPERCASE`, `snake_case`, `SCREAMING_SNAKE_CASE` or `kebab-case`.
+/// * `#[pg_enum(rename = "...")]` on a variant sets its label.
+///
+/// ```ignore
+/// use spin_sdk::pg3::PgEnum;
+///
+/// #[derive(PgEnum)]
+/// #[pg_enum(rename_all = "snake_case")]
+/// enum Status {
+///     Draft,
+///     InReview,
+///     #[pg_enum(rename = "live")]
+///     Published,
+/// }
+/// ```
+#[proc_macro_derive(PgEnum, attributes(pg_enum))]
+pub fn derive_pg_enum(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+    pg_enum::pg_enum(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Embeds a directory of SQL migration scripts as a `&[spin_sdk::sqlite::migrations::Migration]`.
+///
+/// The path is relative to the crate's `Cargo.toml`. Every `.sql` file in the directory becomes
+/// a migration named after the file (without the extension), ordered by file name, so names
+/// like `0001_create_users.sql` apply in the intended order.
+///
+/// ```ignore
+/// const MIGRATIONS: &[Migration] = spin_sdk::sqlite::migrations::include_migrations!("migrations");
+/// ```
+#[proc_macro]
+pub fn include_migrations(input: TokenStream) -> TokenStream {
+    let dir = syn::parse_macro_input!(input as syn::LitStr);
+    include_migrations_from(&dir)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn include_migrations_from(dir: &syn::LitStr) -> syn::Result<proc_macro2::TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|e| syn::Error::new(dir.span(), format!("CARGO_MANIFEST_DIR is not set: {e}")))?;
+    let path = std::path::Path::new(&manifest_dir).join(dir.value());
+    let entries = std::fs::read_dir(&path)
+        .map_err(|e| syn::Error::new(dir.span(), format!("failed to read {path:?}: {e}")))?;
+
+    let mut scripts = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| syn::Error::new(dir.span(), e.to_string()))?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "sql") {
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| syn::Error::new(dir.span(), format!("invalid file name {path:?}")))?
+                .to_owned();
+            let path = path
+                .to_str()
+                .ok_or_else(|| syn::Error::new(dir.span(), format!("invalid path {path:?}")))?
+                .to_owned();
+            scripts.push((name, path));
+        }
+    }
+    scripts.sort();
+
+    let migrations = scripts.iter().map(|(name, path)| {
+        quote!(::spin_sdk::sqlite::migrations::Migration::new(#name, ::std::include_str!(#path)))
+    });
+    Ok(quote!(&[#(#migrations),*]))
+}
+
+/// A SQLite query whose parameters and result columns are checked at compile time.
+///
+/// See `spin_sdk::sqlite::query!` for documentation.
+#[proc_macro]
+pub fn sqlite_query(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as sqlite_query::Input);
+    sqlite_query::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,183 @@
+//! The `PgEnum` derive.
+
+use quote::quote;
+
+pub(crate) fn pg_enum(input: syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let syn::Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`PgEnum` can only be derived for enums",
+        ));
+    };
+
+    let mut rename_all = None;
+    for (name, value) in attr_values(&input.attrs)? {
+        if name.is_ident("rename_all") {
+            rename_all = Some(RenameRule::parse(&value)?);
+        } else {
+            return Err(syn::Error::new_spanned(
+                name,
+                "expected `rename_all = \"...\"`",
+            ));
+        }
+    }
+
+    let mut variants = Vec::new();
+    let mut labels = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "`PgEnum` variants cannot have fields",
+            ));
+        }
+        let ident = &variant.ident;
+        let mut label = match rename_all {
+            Some(rule) => rule.apply(&ident.to_string()),
+            None => ident.to_string(),
+        };
+        for (name, value) in attr_values(&variant.attrs)? {
+            if name.is_ident("rename") {
+                label = value.value();
+            } else {
+                return Err(syn::Error::new_spanned(name, "expected `rename = \"...\"`"));
+            }
+        }
+        if labels.contains(&label) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("duplicate label `{label}`"),
+            ));
+        }
+        variants.push(ident);
+        labels.push(label);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::spin_sdk::pg3::PgEnum for #name #ty_generics #where_clause {
+            const LABELS: &'static [&'static str] = &[#(#labels),*];
+
+            fn as_label(&self) -> &'static str {
+                match self {
+                    #(Self::#variants => #labels,)*
+                }
+            }
+
+            fn from_label(label: &str) -> ::std::option::Option<Self> {
+                match label {
+                    #(#labels => ::std::option::Option::Some(Self::#variants),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+
+        impl #impl_generics ::spin_sdk::pg3::Decode for #name #ty_generics #where_clause {
+            fn decode(
+                value: &::spin_sdk::pg3::DbValue,
+            ) -> ::std::result::Result<Self, ::spin_sdk::pg3::Error> {
+                ::spin_sdk::pg3::__private::decode_enum(value)
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics>
+            for ::spin_sdk::pg3::ParameterValue #where_clause
+        {
+            fn from(value: #name #ty_generics) -> Self {
+                ::spin_sdk::pg3::ParameterValue::Str(
+                    ::std::string::ToString::to_string(
+                        ::spin_sdk::pg3::PgEnum::as_label(&value),
+                    ),
+                )
+            }
+        }
+    })
+}
+
+/// The `name = "value"` pairs of every `#[pg_enum(...)]` attribute.
+fn attr_values(attrs: &[syn::Attribute]) -> syn::Result<Vec<(syn::Path, syn::LitStr)>> {
+    let mut values = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("pg_enum")) {
+        let syn::Meta::List(list) = attr.parse_meta()? else {
+            return Err(syn::Error::new_spanned(attr, "expected `#[pg_enum(...)]`"));
+        };
+        for nested in list.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(value),
+                    ..
+                })) => values.push((path, value)),
+                other => return Err(syn::Error::new_spanned(other, "expected `name = \"...\"`")),
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// How variant names are turned into labels, named as in serde's `rename_all`.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl RenameRule {
+    fn parse(value: &syn::LitStr) -> syn::Result<Self> {
+        Ok(match value.value().as_str() {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    value,
+                    "expected one of `lowercase`, `UPPERCASE`, `snake_case`, \
+                     `SCREAMING_SNAKE_CASE` or `kebab-case`",
+                ))
+            }
+        })
+    }
+
+    fn apply(self, name: &str) -> String {
+        let separated = |separator: char| {
+            let mut label = String::new();
+            for (i, c) in name.char_indices() {
+                if i > 0 && c.is_uppercase() {
+                    label.push(separator);
+                }
+                label.push(c);
+            }
+            label
+        };
+        match self {
+            Self::Lower => name.to_lowercase(),
+            Self::Upper => name.to_uppercase(),
+            Self::Snake => separated('_').to_lowercase(),
+            Self::ScreamingSnake => separated('_').to_uppercase(),
+            Self::Kebab => separated('-').to_lowercase(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renames() {
+        assert_eq!(RenameRule::Snake.apply("InProgress"), "in_progress");
+        assert_eq!(
+            RenameRule::ScreamingSnake.apply("InProgress"),
+            "IN_PROGRESS"
+        );
+        assert_eq!(RenameRule::Kebab.apply("InProgress"), "in-progress");
+        assert_eq!(RenameRule::Lower.apply("InProgress"), "inprogress");
+        assert_eq!(RenameRule::Upper.apply("Done"), "DONE");
+    }
+}
@@ -0,0 +1,1443 @@
+//! The implementation of `spin_sdk::sqlite::query!`.
+//!
+//! This is deliberately not a full SQL parser: it understands enough of SQLite's grammar to
+//! count the parameters of a statement, to read the tables of a schema, and to resolve the
+//! result columns of a `SELECT` or a `RETURNING` clause against it. Anything it cannot type
+//! can be annotated.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+/// The arguments of `query!`: the SQL literal followed by the parameters.
+pub struct Input {
+    sql: syn::LitStr,
+    args: Vec<syn::Expr>,
+}
+
+impl syn::parse::Parse for Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let sql = input.parse()?;
+        let mut args = Vec::new();
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            args.push(input.parse()?);
+        }
+        Ok(Self { sql, args })
+    }
+}
+
+pub fn expand(input: Input) -> syn::Result<TokenStream> {
+    let span = input.sql.span();
+    let sql = input.sql.value();
+    let error = |message: String| syn::Error::new(span, message);
+
+    let tokens = tokenize(&sql).map_err(error)?;
+    let expected = parameter_count(&tokens);
+    if expected != input.args.len() {
+        return Err(error(format!(
+            "the statement has {expected} parameter(s) but {} argument(s) were supplied",
+            input.args.len()
+        )));
+    }
+
+    let (schema, files) = load_schema().map_err(error)?;
+    let columns = result_columns(&tokens, &schema).map_err(error)?;
+
+    let files = files.iter().map(|f| f.to_string_lossy().into_owned());
+    let args = &input.args;
+    let row = if columns.is_empty() {
+        quote!(())
+    } else {
+        quote!(QueryRow)
+    };
+    let row_struct = (!columns.is_empty()).then(|| {
+        let fields = columns.iter().map(|c| {
+            let field = format_ident!("{}", c.field);
+            let name = &c.name;
+            let ty = &c.ty;
+            quote!(#[row(rename = #name)] #field: #ty)
+        });
+        quote! {
+            #[derive(::std::fmt::Debug, ::std::clone::Clone, ::spin_sdk::rows::FromRow)]
+            struct QueryRow {
+                #(#fields,)*
+            }
+        }
+    });
+
+    Ok(quote! {{
+        #(const _: &str = ::std::include_str!(#files);)*
+        #row_struct
+        ::spin_sdk::sqlite::Query::<#row>::__new(
+            #sql,
+            ::std::vec![#(::spin_sdk::sqlite::Value::from(#args)),*],
+        )
+    }})
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// An unquoted identifier or keyword.
+    Word(String),
+    /// A quoted identifier.
+    Quoted(String),
+    /// A string literal.
+    Str,
+    /// A numeric or blob literal.
+    Literal,
+    /// A parameter: `?`, `?NNN`, or a named parameter such as `:name`.
+    Param(Param),
+    /// A `--` comment, with the line it starts on.
+    Comment(String, usize),
+    Punct(char),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Param {
+    Next,
+    Numbered(usize),
+    Named(String),
+}
+
+impl Token {
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn ident(&self) -> Option<&str> {
+        match self {
+            Token::Word(w) | Token::Quoted(w) => Some(w),
+            _ => None,
+        }
+    }
+}
+
+/// A token and the line it starts on.
+type Spanned = (Token, usize);
+
+fn tokenize(sql: &str) -> Result<Vec<Spanned>, String> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    let take_while = |i: &mut usize, f: &dyn Fn(char) -> bool| {
+        let start = *i;
+        while *i < chars.len() && f(chars[*i]) {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>()
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        let start_line = line;
+        let token = match c {
+            '\n' => {
+                line += 1;
+                i += 1;
+                continue;
+            }
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                let text = take_while(&mut i, &|c| c != '\n');
+                Token::Comment(text[2..].trim().to_owned(), start_line)
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                i += 1;
+                let mut text = String::new();
+                loop {
+                    match chars.get(i) {
+                        None => return Err(format!("unterminated {c} in SQL")),
+                        Some(&ch) if ch == close => {
+                            if close != ']' && chars.get(i + 1) == Some(&close) {
+                                text.push(close);
+                                i += 2;
+                            } else {
+                                i += 1;
+                                break;
+                            }
+                        }
+                        Some(&ch) => {
+                            if ch == '\n' {
+                                line += 1;
+                            }
+                            text.push(ch);
+                            i += 1;
+                        }
+                    }
+                }
+                if c == '\'' {
+                    Token::Str
+                } else {
+                    Token::Quoted(text)
+                }
+            }
+            '?' => {
+                i += 1;
+                let digits = take_while(&mut i, &|c| c.is_ascii_digit());
+                if digits.is_empty() {
+                    Token::Param(Param::Next)
+                } else {
+                    Token::Param(Param::Numbered(
+                        digits
+                            .parse()
+                            .map_err(|_| format!("invalid parameter ?{digits}"))?,
+                    ))
+                }
+            }
+            ':' | '@' | '$' => {
+                i += 1;
+                let name = take_while(&mut i, &|c| c.is_alphanumeric() || c == '_');
+                if name.is_empty() {
+                    Token::Punct(c)
+                } else {
+                    // SQLite tells `:a` and `@a` apart, so the prefix is part of the name.
+                    Token::Param(Param::Named(format!("{c}{name}")))
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let word = take_while(&mut i, &|c| c.is_alphanumeric() || c == '_' || c == '$');
+                if chars.get(i) == Some(&'\'') && word.eq_ignore_ascii_case("x") {
+                    // A blob literal such as x'00ff'.
+                    i += 1;
+                    take_while(&mut i, &|c| c != '\'');
+                    i += 1;
+                    Token::Literal
+                } else {
+                    Token::Word(word)
+                }
+            }
+            c if c.is_ascii_digit() => {
+                take_while(&mut i, &|c| c.is_ascii_alphanumeric() || c == '.');
+                Token::Literal
+            }
+            c => {
+                i += 1;
+                Token::Punct(c)
+            }
+        };
+        tokens.push((token, start_line));
+    }
+    Ok(tokens)
+}
+
+/// The number of parameters, computed the way `sqlite3_bind_parameter_count` does.
+fn parameter_count(tokens: &[Spanned]) -> usize {
+    let mut count = 0;
+    let mut named = std::collections::BTreeSet::new();
+    for (token, _) in tokens {
+        match token {
+            Token::Param(Param::Next) => count += 1,
+            Token::Param(Param::Numbered(n)) => count = count.max(*n),
+            Token::Param(Param::Named(name)) if !named.contains(name) => {
+                named.insert(name.clone());
+                count += 1;
+            }
+            _ => {}
+        }
+    }
+    count
+}
+
+/// A column of a table in the schema.
+#[derive(Clone, Debug, PartialEq)]
+struct Column {
+    name: String,
+    /// The Rust type of non-null values, if it is known.
+    ty: Option<String>,
+    nullable: bool,
+}
+
+/// Tables by lower-cased name.
+type Schema = BTreeMap<String, Vec<Column>>;
+
+/// Read the schema from `schema.sql`, or failing that from the `.sql` files in `migrations`,
+/// relative to the crate being compiled. Returns the schema and the files it was read from.
+fn load_schema() -> Result<(Schema, Vec<PathBuf>), String> {
+    let manifest_dir = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR").map_err(|e| format!("CARGO_MANIFEST_DIR: {e}"))?,
+    );
+    let schema_file = manifest_dir.join("schema.sql");
+    let files = if schema_file.exists() {
+        vec![schema_file]
+    } else {
+        sql_files(&manifest_dir.join("migrations"))
+    };
+    if files.is_empty() {
+        return Err(
+            "no schema found: add a `schema.sql` or a `migrations` directory next to Cargo.toml"
+                .to_owned(),
+        );
+    }
+    let mut schema = Schema::new();
+    for file in &files {
+        let sql = std::fs::read_to_string(file)
+            .map_err(|e| format!("failed to read {}: {e}", file.display()))?;
+        apply_schema(&mut schema, &sql)
+            .map_err(|e| format!("failed to read schema from {}: {e}", file.display()))?;
+    }
+    Ok((schema, files))
+}
+
+fn sql_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Update `schema` with the effect of the statements in `sql`.
+fn apply_schema(schema: &mut Schema, sql: &str) -> Result<(), String> {
+    let tokens = tokenize(sql)?;
+    for statement in statements(&tokens) {
+        let words = statement
+            .iter()
+            .filter(|(t, _)| !matches!(t, Token::Comment(..)))
+            .map(|(t, _)| t)
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        let accept = |word: &str, i: &mut usize| {
+            let found = words.get(*i).is_some_and(|t| t.is_word(word));
+            if found {
+                *i += 1;
+            }
+            found
+        };
+        if accept("CREATE", &mut i) {
+            let _ = accept("TEMP", &mut i) || accept("TEMPORARY", &mut i);
+            if !accept("TABLE", &mut i) {
+                continue;
+            }
+            if accept("IF", &mut i) {
+                accept("NOT", &mut i);
+                accept("EXISTS", &mut i);
+            }
+            let name = table_name(&words, &mut i)?;
+            if words.get(i) != Some(&&Token::Punct('(')) {
+                // `CREATE TABLE ... AS SELECT` has no declared columns.
+                continue;
+            }
+            let columns = column_defs(statement)?;
+            schema.entry(name.to_lowercase()).or_insert(columns);
+        } else if accept("ALTER", &mut i) && accept("TABLE", &mut i) {
+            let name = table_name(&words, &mut i)?.to_lowercase();
+            if !schema.contains_key(&name) {
+                return Err(format!("ALTER TABLE of unknown table `{name}`"));
+            }
+            if accept("RENAME", &mut i) && accept("TO", &mut i) {
+                let new_name = table_name(&words, &mut i)?.to_lowercase();
+                let columns = schema.remove(&name).expect("table exists");
+                schema.insert(new_name, columns);
+                continue;
+            }
+            let table = schema.get_mut(&name).expect("table exists");
+            if accept("ADD", &mut i) {
+                let start = statement
+                    .iter()
+                    .position(|(t, _)| t.is_word("ADD"))
+                    .expect("ADD was accepted");
+                let mut rest = &statement[start + 1..];
+                if rest.first().is_some_and(|(t, _)| t.is_word("COLUMN")) {
+                    rest = &rest[1..];
+                }
+                table.extend(column_def(rest, &annotations(rest))?);
+            } else if words.get(i - 1).is_some_and(|t| t.is_word("RENAME")) {
+                accept("COLUMN", &mut i);
+                let from = ident(&words, &mut i)?;
+                accept("TO", &mut i);
+                let to = ident(&words, &mut i)?;
+                if let Some(column) = table
+                    .iter_mut()
+                    .find(|c| c.name.eq_ignore_ascii_case(&from))
+                {
+                    column.name = to;
+                }
+            } else if accept("DROP", &mut i) {
+                accept("COLUMN", &mut i);
+                let column = ident(&words, &mut i)?;
+                table.retain(|c| !c.name.eq_ignore_ascii_case(&column));
+            }
+        } else if accept("DROP", &mut i) && accept("TABLE", &mut i) {
+            if accept("IF", &mut i) {
+                accept("EXISTS", &mut i);
+            }
+            let name = table_name(&words, &mut i)?;
+            schema.remove(&name.to_lowercase());
+        }
+    }
+    Ok(())
+}
+
+/// Split tokens into statements, keeping the bodies of triggers together.
+fn statements(tokens: &[Spanned]) -> Vec<&[Spanned]> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    for (i, (token, _)) in tokens.iter().enumerate() {
+        let words = tokens[start..i]
+            .iter()
+            .filter(|(t, _)| matches!(t, Token::Word(_)))
+            .take(3)
+            .map(|(t, _)| t)
+            .collect::<Vec<_>>();
+        let is_trigger = words.first().is_some_and(|t| t.is_word("CREATE"))
+            && words.iter().any(|t| t.is_word("TRIGGER"));
+        match token {
+            Token::Word(_) if is_trigger && (token.is_word("BEGIN") || token.is_word("CASE")) => {
+                depth += 1
+            }
+            Token::Word(_) if is_trigger && token.is_word("END") => depth = depth.saturating_sub(1),
+            Token::Punct(';') if depth == 0 => {
+                statements.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&tokens[start..]);
+    statements.retain(|s| s.iter().any(|(t, _)| !matches!(t, Token::Comment(..))));
+    statements
+}
+
+fn ident(words: &[&Token], i: &mut usize) -> Result<String, String> {
+    let name = words
+        .get(*i)
+        .and_then(|t| t.ident())
+        .ok_or_else(|| "expected a name".to_owned())?;
+    *i += 1;
+    Ok(name.to_owned())
+}
+
+/// Parse a possibly schema-qualified table name, returning the unqualified name.
+fn table_name(words: &[&Token], i: &mut usize) -> Result<String, String> {
+    let mut name = ident(words, i)?;
+    if words.get(*i) == Some(&&Token::Punct('.')) {
+        *i += 1;
+        name = ident(words, i)?;
+    }
+    Ok(name)
+}
+
+/// The columns declared in the parenthesized list of a `CREATE TABLE` statement.
+fn column_defs(statement: &[Spanned]) -> Result<Vec<Column>, String> {
+    let open = statement
+        .iter()
+        .position(|(t, _)| *t == Token::Punct('('))
+        .expect("caller checked for a column list");
+    let mut depth = 0;
+    let mut defs = Vec::new();
+    let mut start = open + 1;
+    for (i, (token, _)) in statement.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    defs.push(&statement[start..i]);
+                    break;
+                }
+            }
+            Token::Punct(',') if depth == 1 => {
+                defs.push(&statement[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let annotations = annotations(&statement[open..]);
+    let mut columns = Vec::new();
+    for def in defs {
+        columns.extend(column_def(def, &annotations)?);
+    }
+    Ok(columns)
+}
+
+/// `-- type: ...` annotations by the line they apply to.
+///
+/// An annotation applies to the column defined on the same line, or on the next line with a
+/// column definition if it is on a line of its own.
+fn annotations(tokens: &[Spanned]) -> BTreeMap<usize, String> {
+    let mut annotations = BTreeMap::new();
+    for (i, (token, line)) in tokens.iter().enumerate() {
+        let Token::Comment(text, _) = token else {
+            continue;
+        };
+        let Some(ty) = text.strip_prefix("type:") else {
+            continue;
+        };
+        let shares_line = tokens
+            .iter()
+            .any(|(t, l)| l == line && !matches!(t, Token::Comment(..)));
+        let line = if shares_line {
+            *line
+        } else {
+            match tokens[i..]
+                .iter()
+                .find(|(t, _)| !matches!(t, Token::Comment(..)))
+            {
+                Some((_, l)) => *l,
+                None => continue,
+            }
+        };
+        annotations.insert(line, ty.trim().to_owned());
+    }
+    annotations
+}
+
+/// Parse a column definition, or return `None` for a table constraint.
+fn column_def(
+    def: &[Spanned],
+    annotations: &BTreeMap<usize, String>,
+) -> Result<Option<Column>, String> {
+    let tokens = def
+        .iter()
+        .filter(|(t, _)| !matches!(t, Token::Comment(..)))
+        .collect::<Vec<_>>();
+    let Some(((first, _), rest)) = tokens.split_first() else {
+        return Ok(None);
+    };
+    const TABLE_CONSTRAINTS: [&str; 5] = ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+    if TABLE_CONSTRAINTS.iter().any(|w| first.is_word(w)) {
+        return Ok(None);
+    }
+    let name = first
+        .ident()
+        .ok_or_else(|| format!("expected a column name, found {first:?}"))?
+        .to_owned();
+
+    const CONSTRAINTS: [&str; 11] = [
+        "CONSTRAINT",
+        "PRIMARY",
+        "NOT",
+        "NULL",
+        "UNIQUE",
+        "CHECK",
+        "DEFAULT",
+        "COLLATE",
+        "REFERENCES",
+        "GENERATED",
+        "AS",
+    ];
+    let declared_type = rest
+        .iter()
+        .take_while(|(t, _)| !CONSTRAINTS.iter().any(|w| t.is_word(w)))
+        .filter_map(|(t, _)| match t {
+            Token::Word(w) => Some(w.to_uppercase()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let has = |a: &str, b: &str| {
+        rest.windows(2)
+            .any(|w| w[0].0.is_word(a) && w[1].0.is_word(b))
+    };
+    let rowid_alias = declared_type == "INTEGER" && has("PRIMARY", "KEY");
+    let annotation = tokens.iter().find_map(|(_, line)| annotations.get(line));
+
+    Ok(Some(Column {
+        name,
+        ty: annotation
+            .cloned()
+            .or_else(|| affinity_type(&declared_type).map(ToOwned::to_owned)),
+        nullable: !(has("NOT", "NULL") || rowid_alias),
+    }))
+}
+
+/// The Rust type for a declared column type, following SQLite's type affinity rules.
+fn affinity_type(declared: &str) -> Option<&'static str> {
+    let has = |s: &str| declared.contains(s);
+    Some(if has("INT") {
+        "i64"
+    } else if has("CHAR") || has("CLOB") || has("TEXT") {
+        "::std::string::String"
+    } else if has("BLOB") {
+        "::std::vec::Vec<u8>"
+    } else if has("REAL") || has("FLOA") || has("DOUB") {
+        "f64"
+    } else if has("BOOL") {
+        "bool"
+    } else if has("DATETIME") || has("TIMESTAMP") {
+        "::chrono::NaiveDateTime"
+    } else if has("DATE") {
+        "::chrono::NaiveDate"
+    } else {
+        return None;
+    })
+}
+
+/// A column of a query's result.
+struct ResultColumn {
+    /// The name SQLite reports for the column.
+    name: String,
+    /// The name of the field in the generated row struct.
+    field: String,
+    ty: syn::Type,
+}
+
+/// A table referenced by a query.
+struct TableRef<'a> {
+    name: String,
+    alias: Option<String>,
+    columns: Option<&'a [Column]>,
+    /// Whether an outer join can make the table's columns `NULL`.
+    nullable: bool,
+}
+
+impl TableRef<'_> {
+    fn is_named(&self, name: &str) -> bool {
+        match &self.alias {
+            Some(alias) => alias.eq_ignore_ascii_case(name),
+            None => self.name.eq_ignore_ascii_case(name),
+        }
+    }
+}
+
+const CLAUSES: [&str; 11] = [
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "LIMIT",
+    "WINDOW",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "RETURNING",
+    "ON",
+];
+
+/// Keywords which start the statement following a `WITH` clause.
+const STATEMENTS: [&str; 6] = ["SELECT", "VALUES", "INSERT", "REPLACE", "UPDATE", "DELETE"];
+
+/// Resolve the result columns of a statement against `schema`.
+///
+/// Which statements return rows is decided by their leading keyword: a `SELECT` does, and an
+/// `INSERT`, `REPLACE`, `UPDATE` or `DELETE` does only if it has a `RETURNING` clause. Other
+/// statements, such as `INSERT ... SELECT` or `CREATE TABLE ... AS SELECT`, have none.
+fn result_columns(tokens: &[Spanned], schema: &Schema) -> Result<Vec<ResultColumn>, String> {
+    let tokens = tokens
+        .iter()
+        .map(|(t, _)| t)
+        .filter(|t| !matches!(t, Token::Comment(..)))
+        .collect::<Vec<_>>();
+    // The common table expressions of a `WITH` clause are parenthesized, so the statement
+    // itself starts at the first statement keyword at depth zero.
+    let start = match tokens.first() {
+        Some(t) if t.is_word("WITH") => STATEMENTS
+            .iter()
+            .filter_map(|w| at_depth_zero(&tokens, w, 1))
+            .min(),
+        Some(_) => Some(0),
+        None => None,
+    };
+    let Some(start) = start else {
+        return Ok(Vec::new());
+    };
+    let keyword = tokens[start];
+    let columns = if keyword.is_word("SELECT") {
+        select_columns(&tokens, start, schema)?
+    } else if keyword.is_word("VALUES") {
+        return Err(
+            "cannot infer the types of a `VALUES` statement; use a `SELECT` with aliases such \
+             as `AS \"name: i64\"`"
+                .to_owned(),
+        );
+    } else if ["INSERT", "REPLACE", "UPDATE", "DELETE"]
+        .iter()
+        .any(|w| keyword.is_word(w))
+    {
+        returning_columns(&tokens, start, schema)?
+    } else {
+        Vec::new()
+    };
+
+    let mut seen = std::collections::BTreeSet::new();
+    for column in &columns {
+        if !seen.insert(column.field.clone()) {
+            return Err(format!(
+                "more than one result column is named `{}`; give them distinct aliases",
+                column.field
+            ));
+        }
+    }
+    Ok(columns)
+}
+
+/// The position of the first `word` at parenthesis depth zero, starting at `from`.
+fn at_depth_zero(tokens: &[&Token], word: &str, from: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, t) in tokens.iter().enumerate().skip(from) {
+        match t {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            t if depth == 0 && t.is_word(word) => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The columns of the `SELECT` at `select`.
+fn select_columns(
+    tokens: &[&Token],
+    select: usize,
+    schema: &Schema,
+) -> Result<Vec<ResultColumn>, String> {
+    let mut list_start = select + 1;
+    if tokens
+        .get(list_start)
+        .is_some_and(|t| t.is_word("DISTINCT") || t.is_word("ALL"))
+    {
+        list_start += 1;
+    }
+    let from = at_depth_zero(tokens, "FROM", list_start);
+    let list_end = from
+        .or_else(|| {
+            CLAUSES
+                .iter()
+                .filter_map(|c| at_depth_zero(tokens, c, list_start))
+                .min()
+        })
+        .unwrap_or(tokens.len());
+    let tables = match from {
+        Some(from) => {
+            let end = CLAUSES
+                .iter()
+                .filter(|c| **c != "ON")
+                .filter_map(|c| at_depth_zero(tokens, c, from))
+                .min()
+                .unwrap_or(tokens.len());
+            table_refs(&tokens[from + 1..end], schema)
+        }
+        None => Vec::new(),
+    };
+
+    let mut columns = Vec::new();
+    for item in split_top_level(&tokens[list_start..list_end]) {
+        columns.extend(select_item(item, &tables)?);
+    }
+    Ok(columns)
+}
+
+/// The columns of the `RETURNING` clause, if any, of the statement at `start`, which modifies
+/// a single table.
+fn returning_columns(
+    tokens: &[&Token],
+    start: usize,
+    schema: &Schema,
+) -> Result<Vec<ResultColumn>, String> {
+    let Some(returning) = at_depth_zero(tokens, "RETURNING", start) else {
+        return Ok(Vec::new());
+    };
+    let mut i = start + 1;
+    // Skip a conflict clause such as `OR REPLACE`, and the `INTO` or `FROM` before the table.
+    if tokens.get(i).is_some_and(|t| t.is_word("OR")) {
+        i += 2;
+    }
+    if tokens
+        .get(i)
+        .is_some_and(|t| t.is_word("INTO") || t.is_word("FROM"))
+    {
+        i += 1;
+    }
+    let name = table_name(tokens, &mut i)
+        .map_err(|_| "expected the name of the table the statement modifies".to_owned())?;
+    let alias = if tokens.get(i).is_some_and(|t| t.is_word("AS")) {
+        tokens
+            .get(i + 1)
+            .and_then(|t| t.ident())
+            .map(ToOwned::to_owned)
+    } else {
+        None
+    };
+    let table = TableRef {
+        columns: schema.get(&name.to_lowercase()).map(Vec::as_slice),
+        name,
+        alias,
+        nullable: false,
+    };
+
+    let mut columns = Vec::new();
+    for item in split_top_level(&tokens[returning + 1..]) {
+        columns.extend(select_item(item, std::slice::from_ref(&table))?);
+    }
+    Ok(columns)
+}
+
+fn split_top_level<'a, 'b>(tokens: &'a [&'b Token]) -> Vec<&'a [&'b Token]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, t) in tokens.iter().enumerate() {
+        match t {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// Parse the tables of a `FROM` clause.
+fn table_refs<'a>(tokens: &[&Token], schema: &'a Schema) -> Vec<TableRef<'a>> {
+    let mut tables: Vec<TableRef> = Vec::new();
+    let mut i = 0;
+    let mut nullable = false;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Punct(',') => {
+                nullable = false;
+                i += 1;
+            }
+            t if t.is_word("LEFT") => {
+                nullable = true;
+                i += 1;
+            }
+            t if t.is_word("RIGHT") || t.is_word("FULL") => {
+                let full = t.is_word("FULL");
+                for table in &mut tables {
+                    table.nullable = true;
+                }
+                nullable = full;
+                i += 1;
+            }
+            t if ["NATURAL", "INNER", "CROSS", "OUTER", "JOIN"]
+                .iter()
+                .any(|w| t.is_word(w)) =>
+            {
+                i += 1
+            }
+            t if t.is_word("ON") || t.is_word("USING") => {
+                // Skip the join constraint.
+                let mut depth = 0;
+                i += 1;
+                while i < tokens.len() {
+                    match tokens[i] {
+                        Token::Punct('(') => depth += 1,
+                        Token::Punct(')') => depth -= 1,
+                        Token::Punct(',') if depth == 0 => break,
+                        t if depth == 0
+                            && ["LEFT", "RIGHT", "FULL", "NATURAL", "INNER", "CROSS", "JOIN"]
+                                .iter()
+                                .any(|w| t.is_word(w)) =>
+                        {
+                            break
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+            }
+            Token::Punct('(') => {
+                // A subquery; its columns are unknown but it may have an alias.
+                let mut depth = 0;
+                while i < tokens.len() {
+                    match tokens[i] {
+                        Token::Punct('(') => depth += 1,
+                        Token::Punct(')') => depth -= 1,
+                        _ => {}
+                    }
+                    i += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                let alias = alias(tokens, &mut i);
+                tables.push(TableRef {
+                    name: String::new(),
+                    alias,
+                    columns: None,
+                    nullable,
+                });
+            }
+            t => {
+                let Some(mut name) = t.ident().map(ToOwned::to_owned) else {
+                    i += 1;
+                    continue;
+                };
+                i += 1;
+                if tokens.get(i) == Some(&&Token::Punct('.')) {
+                    if let Some(n) = tokens.get(i + 1).and_then(|t| t.ident()) {
+                        name = n.to_owned();
+                        i += 2;
+                    }
+                }
+                let alias = alias(tokens, &mut i);
+                tables.push(TableRef {
+                    columns: schema.get(&name.to_lowercase()).map(Vec::as_slice),
+                    name,
+                    alias,
+                    nullable,
+                });
+            }
+        }
+    }
+    tables
+}
+
+/// Parse an optional `[AS] alias` after a table.
+fn alias(tokens: &[&Token], i: &mut usize) -> Option<String> {
+    if tokens.get(*i).is_some_and(|t| t.is_word("AS")) {
+        *i += 1;
+    }
+    const KEYWORDS: [&str; 9] = [
+        "LEFT", "RIGHT", "FULL", "NATURAL", "INNER", "CROSS", "JOIN", "ON", "USING",
+    ];
+    let t = tokens.get(*i)?;
+    if KEYWORDS.iter().any(|w| t.is_word(w)) {
+        return None;
+    }
+    let alias = t.ident()?.to_owned();
+    *i += 1;
+    Some(alias)
+}
+
+/// Resolve one item of a select list.
+fn select_item(item: &[&Token], tables: &[TableRef]) -> Result<Vec<ResultColumn>, String> {
+    let text = item
+        .iter()
+        .map(|t| describe(t))
+        .collect::<Vec<_>>()
+        .join("");
+    // Split off an alias, which is either `AS name` or a bare name after a column reference.
+    let (expr, alias) = match item {
+        [expr @ .., as_, alias] if as_.is_word("AS") => (expr, alias.ident()),
+        [col @ Token::Word(_) | col @ Token::Quoted(_), alias @ (Token::Word(_) | Token::Quoted(_))] => {
+            (std::slice::from_ref(col), alias.ident())
+        }
+        [_, Token::Punct('.'), col, alias @ (Token::Word(_) | Token::Quoted(_))]
+            if col.ident().is_some() =>
+        {
+            (&item[..3], alias.ident())
+        }
+        expr => (expr, None),
+    };
+
+    if let Some(alias) = alias {
+        if let Some((field, ty)) = alias.split_once(':') {
+            let ty = syn::parse_str(ty.trim())
+                .map_err(|e| format!("invalid type in alias `{alias}`: {e}"))?;
+            return Ok(vec![result_column(alias, field.trim(), ty)?]);
+        }
+    }
+
+    let reference = match expr {
+        [Token::Punct('*')] => return all_columns(tables, None),
+        [table, Token::Punct('.'), Token::Punct('*')] => {
+            return all_columns(tables, table.ident());
+        }
+        [column] => column.ident().map(|c| (None, c)),
+        [table, Token::Punct('.'), column] => column.ident().map(|c| (table.ident(), c)),
+        _ => None,
+    };
+    let Some((table, column)) = reference else {
+        return Err(format!(
+            "cannot infer the type of `{text}`; give it an alias such as `AS \"name: i64\"`"
+        ));
+    };
+    let (table, resolved) = resolve(tables, table, column)?;
+    let name = alias.unwrap_or(&resolved.name);
+    Ok(vec![result_column(
+        name,
+        name,
+        column_type(table, resolved)?,
+    )?])
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(w) => format!("{w} "),
+        Token::Quoted(q) => format!("\"{q}\" "),
+        Token::Str => "'…' ".to_owned(),
+        Token::Literal => "… ".to_owned(),
+        Token::Param(_) => "? ".to_owned(),
+        Token::Comment(..) => String::new(),
+        Token::Punct(c) => c.to_string(),
+    }
+    .trim_end()
+    .to_owned()
+}
+
+fn all_columns(tables: &[TableRef], only: Option<&str>) -> Result<Vec<ResultColumn>, String> {
+    let mut columns = Vec::new();
+    for table in tables
+        .iter()
+        .filter(|t| only.map_or(true, |name| t.is_named(name)))
+    {
+        let table_columns = table.columns.ok_or_else(|| {
+            let name = table.alias.as_deref().unwrap_or(&table.name);
+            format!("the columns of `{name}` are unknown")
+        })?;
+        for column in table_columns {
+            columns.push(result_column(
+                &column.name,
+                &column.name,
+                column_type(table, column)?,
+            )?);
+        }
+    }
+    if columns.is_empty() {
+        return Err(format!("no tables match `{}.*`", only.unwrap_or_default()));
+    }
+    Ok(columns)
+}
+
+fn resolve<'a, 'b>(
+    tables: &'b [TableRef<'a>],
+    table: Option<&str>,
+    column: &str,
+) -> Result<(&'b TableRef<'a>, &'a Column), String> {
+    let mut found = tables
+        .iter()
+        .filter(|t| table.map_or(true, |name| t.is_named(name)))
+        .filter_map(|t| {
+            t.columns?
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(column))
+                .map(|c| (t, c))
+        });
+    let qualified = match table {
+        Some(table) => format!("{table}.{column}"),
+        None => column.to_owned(),
+    };
+    let first = found
+        .next()
+        .ok_or_else(|| format!("no column `{qualified}` in the schema"))?;
+    if found.next().is_some() {
+        return Err(format!("column `{qualified}` is ambiguous"));
+    }
+    Ok(first)
+}
+
+fn column_type(table: &TableRef, column: &Column) -> Result<syn::Type, String> {
+    let ty = column.ty.as_deref().ok_or_else(|| {
+        format!(
+            "cannot infer a Rust type for `{}.{}`; annotate it in the schema with `-- type: ...`",
+            table.name, column.name
+        )
+    })?;
+    let ty: syn::Type = syn::parse_str(ty)
+        .map_err(|e| format!("invalid type `{ty}` for `{}`: {e}", column.name))?;
+    Ok(if column.nullable || table.nullable {
+        syn::parse_quote!(::std::option::Option<#ty>)
+    } else {
+        ty
+    })
+}
+
+fn result_column(name: &str, field: &str, ty: syn::Type) -> Result<ResultColumn, String> {
+    if syn::parse_str::<syn::Ident>(field).is_err() {
+        return Err(format!(
+            "result column `{field}` is not a valid Rust identifier; give it an alias"
+        ));
+    }
+    Ok(ResultColumn {
+        name: name.to_owned(),
+        field: field.to_owned(),
+        ty,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL, -- display name
+            email VARCHAR(255),
+            created_at TEXT NOT NULL, -- type: chrono::NaiveDateTime
+            -- type: serde_json::Value
+            settings TEXT NOT NULL,
+            UNIQUE (email)
+        );
+        CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER NOT NULL, body TEXT NOT NULL);
+        CREATE TRIGGER t AFTER INSERT ON posts BEGIN SELECT 1; END;
+        ALTER TABLE posts ADD COLUMN score REAL;
+        CREATE TABLE old (x);
+        DROP TABLE old;
+        CREATE TABLE tags (name, weight NUMERIC);
+    ";
+
+    fn schema() -> Schema {
+        let mut schema = Schema::new();
+        apply_schema(&mut schema, SCHEMA).unwrap();
+        schema
+    }
+
+    fn columns(sql: &str) -> Result<Vec<(String, String, String)>, String> {
+        let columns = result_columns(&tokenize(sql)?, &schema())?;
+        Ok(columns
+            .into_iter()
+            .map(|c| {
+                let ty = c.ty;
+                (c.name, c.field, quote!(#ty).to_string().replace(' ', ""))
+            })
+            .collect())
+    }
+
+    fn names(sql: &str) -> Vec<String> {
+        columns(sql)
+            .unwrap()
+            .into_iter()
+            .map(|(_, field, _)| field)
+            .collect()
+    }
+
+    #[test]
+    fn counts_parameters() {
+        let count = |sql| parameter_count(&tokenize(sql).unwrap());
+        assert_eq!(count("SELECT * FROM users"), 0);
+        assert_eq!(
+            count("SELECT * FROM users WHERE id = ? AND name = '?' -- ?"),
+            1
+        );
+        assert_eq!(count("SELECT ?, ?3, ?"), 4);
+        assert_eq!(count("SELECT :a, :b, :a"), 2);
+        assert_eq!(count("SELECT :a, @a, $a"), 3);
+        assert_eq!(count("SELECT ?2, :a, ?1"), 3);
+        assert_eq!(count("SELECT \"?\", `?`, [?] FROM t /* ? */"), 0);
+    }
+
+    #[test]
+    fn tokenizes() {
+        use Token::*;
+
+        let tokens = |sql| {
+            tokenize(sql)
+                .unwrap()
+                .into_iter()
+                .map(|(t, _)| t)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            tokens("SELECT \"a\"\"b\", `c`, [d e] FROM t"),
+            [
+                Word("SELECT".into()),
+                Quoted("a\"b".into()),
+                Punct(','),
+                Quoted("c".into()),
+                Punct(','),
+                Quoted("d e".into()),
+                Word("FROM".into()),
+                Word("t".into()),
+            ]
+        );
+        assert_eq!(
+            tokens("'it''s;' x'00ff' 1.5e3 /* ; */ -- note\n?7"),
+            [
+                Str,
+                Literal,
+                Literal,
+                Comment("note".into(), 1),
+                Param(self::Param::Numbered(7)),
+            ]
+        );
+        let lines = tokenize("a\n/*\n*/ b 'x\ny' c")
+            .unwrap()
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [1, 3, 3, 4]);
+        assert_eq!(
+            tokenize("SELECT 'oops").unwrap_err(),
+            "unterminated ' in SQL"
+        );
+    }
+
+    #[test]
+    fn splits_statements() {
+        let tokens = tokenize(
+            "CREATE TABLE a (x); -- only a comment
+             ;
+             CREATE TEMP TRIGGER t AFTER INSERT ON a BEGIN
+                 UPDATE a SET x = CASE WHEN x THEN 1 END;
+             END;
+             SELECT ';'",
+        )
+        .unwrap();
+        let statements = statements(&tokens);
+        assert_eq!(statements.len(), 3);
+        assert!(statements[1].iter().any(|(t, _)| t.is_word("UPDATE")));
+    }
+
+    #[test]
+    fn reads_schema() {
+        let schema = schema();
+        assert_eq!(
+            schema.keys().collect::<Vec<_>>(),
+            ["posts", "tags", "users"]
+        );
+        let users = &schema["users"];
+        assert_eq!(
+            users
+                .iter()
+                .map(|c| (c.name.as_str(), c.ty.as_deref(), c.nullable))
+                .collect::<Vec<_>>(),
+            [
+                ("id", Some("i64"), false),
+                ("name", Some("::std::string::String"), false),
+                ("email", Some("::std::string::String"), true),
+                ("created_at", Some("chrono::NaiveDateTime"), false),
+                ("settings", Some("serde_json::Value"), false),
+            ]
+        );
+        assert_eq!(schema["posts"].last().unwrap().name, "score");
+        assert_eq!(schema["tags"][1].ty, None);
+    }
+
+    #[test]
+    fn applies_schema_changes() {
+        let mut schema = Schema::new();
+        apply_schema(
+            &mut schema,
+            "CREATE TABLE main.\"Items\" (id INT PRIMARY KEY, label TEXT, gone BLOB);
+             CREATE TABLE IF NOT EXISTS items (other INT);
+             CREATE TABLE copy AS SELECT * FROM items;
+             ALTER TABLE items RENAME COLUMN label TO title;
+             ALTER TABLE items DROP COLUMN gone;
+             ALTER TABLE items ADD COLUMN done BOOLEAN NOT NULL DEFAULT 0;
+             ALTER TABLE items RENAME TO things;",
+        )
+        .unwrap();
+        assert_eq!(schema.keys().collect::<Vec<_>>(), ["things"]);
+        assert_eq!(
+            schema["things"]
+                .iter()
+                .map(|c| (c.name.as_str(), c.ty.as_deref(), c.nullable))
+                .collect::<Vec<_>>(),
+            [
+                // Unlike `INTEGER PRIMARY KEY`, `INT PRIMARY KEY` is not a rowid alias.
+                ("id", Some("i64"), true),
+                ("title", Some("::std::string::String"), true),
+                ("done", Some("bool"), false),
+            ]
+        );
+        assert_eq!(
+            apply_schema(&mut schema, "ALTER TABLE nope ADD x INT").unwrap_err(),
+            "ALTER TABLE of unknown table `nope`"
+        );
+    }
+
+    #[test]
+    fn follows_type_affinity() {
+        assert_eq!(affinity_type("BIGINT"), Some("i64"));
+        // SQLite checks for `INT` first, so this is an integer column.
+        assert_eq!(affinity_type("FLOATING POINT"), Some("i64"));
+        assert_eq!(
+            affinity_type("NATIVE CHARACTER"),
+            Some("::std::string::String")
+        );
+        assert_eq!(affinity_type("BLOB"), Some("::std::vec::Vec<u8>"));
+        assert_eq!(affinity_type("DOUBLE PRECISION"), Some("f64"));
+        assert_eq!(affinity_type("BOOLEAN"), Some("bool"));
+        assert_eq!(affinity_type("DATETIME"), Some("::chrono::NaiveDateTime"));
+        assert_eq!(affinity_type("DATE"), Some("::chrono::NaiveDate"));
+        assert_eq!(affinity_type("NUMERIC"), None);
+        assert_eq!(affinity_type(""), None);
+    }
+
+    #[test]
+    fn resolves_columns() {
+        assert_eq!(
+            columns("SELECT id, name AS display, email FROM users WHERE id = ?").unwrap(),
+            [
+                ("id".into(), "id".into(), "i64".into()),
+                (
+                    "display".into(),
+                    "display".into(),
+                    "::std::string::String".into()
+                ),
+                (
+                    "email".into(),
+                    "email".into(),
+                    "::std::option::Option<::std::string::String>".into()
+                ),
+            ]
+        );
+        assert_eq!(
+            columns(
+                "SELECT u.name, p.body, COUNT(*) AS \"n: i64\" FROM users u \
+                 LEFT JOIN posts AS p ON p.user_id = u.id GROUP BY u.id"
+            )
+            .unwrap(),
+            [
+                ("name".into(), "name".into(), "::std::string::String".into()),
+                (
+                    "body".into(),
+                    "body".into(),
+                    "::std::option::Option<::std::string::String>".into()
+                ),
+                ("n: i64".into(), "n".into(), "i64".into()),
+            ]
+        );
+        assert_eq!(columns("SELECT * FROM posts").unwrap().len(), 4);
+        assert_eq!(names("SELECT DISTINCT name FROM users"), ["name"]);
+        assert_eq!(
+            names("SELECT p.*, u.name FROM posts p JOIN users u ON u.id = p.user_id"),
+            ["id", "user_id", "body", "score", "name"]
+        );
+        assert_eq!(names("SELECT \"name\" FROM main.\"users\""), ["name"]);
+        assert_eq!(names("SELECT email address FROM users"), ["address"]);
+        assert_eq!(names("SELECT id -- the key\n FROM users"), ["id"]);
+        assert_eq!(
+            names("SELECT id FROM users UNION SELECT user_id FROM posts"),
+            ["id"]
+        );
+        assert_eq!(
+            columns("SELECT u.name FROM users u RIGHT JOIN posts p ON p.user_id = u.id").unwrap()
+                [0]
+            .2,
+            "::std::option::Option<::std::string::String>"
+        );
+        assert_eq!(
+            columns("SELECT 1 AS \"one: i64\"").unwrap(),
+            [("one: i64".into(), "one".into(), "i64".into())]
+        );
+        assert_eq!(
+            names("SELECT n AS \"n: i64\" FROM (SELECT COUNT(*) AS n FROM posts) AS c"),
+            ["n"]
+        );
+        assert_eq!(names("WITH n AS (SELECT 1) SELECT id FROM users"), ["id"]);
+    }
+
+    #[test]
+    fn decides_from_leading_keyword() {
+        let none = |sql| columns(sql).unwrap().is_empty();
+        assert!(none("INSERT INTO posts (body) VALUES (?)"));
+        assert!(none(
+            "INSERT INTO posts (user_id, body) SELECT id, name FROM users"
+        ));
+        assert!(none(
+            "UPDATE posts SET body = (SELECT name FROM users) WHERE id = ?"
+        ));
+        assert!(none("DELETE FROM posts WHERE id IN (SELECT id FROM posts)"));
+        assert!(none("CREATE TABLE copy AS SELECT * FROM users"));
+        assert!(none(
+            "WITH recent AS (SELECT * FROM posts) \
+             INSERT INTO posts (body) SELECT body FROM recent"
+        ));
+        assert!(none("PRAGMA foreign_keys = ON"));
+        assert!(none("-- nothing"));
+    }
+
+    #[test]
+    fn resolves_returning_columns() {
+        assert_eq!(
+            columns("INSERT INTO users (name, settings) VALUES (?, ?) RETURNING id, created_at")
+                .unwrap(),
+            [
+                ("id".into(), "id".into(), "i64".into()),
+                (
+                    "created_at".into(),
+                    "created_at".into(),
+                    "chrono::NaiveDateTime".into()
+                ),
+            ]
+        );
+        assert_eq!(
+            names("UPDATE posts SET score = ? WHERE id = ? RETURNING *"),
+            ["id", "user_id", "body", "score"]
+        );
+        assert_eq!(
+            columns("DELETE FROM main.users AS u WHERE id = ? RETURNING u.email AS address")
+                .unwrap(),
+            [(
+                "address".into(),
+                "address".into(),
+                "::std::option::Option<::std::string::String>".into()
+            )]
+        );
+        assert_eq!(
+            names("INSERT OR REPLACE INTO posts (id, body) VALUES (?, ?) RETURNING body"),
+            ["body"]
+        );
+        assert_eq!(
+            names("REPLACE INTO posts (id, body) VALUES (?, ?) RETURNING id"),
+            ["id"]
+        );
+        assert_eq!(
+            names(
+                "INSERT INTO posts (id, body) VALUES (?, ?) \
+                 ON CONFLICT (id) DO UPDATE SET body = excluded.body RETURNING id"
+            ),
+            ["id"]
+        );
+        assert_eq!(
+            names(
+                "WITH old AS (SELECT id FROM posts) \
+                 DELETE FROM posts WHERE id IN old RETURNING id"
+            ),
+            ["id"]
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = |sql| columns(sql).unwrap_err();
+        assert_eq!(
+            error("SELECT nope FROM users"),
+            "no column `nope` in the schema"
+        );
+        assert_eq!(
+            error("SELECT id FROM users, posts"),
+            "column `id` is ambiguous"
+        );
+        assert!(error("SELECT COUNT(*) FROM users").starts_with("cannot infer the type"));
+        assert!(error("SELECT u.id, p.id FROM users u JOIN posts p").starts_with("more than one"));
+        assert_eq!(
+            error("SELECT weight FROM tags"),
+            "cannot infer a Rust type for `tags.weight`; annotate it in the schema with \
+             `-- type: ...`"
+        );
+        assert_eq!(
+            error("SELECT * FROM (SELECT 1) AS s"),
+            "the columns of `s` are unknown"
+        );
+        assert_eq!(error("SELECT x.* FROM users"), "no tables match `x.*`");
+        assert!(error("SELECT id AS \"id: Vec<\" FROM users").starts_with("invalid type"));
+        assert_eq!(
+            error("SELECT name AS \"1st\" FROM users"),
+            "result column `1st` is not a valid Rust identifier; give it an alias"
+        );
+        assert!(error("VALUES (1), (2)").starts_with("cannot infer the types of a `VALUES`"));
+        assert_eq!(
+            error("INSERT INTO nope (x) VALUES (1) RETURNING x"),
+            "no column `x` in the schema"
+        );
+        assert_eq!(
+            error("INSERT INTO posts (body) VALUES (?) RETURNING id, id"),
+            "more than one result column is named `id`; give them distinct aliases"
+        );
+    }
+}
@@ -1,53 +1,29 @@
 #![allow(dead_code)]
 use anyhow::Result;
 use http::{Request, Response};
-use spin_sdk::{http_component, pg3, pg3::Decode};
+use spin_sdk::{http_component, pg3};
 
 // The environment variable set in `spin.toml` that points to the
 // address of the Pg server that the component will write to
 const DB_URL_ENV: &str = "DB_URL";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Deserialize)]
 struct Article {
     id: i32,
     title: String,
     content: String,
     authorname: String,
+    #[serde(rename = "publisheddate")]
     published_date: chrono::NaiveDate,
+    #[serde(rename = "publishedtime")]
     published_time: Option<chrono::NaiveTime>,
+    #[serde(rename = "publisheddatetime")]
     published_datetime: Option<chrono::NaiveDateTime>,
+    #[serde(rename = "readtime")]
     read_time: Option<i64>,
     coauthor: Option<String>,
 }
 
-impl TryFrom<&pg3::Row> for Article {
-    type Error = anyhow::Error;
-
-    fn try_from(row: &pg3::Row) -> Result<Self, Self::Error> {
-        let id = i32::decode(&row[0])?;
-        let title = String::decode(&row[1])?;
-        let content = String::decode(&row[2])?;
-        let authorname = String::decode(&row[3])?;
-        let published_date = chrono::NaiveDate::decode(&row[4])?;
-        let published_time = Option::<chrono::NaiveTime>::decode(&row[5])?;
-        let published_datetime = Option::<chrono::NaiveDateTime>::decode(&row[6])?;
-        let read_time = Option::<i64>::decode(&row[7])?;
-        let coauthor = Option::<String>::decode(&row[8])?;
-
-        Ok(Self {
-            id,
-            title,
-            content,
-            authorname,
-            published_date,
-            published_time,
-            published_datetime,
-            read_time,
-            coauthor,
-        })
-    }
-}
-
 #[http_component]
 fn process(req: Request<()>) -> Result<Response<String>> {
     match req.uri().path() {
@@ -63,7 +39,7 @@ fn process(req: Request<()>) -> Result<Response<String>> {
 
 fn read(_req: Request<()>) -> Result<Response<String>> {
     let address = std::env::var(DB_URL_ENV)?;
-    let conn = pg3::Connection::open(&address)?;
+    let conn = pg3::shared(&address)?;
 
     let sql = "SELECT id, title, content, authorname, publisheddate, publishedtime, publisheddatetime, readtime, coauthor FROM articletest";
     let rowset = conn.query(sql, &[])?;
@@ -77,9 +53,7 @@ fn read(_req: Request<()>) -> Result<Response<String>> {
 
     let mut response_lines = vec![];
 
-    for row in rowset.rows {
-        let article = Article::try_from(&row)?;
-
+    for article in rowset.deserialize::<Article>()? {
         println!("article: {:#?}", article);
         response_lines.push(format!("article: {:#?}", article));
     }
@@ -98,7 +72,7 @@ fn read(_req: Request<()>) -> Result<Response<String>> {
 
 fn write_datetime_info(_req: Request<()>) -> Result<Response<String>> {
     let address = std::env::var(DB_URL_ENV)?;
-    let conn = pg3::Connection::open(&address)?;
+    let conn = pg3::shared(&address)?;
 
     let date: chrono::NaiveDate = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let time: chrono::NaiveTime = chrono::NaiveTime::from_hms_nano_opt(12, 34, 56, 1).unwrap();
@@ -112,10 +86,7 @@ fn write_datetime_info(_req: Request<()>) -> Result<Response<String>> {
 
     println!("nrow_executed: {:?}", nrow_executed);
 
-    let sql = "SELECT COUNT(id) FROM articletest";
-    let rowset = conn.query(sql, &[])?;
-    let row = &rowset.rows[0];
-    let count = i64::decode(&row[0])?;
+    let count: i64 = conn.query_scalar("SELECT COUNT(id) FROM articletest", &[])?;
     let response = format!("Count: {}\n", count);
 
     Ok(http::Response::builder().status(200).body(response)?)
@@ -123,7 +94,7 @@ fn write_datetime_info(_req: Request<()>) -> Result<Response<String>> {
 
 fn write(_req: Request<()>) -> Result<Response<String>> {
     let address = std::env::var(DB_URL_ENV)?;
-    let conn = pg3::Connection::open(&address)?;
+    let conn = pg3::shared(&address)?;
 
     let sql =
         "INSERT INTO articletest (title, content, authorname, publisheddate) VALUES ('aaa', 'bbb', 'ccc', '2024-01-01')";
@@ -131,10 +102,7 @@ fn write(_req: Request<()>) -> Result<Response<String>> {
 
     println!("nrow_executed: {}", nrow_executed);
 
-    let sql = "SELECT COUNT(id) FROM articletest";
-    let rowset = conn.query(sql, &[])?;
-    let row = &rowset.rows[0];
-    let count = i64::decode(&row[0])?;
+    let count: i64 = conn.query_scalar("SELECT COUNT(id) FROM articletest", &[])?;
     let response = format!("Count: {}\n", count);
 
     Ok(http::Response::builder().status(200).body(response)?)
@@ -142,15 +110,10 @@ fn write(_req: Request<()>) -> Result<Response<String>> {
 
 fn pg_backend_pid(_req: Request<()>) -> Result<Response<String>> {
     let address = std::env::var(DB_URL_ENV)?;
-    let conn = pg3::Connection::open(&address)?;
+    let conn = pg3::shared(&address)?;
     let sql = "SELECT pg_backend_pid()";
 
-    let get_pid = || {
-        let rowset = conn.query(sql, &[])?;
-        let row = &rowset.rows[0];
-
-        i32::decode(&row[0])
-    };
+    let get_pid = || conn.query_scalar::<i32>(sql, &[]);
 
     assert_eq!(get_pid()?, get_pid()?);
 
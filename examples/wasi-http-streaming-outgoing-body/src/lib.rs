@@ -1,4 +1,4 @@
-use futures::{future, Future, SinkExt, StreamExt};
+use futures::{future, Future};
 use spin_sdk::{
     http::{
         self, Headers, IncomingRequest, IncomingResponse, Method, OutgoingBody, OutgoingRequest,
@@ -28,7 +28,7 @@ async fn handle_request(request: IncomingRequest, response_out: ResponseOutparam
 
             match double_echo(request, &url).await {
                 Ok((request_copy, incoming_response)) => {
-                    let mut incoming_response_body = incoming_response.take_body_stream();
+                    let incoming_response_body = incoming_response.take_body_stream();
 
                     let outgoing_response = OutgoingResponse::new(
                         Headers::from_list(
@@ -40,14 +40,12 @@ async fn handle_request(request: IncomingRequest, response_out: ResponseOutparam
                         .unwrap(),
                     );
 
-                    let mut outgoing_response_body = outgoing_response.take_body();
+                    let outgoing_response_body = outgoing_response.take_body();
 
                     response_out.set(outgoing_response);
 
                     let response_copy = async move {
-                        while let Some(chunk) = incoming_response_body.next().await {
-                            outgoing_response_body.send(chunk?).await?;
-                        }
+                        spin_sdk::copy(incoming_response_body, outgoing_response_body).await?;
                         Ok::<_, anyhow::Error>(())
                     };
 
@@ -88,16 +86,14 @@ async fn double_echo(
         .set_authority(Some(url.authority()))
         .map_err(|()| anyhow::anyhow!("unable to set authority"))?;
 
-    let mut body = outgoing_request.take_body();
+    let body = outgoing_request.take_body();
 
     let response = http::send::<_, IncomingResponse>(outgoing_request).await?;
 
-    let mut stream = incoming_request.into_body_stream();
+    let stream = incoming_request.into_body_stream();
 
     let copy = async move {
-        while let Some(chunk) = stream.next().await {
-            body.send(chunk?).await?;
-        }
+        spin_sdk::copy(stream, body).await?;
         Ok::<_, anyhow::Error>(())
     };
 
@@ -0,0 +1,271 @@
+//! Lightweight health metrics for database connections.
+//!
+//! Connections opened through [`Instrumented`] record open attempts, open failures and query
+//! latencies under a caller-chosen label (e.g. `"orders-db"`). The counters live for the
+//! lifetime of the component instance and can be read back with [`connection_health`], for
+//! example to serve a diagnostics endpoint or to log a warning when failures start piling up.
+//!
+//! ```ignore
+//! use spin_sdk::{db_health::{self, Instrumented}, pg3};
+//!
+//! let conn = Instrumented::<pg3::Connection>::open("orders-db", &address)?;
+//! let rows = conn.query("SELECT id FROM orders", &[])?;
+//!
+//! for health in db_health::connection_health() {
+//!     println!("{}: {} failed opens", health.label, health.open_failures);
+//! }
+//! ```
+//!
+//! [`Instrumented`]: crate::db_health::Instrumented
+//! [`connection_health`]: crate::db_health::connection_health
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static REGISTRY: Mutex<BTreeMap<String, ConnectionHealth>> = Mutex::new(BTreeMap::new());
+
+/// A snapshot of the health metrics recorded for one connection label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionHealth {
+    /// The label the connection was opened with.
+    pub label: String,
+    /// The number of times a connection was opened (successfully or not).
+    pub open_attempts: u64,
+    /// The number of times opening a connection failed.
+    pub open_failures: u64,
+    /// The number of queries and statements issued.
+    pub queries: u64,
+    /// The number of queries and statements which returned an error.
+    pub query_failures: u64,
+    /// The total time spent waiting on queries.
+    pub total_query_time: Duration,
+    /// The slowest query observed.
+    pub max_query_time: Duration,
+    /// The most recent error, if any.
+    pub last_error: Option<String>,
+}
+
+impl ConnectionHealth {
+    /// The mean query latency, or `None` if no queries have been issued.
+    pub fn mean_query_time(&self) -> Option<Duration> {
+        let queries = u32::try_from(self.queries).ok().filter(|q| *q > 0)?;
+        Some(self.total_query_time / queries)
+    }
+}
+
+/// Return a snapshot of the metrics for every label, ordered by label.
+pub fn connection_health() -> Vec<ConnectionHealth> {
+    REGISTRY.lock().unwrap().values().cloned().collect()
+}
+
+/// Return a snapshot of the metrics for the given label.
+pub fn connection_health_for(label: &str) -> Option<ConnectionHealth> {
+    REGISTRY.lock().unwrap().get(label).cloned()
+}
+
+/// Clear all recorded metrics.
+pub fn reset() {
+    REGISTRY.lock().unwrap().clear();
+}
+
+fn update(label: &str, f: impl FnOnce(&mut ConnectionHealth)) {
+    let mut registry = REGISTRY.lock().unwrap();
+    let health = registry
+        .entry(label.to_owned())
+        .or_insert_with(|| ConnectionHealth {
+            label: label.to_owned(),
+            ..Default::default()
+        });
+    f(health);
+}
+
+fn record_open<T, E: Display>(label: &str, result: &Result<T, E>) {
+    update(label, |health| {
+        health.open_attempts += 1;
+        if let Err(e) = result {
+            health.open_failures += 1;
+            health.last_error = Some(e.to_string());
+        }
+    });
+}
+
+fn record_query<T, E: Display>(label: &str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    update(label, |health| {
+        health.queries += 1;
+        health.total_query_time += elapsed;
+        health.max_query_time = health.max_query_time.max(elapsed);
+        if let Err(e) = &result {
+            health.query_failures += 1;
+            health.last_error = Some(e.to_string());
+        }
+    });
+    result
+}
+
+/// A database connection which records health metrics under a label.
+///
+/// Only the methods defined on `Instrumented` are counted. Other operations are available
+/// through [`inner`](Self::inner), and are not recorded.
+pub struct Instrumented<C> {
+    label: String,
+    inner: C,
+}
+
+impl<C> Instrumented<C> {
+    /// The label metrics are recorded under.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The underlying connection. Operations issued through it are not recorded.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Return the underlying connection.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    fn open_with<E: Display>(
+        label: impl Into<String>,
+        open: impl FnOnce() -> Result<C, E>,
+    ) -> Result<Self, E> {
+        let label = label.into();
+        let result = open();
+        record_open(&label, &result);
+        result.map(|inner| Self { label, inner })
+    }
+
+    fn query_with<T, E: Display>(&self, f: impl FnOnce(&C) -> Result<T, E>) -> Result<T, E> {
+        record_query(&self.label, || f(&self.inner))
+    }
+}
+
+impl Instrumented<crate::pg3::Connection> {
+    /// Open a connection to the Postgres instance at `address`.
+    pub fn open(label: impl Into<String>, address: &str) -> Result<Self, crate::pg3::PgError> {
+        Self::open_with(label, || crate::pg3::Connection::open(address))
+    }
+
+    /// Query the database.
+    pub fn query(
+        &self,
+        statement: &str,
+        params: &[crate::pg3::ParameterValue],
+    ) -> Result<crate::pg3::RowSet, crate::pg3::PgError> {
+        self.query_with(|c| c.query(statement, params))
+    }
+
+    /// Execute command to the database.
+    pub fn execute(
+        &self,
+        statement: &str,
+        params: &[crate::pg3::ParameterValue],
+    ) -> Result<u64, crate::pg3::PgError> {
+        self.query_with(|c| c.execute(statement, params))
+    }
+}
+
+impl Instrumented<crate::mysql::Connection> {
+    /// Open a connection to the MySQL instance at `address`.
+    pub fn open(label: impl Into<String>, address: &str) -> Result<Self, crate::mysql::MysqlError> {
+        Self::open_with(label, || crate::mysql::Connection::open(address))
+    }
+
+    /// Query the database: select
+    pub fn query(
+        &self,
+        statement: &str,
+        params: &[crate::mysql::ParameterValue],
+    ) -> Result<crate::mysql::RowSet, crate::mysql::MysqlError> {
+        self.query_with(|c| c.query(statement, params))
+    }
+
+    /// Execute command to the database: insert, update, delete
+    pub fn execute(
+        &self,
+        statement: &str,
+        params: &[crate::mysql::ParameterValue],
+    ) -> Result<(), crate::mysql::MysqlError> {
+        self.query_with(|c| c.execute(statement, params))
+    }
+}
+
+impl Instrumented<crate::sqlite::Connection> {
+    /// Open a connection to a named database instance.
+    pub fn open(label: impl Into<String>, database: &str) -> Result<Self, crate::sqlite::Error> {
+        Self::open_with(label, || crate::sqlite::Connection::open(database))
+    }
+
+    /// Execute a statement returning back data if there is any
+    pub fn execute(
+        &self,
+        statement: &str,
+        parameters: &[crate::sqlite::Value],
+    ) -> Result<crate::sqlite::QueryResult, crate::sqlite::Error> {
+        self.query_with(|c| c.execute(statement, parameters))
+    }
+}
+
+impl Instrumented<crate::redis::Connection> {
+    /// Open a connection to the Redis instance at `address`.
+    pub fn open(label: impl Into<String>, address: &str) -> Result<Self, crate::redis::Error> {
+        Self::open_with(label, || crate::redis::Connection::open(address))
+    }
+
+    /// Get the value of a key.
+    pub fn get(&self, key: &str) -> Result<Option<crate::redis::Payload>, crate::redis::Error> {
+        self.query_with(|c| c.get(key))
+    }
+
+    /// Set key to value.
+    pub fn set(&self, key: &str, value: &crate::redis::Payload) -> Result<(), crate::redis::Error> {
+        self.query_with(|c| c.set(key, value))
+    }
+
+    /// Publish a Redis message to the specified channel.
+    pub fn publish(
+        &self,
+        channel: &str,
+        payload: &crate::redis::Payload,
+    ) -> Result<(), crate::redis::Error> {
+        self.query_with(|c| c.publish(channel, payload))
+    }
+
+    /// Execute an arbitrary Redis command and receive the result.
+    pub fn execute(
+        &self,
+        command: &str,
+        arguments: &[crate::redis::RedisParameter],
+    ) -> Result<Vec<crate::redis::RedisResult>, crate::redis::Error> {
+        self.query_with(|c| c.execute(command, arguments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_opens_and_queries() {
+        let label = "records_opens_and_queries";
+        record_open::<(), _>(label, &Ok::<_, String>(()));
+        record_open::<(), _>(label, &Err("connection refused".to_owned()));
+        record_query(label, || Ok::<_, String>(())).unwrap();
+        record_query(label, || Err::<(), _>("syntax error".to_owned())).unwrap_err();
+
+        let health = connection_health_for(label).unwrap();
+        assert_eq!(health.open_attempts, 2);
+        assert_eq!(health.open_failures, 1);
+        assert_eq!(health.queries, 2);
+        assert_eq!(health.query_failures, 1);
+        assert_eq!(health.last_error.as_deref(), Some("syntax error"));
+        assert!(health.mean_query_time().is_some());
+    }
+}
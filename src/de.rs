@@ -0,0 +1,204 @@
+//! Deserialization of named text fields, such as Redis hashes and application variables, into
+//! Rust types. Database rows are deserialized by [`row`].
+
+use serde::de::{
+    self,
+    value::{MapDeserializer, SeqDeserializer, StrDeserializer},
+    Deserializer, IntoDeserializer, Visitor,
+};
+
+pub(crate) mod row;
+
+/// An error deserializing fields.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub(crate) struct DeserializeError(String);
+
+impl de::Error for DeserializeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Deserialize fields as a map from field name to value.
+pub(crate) fn from_fields<T: de::DeserializeOwned>(
+    fields: &[(String, impl AsRef<[u8]>)],
+) -> Result<T, DeserializeError> {
+    T::deserialize(MapDeserializer::new(fields.iter().map(|(name, value)| {
+        let field = FieldDeserializer {
+            name,
+            value: value.as_ref(),
+        };
+        (name.as_str(), field)
+    })))
+}
+
+/// Deserializes a field value, parsing it from text as the target type requires.
+///
+/// Errors name the field but not its value, which may be a secret.
+struct FieldDeserializer<'a> {
+    name: &'a str,
+    value: &'a [u8],
+}
+
+impl<'a> FieldDeserializer<'a> {
+    fn text(&self) -> Result<&'a str, DeserializeError> {
+        std::str::from_utf8(self.value).map_err(|_| self.error(format_args!("value is not UTF-8")))
+    }
+
+    fn parse<T: std::str::FromStr>(&self) -> Result<T, DeserializeError>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.text()?.trim().parse().map_err(|e| self.error(e))
+    }
+
+    fn error(&self, reason: impl std::fmt::Display) -> DeserializeError {
+        de::Error::custom(format!("invalid `{}`: {reason}", self.name))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeserializeError> for FieldDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for FieldDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match std::str::from_utf8(self.value) {
+            Ok(s) => visitor.visit_borrowed_str(s),
+            Err(_) => visitor.visit_borrowed_bytes(self.value),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char
+    }
+
+    /// Booleans are accepted as `1`/`0` as well as `true`/`false`.
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.text()?.trim() {
+            "1" | "true" => visitor.visit_bool(true),
+            "0" | "false" => visitor.visit_bool(false),
+            _ => Err(self.error(format_args!("expected a boolean"))),
+        }
+    }
+
+    /// Text has no null, so a field which is present always holds a value.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_bytes(self.value)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    /// `Vec<u8>` deserializes from a sequence, so values are also exposed as one.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SeqDeserializer::new(self.value.iter().copied()))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit enum variants are stored as their name.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(StrDeserializer::new(self.text()?))
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string unit unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_fields() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Role {
+            Admin,
+        }
+
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct User {
+            name: String,
+            visits: u32,
+            active: bool,
+            role: Role,
+            email: Option<String>,
+        }
+
+        let fields = [
+            ("name", "Ada"),
+            ("visits", "3"),
+            ("active", "1"),
+            ("role", "admin"),
+            ("theme", "dark"),
+        ]
+        .map(|(f, v)| (f.to_owned(), v.as_bytes().to_vec()));
+        assert_eq!(
+            from_fields::<User>(&fields).unwrap(),
+            User {
+                name: "Ada".to_owned(),
+                visits: 3,
+                active: true,
+                role: Role::Admin,
+                email: None,
+            }
+        );
+
+        let bad = [("visits".to_owned(), b"many".to_vec())];
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Visits {
+            visits: u32,
+        }
+        assert!(from_fields::<Visits>(&bad).is_err());
+        assert!(from_fields::<Visits>(&[] as &[(String, Vec<u8>)]).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Deserialization of database rows into Rust types, shared by the database modules.
+
+use serde::de::{
+    self,
+    value::{BorrowedStrDeserializer, SeqDeserializer, StrDeserializer},
+    Deserializer, IntoDeserializer, MapAccess, Visitor,
+};
+
+/// An error deserializing a database row.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct DeserializeError(String);
+
+impl de::Error for DeserializeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// A column of a result, as the row deserializer needs to see it.
+pub(crate) trait Column {
+    /// The name of the column.
+    fn name(&self) -> &str;
+}
+
+impl Column for String {
+    fn name(&self) -> &str {
+        self
+    }
+}
+
+/// A database value, as the row deserializer needs to see it.
+pub(crate) trait Value {
+    /// Visit the value as the Rust type closest to it.
+    fn visit<'de, V: Visitor<'de>>(&'de self, visitor: V) -> Result<V::Value, DeserializeError>;
+
+    /// Whether the value is `NULL`.
+    fn is_null(&self) -> bool;
+
+    /// The value if it is text.
+    fn as_str(&self) -> Option<&str>;
+
+    /// The value if it is binary.
+    fn as_bytes(&self) -> Option<&[u8]>;
+
+    /// The value as a boolean, for databases which store booleans as integers.
+    fn as_bool(&self) -> Option<bool> {
+        None
+    }
+}
+
+/// Deserializes a row as a map from column name to value, or as a sequence of values.
+pub(crate) struct RowDeserializer<'a, C, T> {
+    pub(crate) columns: &'a [C],
+    pub(crate) values: &'a [T],
+}
+
+impl<'de, C: Column, T: Value> Deserializer<'de> for RowDeserializer<'de, C, T> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(RowMap {
+            columns: self.columns.iter(),
+            values: self.values.iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(SeqDeserializer::new(
+            self.values.iter().map(ValueDeserializer),
+        ))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+struct RowMap<'a, C, T> {
+    columns: std::slice::Iter<'a, C>,
+    values: std::slice::Iter<'a, T>,
+    value: Option<&'a T>,
+}
+
+impl<'de, C: Column, T: Value> MapAccess<'de> for RowMap<'de, C, T> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let (Some(column), Some(value)) = (self.columns.next(), self.values.next()) else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::new(column.name()))
+            .map(Some)
+    }
+
+    fn next_value_seed<S: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<S::Value, Self::Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        seed.deserialize(ValueDeserializer(value))
+    }
+}
+
+/// Deserializes a single column value.
+pub(crate) struct ValueDeserializer<'a, T>(pub(crate) &'a T);
+
+impl<'de, T: Value> IntoDeserializer<'de, DeserializeError> for ValueDeserializer<'de, T> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de, T: Value> Deserializer<'de> for ValueDeserializer<'de, T> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.visit(visitor)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.as_bool() {
+            Some(b) => visitor.visit_bool(b),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.as_str() {
+            Some(s) => visitor.visit_borrowed_bytes(s.as_bytes()),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    /// `Vec<u8>` deserializes from a sequence, so binary values are also exposed as one.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.as_bytes() {
+            Some(b) => visitor.visit_seq(SeqDeserializer::new(b.iter().copied())),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit enum variants are stored as their name.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0.as_str() {
+            Some(s) => visitor.visit_enum(StrDeserializer::new(s)),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    /// Columns without a matching field are skipped, whatever their type.
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct tuple tuple_struct map struct identifier
+    }
+}
@@ -0,0 +1,22 @@
+//! Hashing for values which must stay stable across builds and runs.
+
+/// A 64-bit FNV-1a hash of `bytes`, hex encoded.
+///
+/// This only needs to be stable, not cryptographically strong.
+pub(crate) fn fnv1a(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_stable() {
+        assert_eq!(fnv1a(b""), "cbf29ce484222325");
+        assert_eq!(fnv1a(b"a"), "af63dc4c8601ec8c");
+    }
+}
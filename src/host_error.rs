@@ -0,0 +1,18 @@
+//! Classification of failures which the host reports only as text.
+
+/// Whether a host error message describes an I/O failure of the connection itself, rather
+/// than a failure of the operation, recognised by the messages the host's clients give for
+/// such failures.
+pub(crate) fn is_connection_failure(message: &str) -> bool {
+    const MARKERS: [&str; 7] = [
+        "broken pipe",
+        "connection reset",
+        "connection refused",
+        "connection closed",
+        "connection aborted",
+        "timed out",
+        "os error",
+    ];
+    let message = message.to_lowercase();
+    MARKERS.iter().any(|marker| message.contains(marker))
+}
@@ -508,7 +508,26 @@ impl IncomingRequest {
     ///
     /// Panics if the body was already consumed.
     pub fn into_body_stream(self) -> impl futures::Stream<Item = Result<Vec<u8>, streams::Error>> {
-        executor::incoming_body(self.consume().expect("request body was already consumed"))
+        self.into_body_stream_with_chunk_size(executor::DEFAULT_READ_SIZE)
+    }
+
+    /// Like [`into_body_stream`](Self::into_body_stream), but reading at most `chunk_size`
+    /// bytes at a time rather than 16 KiB.
+    ///
+    /// Larger chunks mean fewer, larger allocations when proxying big bodies; smaller chunks
+    /// bound the memory held by each one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the body was already consumed, or if `chunk_size` is zero.
+    pub fn into_body_stream_with_chunk_size(
+        self,
+        chunk_size: u64,
+    ) -> impl futures::Stream<Item = Result<Vec<u8>, streams::Error>> {
+        executor::incoming_body(
+            self.consume().expect("request body was already consumed"),
+            chunk_size,
+        )
     }
 
     /// Return a `Vec<u8>` of the body or fails
@@ -517,7 +536,12 @@ impl IncomingRequest {
         let mut stream = self.into_body_stream();
         let mut body = Vec::new();
         while let Some(chunk) = stream.try_next().await? {
-            body.extend(chunk);
+            // Keep the first chunk's buffer rather than copying it.
+            if body.is_empty() {
+                body = chunk;
+            } else {
+                body.extend(chunk);
+            }
         }
         Ok(body)
     }
@@ -536,7 +560,23 @@ impl IncomingResponse {
     // have started, they might not be able to finish before the connection is closed).  See
     // https://github.com/bytecodealliance/wasmtime/issues/7413 for details.
     pub fn take_body_stream(&self) -> impl futures::Stream<Item = Result<Vec<u8>, streams::Error>> {
-        executor::incoming_body(self.consume().expect("response body was already consumed"))
+        self.take_body_stream_with_chunk_size(executor::DEFAULT_READ_SIZE)
+    }
+
+    /// Like [`take_body_stream`](Self::take_body_stream), but reading at most `chunk_size`
+    /// bytes at a time rather than 16 KiB.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the body was already consumed, or if `chunk_size` is zero.
+    pub fn take_body_stream_with_chunk_size(
+        &self,
+        chunk_size: u64,
+    ) -> impl futures::Stream<Item = Result<Vec<u8>, streams::Error>> {
+        executor::incoming_body(
+            self.consume().expect("response body was already consumed"),
+            chunk_size,
+        )
     }
 
     /// Return a `Vec<u8>` of the body or fails
@@ -549,7 +589,12 @@ impl IncomingResponse {
         let mut stream = self.take_body_stream();
         let mut body = Vec::new();
         while let Some(chunk) = stream.try_next().await? {
-            body.extend(chunk);
+            // Keep the first chunk's buffer rather than copying it.
+            if body.is_empty() {
+                body = chunk;
+            } else {
+                body.extend(chunk);
+            }
         }
         Ok(body)
     }
@@ -695,6 +740,9 @@ mod router;
 /// Exports HTTP Router items.
 pub use router::*;
 
+/// Helpers for checking a router against a stored API contract.
+pub mod contract;
+
 /// A Body extractor
 #[derive(Debug)]
 pub struct Body<T>(pub T);
@@ -708,7 +756,10 @@ impl<T> std::ops::Deref for Body<T> {
 }
 
 /// A Json extractor
-#[derive(Debug)]
+///
+/// The same type wraps JSON values elsewhere in the SDK: Redis message payloads and
+/// PostgreSQL and MySQL documents are decoded into, and encoded from, a `Json<T>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Json<T>(pub T);
 
 impl<T> std::ops::Deref for Json<T> {
@@ -0,0 +1,205 @@
+//! API contracts for [`Router`]s.
+//!
+//! A contract is a text file listing the routes an API promises to serve, one per line in the
+//! form `METHOD /path` (`*` for routes which accept any method). Lines starting with `#` are
+//! comments. Checking a router against a stored contract in a unit test catches breaking
+//! changes, such as removing or renaming a route, as part of `cargo test`:
+//!
+//! ```ignore
+//! #[test]
+//! fn api_is_compatible() {
+//!     let contract = RouteContract::parse(include_str!("../api.contract")).unwrap();
+//!     contract.assert_satisfied_by(&my_router());
+//! }
+//! ```
+//!
+//! Adding routes is not a breaking change, so routes missing from the contract are allowed.
+//! [`RouteContract::from_router`] can be used to write out an updated contract.
+//!
+//! [`RouteContract::from_router`]: crate::http::contract::RouteContract::from_router
+
+use std::fmt::Display;
+
+use super::{Method, Route, Router};
+
+/// A set of routes a router is expected to serve.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouteContract {
+    routes: Vec<Route>,
+}
+
+impl RouteContract {
+    /// Parse a contract from its text representation.
+    pub fn parse(contract: &str) -> Result<Self, ParseError> {
+        let routes = contract
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(number, line)| {
+                let (method, path) =
+                    line.split_once(char::is_whitespace)
+                        .ok_or_else(|| ParseError {
+                            line: number,
+                            reason: "expected `METHOD /path`".to_owned(),
+                        })?;
+                let path = path.trim();
+                if !path.starts_with('/') {
+                    return Err(ParseError {
+                        line: number,
+                        reason: format!("path `{path}` must start with `/`"),
+                    });
+                }
+                Ok(Route {
+                    method: parse_method(method),
+                    path: path.to_owned(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { routes })
+    }
+
+    /// Create a contract describing every route of `router`.
+    pub fn from_router(router: &Router) -> Self {
+        Self {
+            routes: router.routes(),
+        }
+    }
+
+    /// The routes in this contract.
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Check that `router` serves every route in this contract.
+    pub fn check(&self, router: &Router) -> Result<(), ContractViolation> {
+        let served = router.routes();
+        let missing = self
+            .routes
+            .iter()
+            .filter(|route| !served.contains(route))
+            .cloned()
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ContractViolation { missing })
+        }
+    }
+
+    /// Panic with a description of the breaking changes if `router` does not satisfy this
+    /// contract.
+    #[track_caller]
+    pub fn assert_satisfied_by(&self, router: &Router) {
+        if let Err(violation) = self.check(router) {
+            panic!(
+                "{violation}\nthe router currently serves:\n{}",
+                Self::from_router(router)
+            );
+        }
+    }
+}
+
+impl Display for RouteContract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for route in &self.routes {
+            writeln!(f, "{route}")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_method(method: &str) -> Option<Method> {
+    Some(match method.to_ascii_uppercase().as_str() {
+        "*" => return None,
+        "GET" => Method::Get,
+        "POST" => Method::Post,
+        "PUT" => Method::Put,
+        "DELETE" => Method::Delete,
+        "PATCH" => Method::Patch,
+        "HEAD" => Method::Head,
+        "OPTIONS" => Method::Options,
+        "CONNECT" => Method::Connect,
+        "TRACE" => Method::Trace,
+        _ => Method::Other(method.to_owned()),
+    })
+}
+
+/// An error parsing a [`RouteContract`].
+#[derive(Debug, thiserror::Error)]
+#[error("invalid contract on line {line}: {reason}")]
+pub struct ParseError {
+    line: usize,
+    reason: String,
+}
+
+/// The routes of a [`RouteContract`] which a router no longer serves.
+#[derive(Debug, thiserror::Error)]
+pub struct ContractViolation {
+    /// Routes in the contract which the router does not serve.
+    pub missing: Vec<Route>,
+}
+
+impl Display for ContractViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "the router no longer serves these contracted routes:")?;
+        for route in &self.missing {
+            writeln!(f, "- {route}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Params, Request, Response};
+    use super::*;
+
+    fn ok(_req: Request, _params: Params) -> Response {
+        Response::new(200, ())
+    }
+
+    fn router() -> Router {
+        let mut router = Router::new();
+        router.get("/users", ok);
+        router.get("/users/:id", ok);
+        router.delete("/users/:id", ok);
+        router
+    }
+
+    #[test]
+    fn satisfied() {
+        let contract = RouteContract::parse(
+            "# users API
+            GET /users
+            delete /users/:id
+            ",
+        )
+        .unwrap();
+        contract.check(&router()).unwrap();
+        contract.assert_satisfied_by(&router());
+    }
+
+    #[test]
+    fn missing_route_is_a_violation() {
+        let contract = RouteContract::parse("GET /users\nPUT /users/:id").unwrap();
+        let violation = contract.check(&router()).unwrap_err();
+        assert_eq!(violation.missing.len(), 1);
+        assert_eq!(violation.missing[0].to_string(), "PUT /users/:id");
+    }
+
+    #[test]
+    fn round_trips() {
+        let contract = RouteContract::from_router(&router());
+        assert_eq!(
+            RouteContract::parse(&contract.to_string()).unwrap(),
+            contract
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(RouteContract::parse("GET").is_err());
+        assert!(RouteContract::parse("GET users").is_err());
+    }
+}
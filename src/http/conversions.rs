@@ -243,6 +243,7 @@ impl<R: IntoResponse, E: IntoResponse> IntoResponse for std::result::Result<R, E
 
 impl IntoResponse for anyhow::Error {
     fn into_response(self) -> Response {
+        crate::observe::record_error(self.as_ref());
         let body = self.to_string();
         eprintln!("Handler returned an error: {}", body);
         let mut source = self.source();
@@ -260,6 +261,7 @@ impl IntoResponse for anyhow::Error {
 
 impl IntoResponse for Box<dyn std::error::Error> {
     fn into_response(self) -> Response {
+        crate::observe::record_error(self.as_ref());
         let body = self.to_string();
         eprintln!("Handler returned an error: {}", body);
         let mut source = self.source();
@@ -534,10 +536,11 @@ impl TryIntoOutgoingRequest for Request {
     type Error = anyhow::Error;
 
     fn try_into_outgoing_request(self) -> Result<(OutgoingRequest, Option<Vec<u8>>), Self::Error> {
-        let headers = self
+        let mut headers = self
             .headers()
             .map(|(k, v)| (k.to_owned(), v.as_bytes().to_owned()))
             .collect::<Vec<_>>();
+        crate::observe::inject(&mut headers);
         let request = OutgoingRequest::new(Headers::from_list(&headers)?);
         request
             .set_method(self.method())
@@ -582,11 +585,12 @@ where
 {
     type Error = anyhow::Error;
     fn try_into_outgoing_request(self) -> Result<(OutgoingRequest, Option<Vec<u8>>), Self::Error> {
-        let headers = self
+        let mut headers = self
             .headers()
             .into_iter()
             .map(|(n, v)| (n.as_str().to_owned(), v.as_bytes().to_owned()))
             .collect::<Vec<_>>();
+        crate::observe::inject(&mut headers);
         let request = OutgoingRequest::new(Headers::from_list(&headers)?);
         request
             .set_method(&self.method().clone().into())
@@ -15,7 +15,8 @@ use std::future::Future;
 use std::rc::Rc;
 use std::task::Poll;
 
-const READ_SIZE: u64 = 16 * 1024;
+/// The most bytes read from an incoming body at a time, unless a chunk size is given.
+pub(crate) const DEFAULT_READ_SIZE: u64 = 16 * 1024;
 
 pub(crate) fn outgoing_body(body: OutgoingBody) -> impl Sink<Vec<u8>, Error = StreamError> {
     struct Outgoing(Option<(OutputStream, OutgoingBody)>);
@@ -109,10 +110,17 @@ pub(crate) fn outgoing_request_send(
     })
 }
 
+/// Stream `body` in chunks of at most `chunk_size` bytes.
+///
+/// Each chunk is a buffer allocated by the `read` binding, which the stream hands over
+/// without copying.
 #[doc(hidden)]
 pub fn incoming_body(
     body: IncomingBody,
+    chunk_size: u64,
 ) -> impl Stream<Item = Result<Vec<u8>, io::streams::Error>> {
+    assert!(chunk_size > 0, "chunk size must not be zero");
+
     struct Incoming(Option<(InputStream, IncomingBody)>);
 
     impl Drop for Incoming {
@@ -130,7 +138,7 @@ pub fn incoming_body(
 
         move |context| {
             if let Some((stream, _)) = &pair.0 {
-                match stream.read(READ_SIZE) {
+                match stream.read(chunk_size) {
                     Ok(buffer) => {
                         if buffer.is_empty() {
                             spin_executor::push_waker(stream.subscribe(), context.waker().clone());
@@ -63,12 +63,50 @@ impl Display for Router {
     }
 }
 
+/// A route registered with a [`Router`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Route {
+    /// The method the route handles, or `None` if it handles all methods.
+    pub method: Option<Method>,
+    /// The path pattern of the route (e.g. `/users/:id`).
+    pub path: String,
+}
+
+impl Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.method {
+            Some(method) => write!(f, "{} {}", method, self.path),
+            None => write!(f, "* {}", self.path),
+        }
+    }
+}
+
 struct RouteMatch<'a> {
     params: Captures<'static, 'static>,
     handler: &'a dyn Handler,
 }
 
 impl Router {
+    /// The routes registered with this router, sorted by path and then method.
+    pub fn routes(&self) -> Vec<Route> {
+        let mut routes = self
+            .methods_map
+            .iter()
+            .flat_map(|(method, router)| {
+                router.iter().map(|(spec, _)| Route {
+                    method: Some(method.clone()),
+                    path: spec.to_string(),
+                })
+            })
+            .chain(self.any_methods.iter().map(|(spec, _)| Route {
+                method: None,
+                path: spec.to_string(),
+            }))
+            .collect::<Vec<_>>();
+        routes.sort_by_cached_key(|r| (r.path.clone(), r.to_string()));
+        routes
+    }
+
     /// Synchronously dispatches a request to the appropriate handler along with the URI parameters.
     pub fn handle<R>(&self, request: R) -> Response
     where
@@ -563,6 +601,25 @@ mod tests {
         assert_eq!(actual.as_str(), expected);
     }
 
+    #[test]
+    fn test_routes() {
+        let mut router = Router::default();
+        router.post("/users", echo_param);
+        router.get("/users/:id", echo_param);
+        router.get("/users", echo_param);
+        router.any("/*", echo_param);
+
+        let routes = router
+            .routes()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            routes,
+            vec!["* /*", "GET /users", "POST /users", "GET /users/:id"]
+        );
+    }
+
     #[test]
     fn test_ambiguous_wildcard_vs_star() {
         fn h1(_req: Request, _params: Params) -> anyhow::Result<Response> {
@@ -4,6 +4,9 @@
 //! ways (e.g. via an in-memory table, a local file, or a remote database). Details such as consistency model and
 //! durability will depend on the implementation and may vary from one to store to the next.
 
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
 use super::wit::v2::key_value;
 
 #[cfg(feature = "json")]
@@ -19,6 +22,204 @@ impl Store {
     pub fn open_default() -> Result<Self, Error> {
         Self::open("default")
     }
+
+    /// Return whether a value exists for the specified `key`, without fetching the value.
+    ///
+    /// This is equivalent to [`Store::exists`].
+    pub fn contains(&self, key: impl AsRef<str>) -> Result<bool, Error> {
+        self.exists(key.as_ref())
+    }
+
+    /// Start watching the keys which begin with `prefix` for changes.
+    ///
+    /// The key-value interface has no change notifications, so changes are detected by
+    /// comparing the store against a snapshot each time [`Watcher::poll`] is called. This fetches
+    /// every matching value, so it is best suited to small sets of keys such as cache entries.
+    pub fn watch(&self, prefix: impl Into<String>) -> Result<Watcher<'_>, Error> {
+        let prefix = prefix.into();
+        let snapshot = snapshot(self, &prefix)?;
+        Ok(Watcher {
+            store: self,
+            prefix,
+            snapshot,
+        })
+    }
+
+    /// Return a view of this store in which every key is prefixed with `prefix`.
+    ///
+    /// This allows several parts of a component to share a single store without their keys
+    /// colliding. No separator is inserted, so the prefix should usually end with one (e.g. `"sessions:"`).
+    pub fn namespaced(&self, prefix: impl Into<String>) -> NamespacedStore<'_> {
+        NamespacedStore {
+            store: self,
+            prefix: prefix.into(),
+        }
+    }
+}
+
+/// A view of a [`Store`] which transparently prefixes all keys.
+///
+/// Created with [`Store::namespaced`].
+pub struct NamespacedStore<'a> {
+    store: &'a Store,
+    prefix: String,
+}
+
+impl<'a> NamespacedStore<'a> {
+    /// The prefix applied to every key in this view.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Return a view nested under this one, prefixing keys with both prefixes.
+    pub fn namespaced(&self, prefix: impl AsRef<str>) -> NamespacedStore<'a> {
+        NamespacedStore {
+            store: self.store,
+            prefix: self.key(prefix.as_ref()),
+        }
+    }
+
+    /// Get the value associated with the specified `key`
+    ///
+    /// Returns `Ok(None)` if the key does not exist.
+    pub fn get(&self, key: impl AsRef<str>) -> Result<Option<Vec<u8>>, Error> {
+        self.store.get(&self.key(key.as_ref()))
+    }
+
+    /// Set the `value` associated with the specified `key` overwriting any existing value.
+    pub fn set(&self, key: impl AsRef<str>, value: &[u8]) -> Result<(), Error> {
+        self.store.set(&self.key(key.as_ref()), value)
+    }
+
+    /// Delete the tuple with the specified `key`
+    ///
+    /// No error is raised if a tuple did not previously exist for `key`.
+    pub fn delete(&self, key: impl AsRef<str>) -> Result<(), Error> {
+        self.store.delete(&self.key(key.as_ref()))
+    }
+
+    /// Return whether a tuple exists for the specified `key`
+    pub fn exists(&self, key: impl AsRef<str>) -> Result<bool, Error> {
+        self.store.exists(&self.key(key.as_ref()))
+    }
+
+    /// Return whether a value exists for the specified `key`, without fetching the value.
+    pub fn contains(&self, key: impl AsRef<str>) -> Result<bool, Error> {
+        self.exists(key)
+    }
+
+    /// Return a list of all the keys in this namespace, with the prefix removed.
+    pub fn get_keys(&self) -> Result<Vec<String>, Error> {
+        Ok(strip_prefix(&self.prefix, self.store.get_keys()?))
+    }
+
+    #[cfg(feature = "json")]
+    /// Serialize the given data to JSON, then set it as the value for the specified `key`.
+    pub fn set_json<T: Serialize>(
+        &self,
+        key: impl AsRef<str>,
+        value: &T,
+    ) -> Result<(), anyhow::Error> {
+        self.store.set_json(self.key(key.as_ref()), value)
+    }
+
+    #[cfg(feature = "json")]
+    /// Deserialize an instance of type `T` from the value of `key`.
+    pub fn get_json<T: DeserializeOwned>(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<Option<T>, anyhow::Error> {
+        self.store.get_json(self.key(key.as_ref()))
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+}
+
+/// A change to a watched key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The key was added to the store.
+    Inserted(String),
+    /// The value of the key changed.
+    Updated(String),
+    /// The key was removed from the store.
+    Deleted(String),
+}
+
+impl Change {
+    /// The key which changed.
+    pub fn key(&self) -> &str {
+        match self {
+            Change::Inserted(k) | Change::Updated(k) | Change::Deleted(k) => k,
+        }
+    }
+}
+
+/// Detects changes to the keys under a prefix.
+///
+/// Created with [`Store::watch`].
+pub struct Watcher<'a> {
+    store: &'a Store,
+    prefix: String,
+    snapshot: HashMap<String, u64>,
+}
+
+impl<'a> Watcher<'a> {
+    /// The prefix of the watched keys.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Return the changes since the watcher was created or last polled, ordered by key.
+    pub fn poll(&mut self) -> Result<Vec<Change>, Error> {
+        let current = snapshot(self.store, &self.prefix)?;
+        let changes = diff(&self.snapshot, &current);
+        self.snapshot = current;
+        Ok(changes)
+    }
+}
+
+fn snapshot(store: &Store, prefix: &str) -> Result<HashMap<String, u64>, Error> {
+    let mut snapshot = HashMap::new();
+    for key in store.get_keys()? {
+        if !key.starts_with(prefix) {
+            continue;
+        }
+        // The key may have been deleted since it was listed.
+        if let Some(value) = store.get(&key)? {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            snapshot.insert(key, hasher.finish());
+        }
+    }
+    Ok(snapshot)
+}
+
+fn diff(previous: &HashMap<String, u64>, current: &HashMap<String, u64>) -> Vec<Change> {
+    let mut changes = current
+        .iter()
+        .filter_map(|(key, hash)| match previous.get(key) {
+            None => Some(Change::Inserted(key.clone())),
+            Some(previous) if previous != hash => Some(Change::Updated(key.clone())),
+            Some(_) => None,
+        })
+        .chain(
+            previous
+                .keys()
+                .filter(|key| !current.contains_key(*key))
+                .map(|key| Change::Deleted(key.clone())),
+        )
+        .collect::<Vec<_>>();
+    changes.sort_by(|a, b| a.key().cmp(b.key()));
+    changes
+}
+
+fn strip_prefix(prefix: &str, keys: Vec<String>) -> Vec<String> {
+    keys.into_iter()
+        .filter_map(|k| k.strip_prefix(prefix).map(ToOwned::to_owned))
+        .collect()
 }
 
 impl Store {
@@ -44,3 +245,34 @@ impl Store {
         Ok(serde_json::from_slice(&value)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_prefix_filters_other_namespaces() {
+        let keys = vec![
+            "users:alice".to_owned(),
+            "sessions:1".to_owned(),
+            "users:bob".to_owned(),
+            "users".to_owned(),
+        ];
+        assert_eq!(strip_prefix("users:", keys), vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn diff_detects_changes() {
+        let previous = HashMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)]);
+        let current = HashMap::from([("b".to_owned(), 3), ("c".to_owned(), 4)]);
+        assert_eq!(
+            diff(&previous, &current),
+            vec![
+                Change::Deleted("a".to_owned()),
+                Change::Updated("b".to_owned()),
+                Change::Inserted("c".to_owned()),
+            ]
+        );
+        assert!(diff(&current, &current).is_empty());
+    }
+}
@@ -5,18 +5,41 @@
 #[cfg(test)]
 mod test;
 
+// Lets the derive macros refer to `::spin_sdk` in this crate's own tests.
+#[cfg(test)]
+extern crate self as spin_sdk;
+
 /// Key/Value storage.
 pub mod key_value;
 
 /// SQLite storage.
+///
+/// Statements are sent to the host as SQL text on every call. The `sqlite` interface has no
+/// prepare step, so a statement cannot be parsed once and executed repeatedly.
 pub mod sqlite;
 
 /// Large Language Model APIs
 pub mod llm;
 
+/// Database-independent row decoding.
+pub mod rows;
+
+#[cfg(feature = "json")]
+mod de;
+
+mod hash;
+mod host_error;
+mod transaction;
+
 /// Exports the procedural macros for writing handlers for Spin components.
 pub use spin_macro::*;
 
+#[doc(inline)]
+pub use spin_executor::{
+    copy, sleep, spawn_local, timeout, yield_now, CopyError, Elapsed, JoinHandle, PollableFuture,
+    Sleep, Timeout, YieldNow,
+};
+
 #[doc(hidden)]
 /// Module containing wit bindgen generated code.
 ///
@@ -59,45 +82,11 @@ pub mod http;
 
 /// Implementation of the spin mqtt interface.
 #[allow(missing_docs)]
-pub mod mqtt {
-    pub use super::wit::v2::mqtt::{Connection, Error, Payload, Qos};
-}
+pub mod mqtt;
 
 /// Implementation of the spin redis interface.
 #[allow(missing_docs)]
-pub mod redis {
-    use std::hash::{Hash, Hasher};
-
-    pub use super::wit::v2::redis::{Connection, Error, Payload, RedisParameter, RedisResult};
-
-    impl PartialEq for RedisResult {
-        fn eq(&self, other: &Self) -> bool {
-            use RedisResult::*;
-            match (self, other) {
-                (Nil, Nil) => true,
-                (Status(a), Status(b)) => a == b,
-                (Int64(a), Int64(b)) => a == b,
-                (Binary(a), Binary(b)) => a == b,
-                _ => false,
-            }
-        }
-    }
-
-    impl Eq for RedisResult {}
-
-    impl Hash for RedisResult {
-        fn hash<H: Hasher>(&self, state: &mut H) {
-            use RedisResult::*;
-
-            match self {
-                Nil => (),
-                Status(s) => s.hash(state),
-                Int64(v) => v.hash(state),
-                Binary(v) => v.hash(state),
-            }
-        }
-    }
-}
+pub mod redis;
 
 /// Implementation of the spin postgres db interface.
 pub mod pg;
@@ -108,8 +97,17 @@ pub mod pg3;
 /// Implementation of the Spin MySQL database interface.
 pub mod mysql;
 
-#[doc(inline)]
-pub use wit::v2::variables;
+/// Health metrics for database connections.
+pub mod db_health;
+
+pub mod observe;
+
+pub mod net;
+
+/// Application variables for the current component.
+pub mod variables;
+
+pub mod time;
 
 #[doc(hidden)]
 pub use wit_bindgen;
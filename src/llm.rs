@@ -3,6 +3,26 @@ pub use crate::wit::v2::llm::{
     InferencingUsage,
 };
 
+#[cfg(feature = "json")]
+mod conversation;
+mod embeddings;
+#[cfg(feature = "schema")]
+mod json;
+mod params;
+mod tokens;
+#[cfg(feature = "json")]
+mod tools;
+pub mod vector;
+#[cfg(feature = "json")]
+pub use conversation::{Conversation, History, KeyValueHistory, Role, Turn};
+pub use embeddings::{embed, Embedding, EmbeddingsInput};
+#[cfg(feature = "schema")]
+pub use json::{infer_json, JsonInferenceError};
+pub use params::InferencingParamsV2;
+pub use tokens::{count_tokens, count_tokens_exact};
+#[cfg(feature = "json")]
+pub use tools::{infer_with_tools, Reply, Tool, ToolCall, Tools};
+
 /// The model use for inferencing
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy)]
@@ -37,17 +57,26 @@ impl Default for InferencingParams {
 }
 
 /// Perform inferencing using the provided model and prompt
+///
+/// The host returns the completion once it has been generated in full. The `llm` interface
+/// cannot stream a completion, so text cannot be forwarded to a client as it is generated.
+///
+/// Inferencing blocks the component until the host returns, so it cannot overlap with other
+/// work such as outbound HTTP requests. The same holds for [`generate_embeddings`], which is
+/// why neither has an async variant.
 pub fn infer(model: InferencingModel, prompt: &str) -> Result<InferencingResult, Error> {
     llm::infer(&model.to_string(), prompt, None)
 }
 
 /// Perform inferencing using the provided model, prompt, and options
+///
+/// The options can be [`InferencingParams`] or [`InferencingParamsV2`].
 pub fn infer_with_options(
     model: InferencingModel,
     prompt: &str,
-    options: InferencingParams,
+    options: impl Into<InferencingParamsV2>,
 ) -> Result<InferencingResult, Error> {
-    llm::infer(&model.to_string(), prompt, Some(options))
+    params::infer(&model.to_string(), prompt, options.into())
 }
 
 /// Model used for generating embeddings
@@ -69,9 +98,12 @@ impl<'a> std::fmt::Display for EmbeddingModel<'a> {
 }
 
 /// Generate embeddings using the provided model and collection of text
+///
+/// The text can be a single string, or a slice, array or `Vec` of strings. To embed the texts
+/// of an iterator, collect them into a `Vec` or use [`embed`].
 pub fn generate_embeddings(
     model: EmbeddingModel,
-    text: &[String],
+    text: impl EmbeddingsInput,
 ) -> Result<llm::EmbeddingsResult, Error> {
-    llm::generate_embeddings(&model.to_string(), text)
+    llm::generate_embeddings(&model.to_string(), &text.into_texts())
 }
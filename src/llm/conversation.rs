@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+use super::{count_tokens, params, InferencingModel, InferencingParamsV2};
+use crate::key_value::Store;
+
+/// The token budget used when none is set, leaving room in a 4096 token context window for
+/// the reply.
+const DEFAULT_TOKEN_BUDGET: usize = 3072;
+
+/// Who said something in a [`Conversation`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// The person talking to the model.
+    User,
+    /// The model.
+    Assistant,
+}
+
+/// One message in a [`Conversation`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Turn {
+    /// Who said it.
+    pub role: Role,
+    /// What they said.
+    pub content: String,
+}
+
+/// Somewhere to keep the history of a [`Conversation`] between requests.
+pub trait History {
+    /// Load the history, or an empty one if none has been saved.
+    fn load(&self) -> anyhow::Result<Vec<Turn>>;
+    /// Replace the saved history.
+    fn save(&self, turns: &[Turn]) -> anyhow::Result<()>;
+}
+
+/// History kept as JSON under a key in a key-value [`Store`].
+#[derive(Debug)]
+pub struct KeyValueHistory<'a> {
+    store: &'a Store,
+    key: String,
+}
+
+impl<'a> KeyValueHistory<'a> {
+    /// Keep the history under `key` in `store`.
+    pub fn new(store: &'a Store, key: impl Into<String>) -> Self {
+        Self {
+            store,
+            key: key.into(),
+        }
+    }
+}
+
+impl History for KeyValueHistory<'_> {
+    fn load(&self) -> anyhow::Result<Vec<Turn>> {
+        Ok(self.store.get_json(&self.key)?.unwrap_or_default())
+    }
+
+    fn save(&self, turns: &[Turn]) -> anyhow::Result<()> {
+        self.store.set_json(&self.key, &turns)
+    }
+}
+
+/// A multi-turn conversation with a model, which remembers what has been said.
+///
+/// ```ignore
+/// let store = Store::open_default()?;
+/// let mut chat = Conversation::new(InferencingModel::Llama2Chat)
+///     .system("You are a helpful assistant for a bicycle shop.")
+///     .history(KeyValueHistory::new(&store, format!("chat:{session_id}")))?;
+/// let reply = chat.ask(&question)?;
+/// ```
+///
+/// Each question is sent along with the earlier turns as a transcript. The oldest turns are
+/// left out once the prompt would exceed the [token budget](Self::token_budget), and
+/// dropped from the saved history too, so the history does not grow without bound. Token
+/// counts are [estimated](super::count_tokens).
+pub struct Conversation<'a> {
+    model: InferencingModel<'a>,
+    system: Option<String>,
+    token_budget: usize,
+    params: InferencingParamsV2,
+    turns: Vec<Turn>,
+    history: Option<Box<dyn History + 'a>>,
+}
+
+impl<'a> Conversation<'a> {
+    /// Start a conversation with `model`.
+    pub fn new(model: InferencingModel<'a>) -> Self {
+        Self {
+            model,
+            system: None,
+            token_budget: DEFAULT_TOKEN_BUDGET,
+            params: InferencingParamsV2::default(),
+            turns: Vec::new(),
+            history: None,
+        }
+    }
+
+    /// Set instructions given to the model ahead of the conversation.
+    pub fn system(mut self, prompt: impl Into<String>) -> Self {
+        self.system = Some(prompt.into());
+        self
+    }
+
+    /// Set the most tokens a prompt may use, including the instructions and the new question.
+    pub fn token_budget(mut self, tokens: usize) -> Self {
+        self.token_budget = tokens;
+        self
+    }
+
+    /// Set the parameters used for each reply.
+    pub fn params(mut self, params: impl Into<InferencingParamsV2>) -> Self {
+        self.params = params.into();
+        self
+    }
+
+    /// Load the conversation so far from `history`, and save it there after each reply.
+    pub fn history(mut self, history: impl History + 'a) -> anyhow::Result<Self> {
+        self.turns = history.load()?;
+        self.history = Some(Box::new(history));
+        Ok(self)
+    }
+
+    /// The turns of the conversation so far.
+    pub fn turns(&self) -> &[Turn] {
+        &self.turns
+    }
+
+    /// Forget the conversation so far.
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.turns.clear();
+        self.save()
+    }
+
+    /// Ask the model `question`, returning its reply.
+    ///
+    /// If the conversation no longer fits the [token budget](Self::token_budget), the oldest
+    /// turns are left out of the prompt, and forgotten once the model has replied.
+    pub fn ask(&mut self, question: &str) -> anyhow::Result<String> {
+        self.turns.push(Turn {
+            role: Role::User,
+            content: question.to_owned(),
+        });
+        let excess = self.excess_turns();
+
+        let mut params = self.params.clone();
+        params.stop.push("\nUser:".to_owned());
+        let prompt = self.prompt(&self.turns[excess..]);
+        let result = match params::infer(&self.model.to_string(), &prompt, params) {
+            Ok(result) => result,
+            Err(e) => {
+                // Leave the question out so that it can be asked again, and keep the turns
+                // which did not fit, as the conversation has not moved on.
+                self.turns.pop();
+                return Err(e.into());
+            }
+        };
+        let reply = result.text.trim().to_owned();
+
+        self.turns.drain(..excess);
+        self.turns.push(Turn {
+            role: Role::Assistant,
+            content: reply.clone(),
+        });
+        self.save()?;
+        Ok(reply)
+    }
+
+    /// The transcript of `turns`, ending with a cue for the model's reply.
+    fn prompt(&self, turns: &[Turn]) -> String {
+        let mut prompt = String::new();
+        if let Some(system) = &self.system {
+            prompt.push_str(system);
+            prompt.push_str("\n\n");
+        }
+        for turn in turns {
+            let speaker = match turn.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+            };
+            prompt.push_str(&format!("{speaker}: {}\n", turn.content));
+        }
+        prompt.push_str("Assistant:");
+        prompt
+    }
+
+    /// How many of the oldest turns to leave out for the prompt to fit the budget, always
+    /// keeping the latest question.
+    fn excess_turns(&self) -> usize {
+        let mut excess = 0;
+        while excess + 1 < self.turns.len()
+            && count_tokens(self.model, &self.prompt(&self.turns[excess..])) > self.token_budget
+        {
+            excess += 1;
+        }
+        excess
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        match &self.history {
+            Some(history) => history.save(&self.turns),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(role: Role, content: &str) -> Turn {
+        Turn {
+            role,
+            content: content.to_owned(),
+        }
+    }
+
+    #[test]
+    fn trims_oldest_turns() {
+        let mut chat = Conversation::new(InferencingModel::Llama2Chat)
+            .system("Be brief.")
+            .token_budget(12);
+        chat.turns = vec![
+            turn(Role::User, "What colour is the sky?"),
+            turn(Role::Assistant, "Blue."),
+            turn(Role::User, "And grass?"),
+        ];
+        let excess = chat.excess_turns();
+        assert_eq!(excess, 2);
+        assert_eq!(
+            chat.prompt(&chat.turns[excess..]),
+            "Be brief.\n\nUser: And grass?\nAssistant:"
+        );
+        // Nothing is forgotten until the model has replied.
+        assert_eq!(chat.turns.len(), 3);
+    }
+}
@@ -0,0 +1,175 @@
+use super::tokens::word_tokens;
+use super::{llm, EmbeddingModel, Error};
+
+/// Text which embeddings can be generated for: a single string, or a slice, array or `Vec` of
+/// them.
+pub trait EmbeddingsInput {
+    /// The texts to send to the host.
+    fn into_texts(self) -> Vec<String>;
+}
+
+impl EmbeddingsInput for &str {
+    fn into_texts(self) -> Vec<String> {
+        vec![self.to_owned()]
+    }
+}
+
+impl EmbeddingsInput for String {
+    fn into_texts(self) -> Vec<String> {
+        vec![self]
+    }
+}
+
+impl<T: AsRef<str>> EmbeddingsInput for &[T] {
+    fn into_texts(self) -> Vec<String> {
+        self.iter().map(|text| text.as_ref().to_owned()).collect()
+    }
+}
+
+impl<T: AsRef<str>> EmbeddingsInput for &Vec<T> {
+    fn into_texts(self) -> Vec<String> {
+        self.as_slice().into_texts()
+    }
+}
+
+impl<T: AsRef<str>, const N: usize> EmbeddingsInput for &[T; N] {
+    fn into_texts(self) -> Vec<String> {
+        self.as_slice().into_texts()
+    }
+}
+
+impl<T: AsRef<str>, const N: usize> EmbeddingsInput for [T; N] {
+    fn into_texts(self) -> Vec<String> {
+        self.as_slice().into_texts()
+    }
+}
+
+impl<T: AsRef<str>> EmbeddingsInput for Vec<T> {
+    fn into_texts(self) -> Vec<String> {
+        self.as_slice().into_texts()
+    }
+}
+
+impl<'a> EmbeddingModel<'a> {
+    /// The most tokens the model embeds from one text, if known. Longer text is truncated by
+    /// the model.
+    pub fn max_input_tokens(&self) -> Option<usize> {
+        match self {
+            EmbeddingModel::AllMiniLmL6V2 => Some(256),
+            EmbeddingModel::Other(_) => None,
+        }
+    }
+}
+
+/// An embedding paired with the text it was generated from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embedding {
+    /// The text which was embedded.
+    pub text: String,
+    /// The index of the input the text came from.
+    pub source: usize,
+    /// The embedding vector.
+    pub vector: Vec<f32>,
+}
+
+/// Generate embeddings for each of `texts`, pairing each with the text it was generated from.
+///
+/// ```ignore
+/// let documents = kv.get_json::<Vec<String>>("documents")?.unwrap_or_default();
+/// for embedding in llm::embed(EmbeddingModel::AllMiniLmL6V2, &documents)? {
+///     index.insert(embedding.source, embedding.vector);
+/// }
+/// ```
+///
+/// Texts longer than the model's [input limit](EmbeddingModel::max_input_tokens) are split into
+/// chunks at word boundaries, each embedded separately, so there may be more embeddings than
+/// inputs; [`source`](Embedding::source) tells which input each came from. Token counts are
+/// estimated as by [`count_tokens`](super::count_tokens).
+pub fn embed<I>(model: EmbeddingModel, texts: I) -> Result<Vec<Embedding>, Error>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let max_tokens = model.max_input_tokens();
+    let mut chunks = Vec::new();
+    let mut sources = Vec::new();
+    for (source, text) in texts.into_iter().enumerate() {
+        for chunk in split(text.as_ref(), max_tokens) {
+            chunks.push(chunk);
+            sources.push(source);
+        }
+    }
+    if chunks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let result = llm::generate_embeddings(&model.to_string(), &chunks)?;
+    Ok(chunks
+        .into_iter()
+        .zip(sources)
+        .zip(result.embeddings)
+        .map(|((text, source), vector)| Embedding {
+            text,
+            source,
+            vector,
+        })
+        .collect())
+}
+
+/// Split `text` at word boundaries into chunks estimated to take at most `max_tokens` tokens.
+///
+/// A single word over the limit makes up a chunk of its own.
+fn split(text: &str, max_tokens: Option<usize>) -> Vec<String> {
+    let Some(max) = max_tokens.filter(|max| *max > 0) else {
+        return vec![text.to_owned()];
+    };
+    if text.split_whitespace().map(word_tokens).sum::<usize>() <= max {
+        return vec![text.to_owned()];
+    }
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::new();
+    let mut tokens = 0;
+    for word in text.split_whitespace() {
+        let count = word_tokens(word);
+        if !chunk.is_empty() && tokens + count > max {
+            chunks.push(chunk.join(" "));
+            chunk.clear();
+            tokens = 0;
+        }
+        chunk.push(word);
+        tokens += count;
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk.join(" "));
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_texts() {
+        assert_eq!("one".into_texts(), ["one"]);
+        assert_eq!(["a", "b"].into_texts(), ["a", "b"]);
+        assert_eq!(vec!["a".to_owned()].into_texts(), ["a"]);
+        assert_eq!((&["a".to_owned()]).into_texts(), ["a"]);
+
+        // Calls written against the old `&[String]` parameter still compile.
+        let _ = || {
+            crate::llm::generate_embeddings(EmbeddingModel::AllMiniLmL6V2, &["hello".to_string()])
+        };
+    }
+
+    #[test]
+    fn splits_long_text() {
+        assert_eq!(split("a b c d e", Some(2)), ["a b", "c d", "e"]);
+        assert_eq!(split("a  b", Some(2)), ["a  b"]);
+        assert_eq!(split("a b c", None), ["a b c"]);
+        assert_eq!(
+            split("tokenization is fun", Some(3)),
+            ["tokenization", "is fun"]
+        );
+    }
+}
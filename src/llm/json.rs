@@ -0,0 +1,89 @@
+use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{llm, Error, InferencingModel};
+
+/// Perform inferencing using the provided model and prompt, asking for a JSON reply matching
+/// the schema of `T` and deserializing it.
+///
+/// ```ignore
+/// #[derive(serde::Deserialize, schemars::JsonSchema)]
+/// struct Sentiment { positive: bool, confidence: f32 }
+///
+/// let sentiment: Sentiment = llm::infer_json(model, &format!("Classify: {review}"))?;
+/// ```
+///
+/// The `llm` interface cannot constrain generation to a grammar, so the schema is given to
+/// the model in the prompt and the reply is checked afterwards. Prose or code fences around
+/// the JSON are ignored. If the reply still cannot be deserialized, the model is asked once to
+/// repair it before [`JsonInferenceError::Invalid`] is returned.
+pub fn infer_json<T: JsonSchema + DeserializeOwned>(
+    model: InferencingModel,
+    prompt: &str,
+) -> Result<T, JsonInferenceError> {
+    let model = model.to_string();
+    let schema = serde_json::to_string(&schema_for!(T)).expect("schemas serialize to JSON");
+    let prompt = format!(
+        "{prompt}\n\nReply with only a JSON value matching this JSON schema, and no other text:\n\
+         {schema}"
+    );
+
+    let reply = llm::infer(&model, &prompt, None)?.text;
+    let error = match parse(&reply) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    let repair = format!(
+        "{prompt}\n\nYour previous reply was:\n{reply}\n\nIt was not valid: {error}. Reply again \
+         with only the corrected JSON."
+    );
+    let reply = llm::infer(&model, &repair, None)?.text;
+    parse(&reply).map_err(|source| JsonInferenceError::Invalid { reply, source })
+}
+
+/// Deserialize the first JSON object or array in `text`, or the whole of it if there is none.
+fn parse<T: DeserializeOwned>(text: &str) -> Result<T, serde_json::Error> {
+    let start = text.find(['{', '[']).unwrap_or(0);
+    let mut values = serde_json::Deserializer::from_str(&text[start..]).into_iter::<Value>();
+    match values.next() {
+        Some(value) => serde_json::from_value(value?),
+        None => serde_json::from_str(text),
+    }
+}
+
+/// An error from [`infer_json`].
+#[derive(Debug, thiserror::Error)]
+pub enum JsonInferenceError {
+    /// Inferencing failed.
+    #[error(transparent)]
+    Inference(#[from] Error),
+    /// The model's reply could not be deserialized, even after asking it to repair it.
+    #[error("the model did not reply with valid JSON: {source}")]
+    Invalid {
+        /// The model's last reply.
+        reply: String,
+        /// Why the reply could not be deserialized.
+        source: serde_json::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn parses_wrapped_json() {
+        let point: Point = parse("Here it is:\n```json\n{\"x\": 1, \"y\": 2}\n```").unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+        assert!(parse::<Point>("{\"x\": 1}").is_err());
+        assert!(parse::<bool>(" true ").unwrap());
+    }
+}
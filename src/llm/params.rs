@@ -0,0 +1,76 @@
+use super::{llm, Error, InferencingParams, InferencingResult};
+
+/// Inference request parameters, including stop sequences, which the `llm` interface does not
+/// define.
+///
+/// Any [`InferencingParams`] can be used where these are expected:
+///
+/// ```ignore
+/// let params = InferencingParamsV2 {
+///     params: InferencingParams { temperature: 0.0, ..Default::default() },
+///     stop: vec!["\nUser:".to_owned()],
+/// };
+/// let reply = llm::infer_with_options(InferencingModel::Llama2Chat, &prompt, params)?;
+/// ```
+///
+/// Stop sequences are applied by truncating the generated text at the first one found, so the
+/// model still generates up to `max_tokens` and the usage reports every token generated. The
+/// host has no seed parameter, so for repeatable output, set the temperature to zero.
+#[derive(Clone, Debug, Default)]
+pub struct InferencingParamsV2 {
+    /// The parameters supported by the `llm` interface.
+    pub params: InferencingParams,
+    /// Sequences which end the generated text. The sequence itself is not included.
+    pub stop: Vec<String>,
+}
+
+impl From<InferencingParams> for InferencingParamsV2 {
+    fn from(params: InferencingParams) -> Self {
+        Self {
+            params,
+            ..Default::default()
+        }
+    }
+}
+
+impl InferencingParamsV2 {
+    /// Cut `text` off at the first stop sequence.
+    fn truncate(&self, text: &mut String) {
+        let end = self
+            .stop
+            .iter()
+            .filter(|stop| !stop.is_empty())
+            .filter_map(|stop| text.find(stop.as_str()))
+            .min();
+        if let Some(end) = end {
+            text.truncate(end);
+        }
+    }
+}
+
+/// Perform inferencing with `options`, applying the parameters the host does not.
+pub(super) fn infer(
+    model: &String,
+    prompt: &str,
+    options: InferencingParamsV2,
+) -> Result<InferencingResult, Error> {
+    let mut result = llm::infer(model, prompt, Some(options.params))?;
+    options.truncate(&mut result.text);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_at_earliest_stop() {
+        let options = InferencingParamsV2 {
+            stop: vec!["\nUser:".to_owned(), "###".to_owned(), String::new()],
+            ..Default::default()
+        };
+        let mut text = "Hello!\n### notes\nUser: hi".to_owned();
+        options.truncate(&mut text);
+        assert_eq!(text, "Hello!\n");
+    }
+}
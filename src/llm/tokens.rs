@@ -0,0 +1,48 @@
+use super::{llm, Error, InferencingModel, InferencingParams};
+
+/// Estimate how many tokens `text` takes up in the model's context window.
+///
+/// The estimate is made without the model's tokenizer, assuming about four characters per
+/// token for ASCII text and one token per character otherwise, and is intended for budgeting
+/// prompts with some headroom rather than as an exact count. Use [`count_tokens_exact`] when
+/// the exact count matters.
+pub fn count_tokens(model: InferencingModel, text: &str) -> usize {
+    // Models differ in their vocabularies, but not by enough to change the estimate.
+    let _ = model;
+    text.split_whitespace().map(word_tokens).sum()
+}
+
+/// The estimated number of tokens in a single word, as counted by [`count_tokens`].
+pub(super) fn word_tokens(word: &str) -> usize {
+    let ascii = word.bytes().filter(u8::is_ascii).count();
+    let other = word.chars().filter(|c| !c.is_ascii()).count();
+    (ascii.div_ceil(4) + other).max(1)
+}
+
+/// Count how many tokens `text` takes up in the model's context window, using the model's
+/// tokenizer on the host.
+///
+/// The `llm` interface only reports token counts as part of inferencing, so this asks the
+/// model to generate a single token after `text` and returns the size of the prompt. That
+/// costs about as much as any other short inferencing request.
+pub fn count_tokens_exact(model: InferencingModel, text: &str) -> Result<u32, Error> {
+    let params = InferencingParams {
+        max_tokens: 1,
+        ..Default::default()
+    };
+    let result = llm::infer(&model.to_string(), text, Some(params))?;
+    Ok(result.usage.prompt_token_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_tokens() {
+        let model = InferencingModel::Llama2Chat;
+        assert_eq!(count_tokens(model, ""), 0);
+        assert_eq!(count_tokens(model, "a big elephant"), 4);
+        assert_eq!(count_tokens(model, "こんにちは"), 5);
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::{params, Error, InferencingModel, InferencingParamsV2};
+
+/// A tool the model may ask to call, described by a JSON schema for its arguments.
+#[derive(Clone, Debug)]
+pub struct Tool {
+    name: String,
+    description: String,
+    parameters: Value,
+}
+
+impl Tool {
+    /// Describe a tool called `name`, taking arguments matching the JSON schema `parameters`.
+    pub fn new(name: impl Into<String>, description: impl Into<String>, parameters: Value) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
+        }
+    }
+
+    /// The name of the tool.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The tools offered to the model for a request.
+///
+/// The `llm` interface has no notion of tools, so they are described to the model in the
+/// prompt, and a reply consisting of a JSON object naming one of them is taken as a call:
+///
+/// ```ignore
+/// let tools = Tools::new().with(Tool::new(
+///     "get_weather",
+///     "Get the current weather for a city",
+///     json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+/// ));
+/// match llm::infer_with_tools(model, &question, &tools, InferencingParams::default())? {
+///     Reply::ToolCall(call) => {
+///         let Weather { city } = call.arguments()?;
+///         let prompt = tools.follow_up(&question, &call, &weather_for(&city))?;
+///         let answer = llm::infer(model, &prompt)?.text;
+///     }
+///     Reply::Text(answer) => { ... }
+/// }
+/// ```
+///
+/// How reliably a model follows the format depends on the model; one which was not trained
+/// for tool use may answer in prose instead.
+#[derive(Clone, Debug, Default)]
+pub struct Tools {
+    tools: Vec<Tool>,
+}
+
+impl Tools {
+    /// Create an empty set of tools.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tool.
+    pub fn with(mut self, tool: Tool) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// Build the prompt describing the tools, followed by `prompt`.
+    pub fn prompt(&self, prompt: &str) -> String {
+        let mut full = String::from(
+            "You can call the tools listed below. To call a tool, reply with only a JSON object \
+             of the form {\"tool\": \"<name>\", \"arguments\": {...}}, with arguments matching \
+             the tool's schema. Otherwise, answer directly.\n\nTools:\n",
+        );
+        for tool in &self.tools {
+            full.push_str(&format!(
+                "- {}: {} Arguments schema: {}\n",
+                tool.name, tool.description, tool.parameters
+            ));
+        }
+        full.push('\n');
+        full.push_str(prompt);
+        full
+    }
+
+    /// Interpret the model's reply to a prompt built by [`prompt`](Self::prompt).
+    ///
+    /// The first JSON object in `text` naming one of the tools is taken as a call, so replies
+    /// which wrap the object in prose or code fences are still recognised.
+    pub fn parse(&self, text: &str) -> Reply {
+        self.find_call(text)
+            .map(Reply::ToolCall)
+            .unwrap_or_else(|| Reply::Text(text.to_owned()))
+    }
+
+    /// Build the prompt for the turn after `call`, giving the model the tool's `result`.
+    pub fn follow_up(
+        &self,
+        prompt: &str,
+        call: &ToolCall,
+        result: &impl Serialize,
+    ) -> Result<String, serde_json::Error> {
+        let request = serde_json::json!({ "tool": call.name, "arguments": call.arguments });
+        Ok(format!(
+            "{}\n\nYou called: {}\nThe tool returned: {}\n\nUse the result to answer directly.",
+            self.prompt(prompt),
+            request,
+            serde_json::to_string(result)?
+        ))
+    }
+
+    fn find_call(&self, text: &str) -> Option<ToolCall> {
+        text.match_indices('{').find_map(|(start, _)| {
+            let mut values = serde_json::Deserializer::from_str(&text[start..]).into_iter();
+            let Some(Ok(Value::Object(mut object))) = values.next() else {
+                return None;
+            };
+            let name = object.get("tool")?.as_str()?.to_owned();
+            if !self.tools.iter().any(|tool| tool.name == name) {
+                return None;
+            }
+            let arguments = object.remove("arguments").unwrap_or(Value::Null);
+            Some(ToolCall { name, arguments })
+        })
+    }
+}
+
+/// A model's reply to a prompt offering tools.
+#[derive(Clone, Debug)]
+pub enum Reply {
+    /// The model answered directly.
+    Text(String),
+    /// The model asked to call a tool.
+    ToolCall(ToolCall),
+}
+
+/// A request from the model to call a tool.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToolCall {
+    name: String,
+    arguments: Value,
+}
+
+impl ToolCall {
+    /// The name of the tool to call.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Deserialize the arguments to call the tool with.
+    pub fn arguments<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.arguments)
+    }
+}
+
+/// Perform inferencing offering `tools` to the model, and interpret its reply.
+pub fn infer_with_tools(
+    model: InferencingModel,
+    prompt: &str,
+    tools: &Tools,
+    options: impl Into<InferencingParamsV2>,
+) -> Result<Reply, Error> {
+    let result = params::infer(&model.to_string(), &tools.prompt(prompt), options.into())?;
+    Ok(tools.parse(&result.text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools() -> Tools {
+        Tools::new().with(Tool::new(
+            "add",
+            "Add two numbers.",
+            serde_json::json!({ "type": "object" }),
+        ))
+    }
+
+    #[test]
+    fn finds_tool_calls() {
+        let reply = tools().parse(
+            "Sure, {not json} here you go:\n```json\n{\"tool\": \"add\", \"arguments\": {\"a\": 1, \"b\": 2}}\n```",
+        );
+        let Reply::ToolCall(call) = reply else {
+            panic!("expected a tool call, got {reply:?}");
+        };
+        assert_eq!(call.name(), "add");
+        #[derive(serde::Deserialize)]
+        struct Args {
+            a: i32,
+            b: i32,
+        }
+        let args: Args = call.arguments().unwrap();
+        assert_eq!(args.a + args.b, 3);
+    }
+
+    #[test]
+    fn ignores_unknown_tools() {
+        let reply = tools().parse(r#"{"tool": "delete_everything", "arguments": {}}"#);
+        assert!(matches!(reply, Reply::Text(_)));
+    }
+}
@@ -0,0 +1,100 @@
+//! Operations on embedding vectors.
+//!
+//! ```ignore
+//! let query = llm::embed(EmbeddingModel::AllMiniLmL6V2, [question])?.remove(0).vector;
+//! for (index, similarity) in vector::top_k(&query, &document_vectors, 3) {
+//!     println!("{similarity:.3} {}", documents[index]);
+//! }
+//! ```
+
+/// The dot product of `a` and `b`.
+///
+/// # Panics
+///
+/// Panics if the vectors have different lengths.
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// The Euclidean length of `v`.
+pub fn magnitude(v: &[f32]) -> f32 {
+    v.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+/// Scale `v` to unit length, leaving a zero vector unchanged.
+pub fn normalize(v: &mut [f32]) {
+    let magnitude = magnitude(v);
+    if magnitude > 0.0 {
+        v.iter_mut().for_each(|x| *x /= magnitude);
+    }
+}
+
+/// The cosine of the angle between `a` and `b`, from -1 for opposite vectors to 1 for vectors
+/// pointing the same way.
+///
+/// The similarity involving a zero vector is 0 rather than NaN.
+///
+/// # Panics
+///
+/// Panics if the vectors have different lengths.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let magnitudes = magnitude(a) * magnitude(b);
+    if magnitudes == 0.0 {
+        return 0.0;
+    }
+    dot(a, b) / magnitudes
+}
+
+/// The `k` candidates most similar to `query` by cosine similarity, as their indexes and
+/// similarities, most similar first.
+///
+/// Fewer than `k` results are returned if there are fewer candidates. Candidates with equal
+/// similarity keep their order.
+///
+/// # Panics
+///
+/// Panics if any candidate has a different length from `query`.
+pub fn top_k(query: &[f32], candidates: &[Vec<f32>], k: usize) -> Vec<(usize, f32)> {
+    let mut scored = candidates
+        .iter()
+        .map(|candidate| cosine_similarity(query, candidate))
+        .enumerate()
+        .collect::<Vec<_>>();
+    // NaN can only come from NaN components; rank it below every real similarity.
+    scored.sort_by(|(_, a), (_, b)| match (a.is_nan(), b.is_nan()) {
+        (false, false) => b.total_cmp(a),
+        (a_nan, b_nan) => a_nan.cmp(&b_nan),
+    });
+    scored.truncate(k);
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_similarity() {
+        assert_eq!(dot(&[1.0, 2.0], &[3.0, 4.0]), 11.0);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), 1.0);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 0.0]), 0.0);
+        let mut v = [3.0, 4.0];
+        normalize(&mut v);
+        assert_eq!(v, [0.6, 0.8]);
+    }
+
+    #[test]
+    fn ranks_candidates() {
+        let candidates = vec![
+            vec![0.0, 1.0],
+            vec![f32::NAN, 1.0],
+            vec![1.0, 0.1],
+            vec![1.0, 0.0],
+        ];
+        let ranked = top_k(&[1.0, 0.0], &candidates, 3);
+        let indexes = ranked.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+        assert_eq!(indexes, [3, 2, 0]);
+        assert_eq!(top_k(&[1.0, 0.0], &candidates, 10).len(), 4);
+    }
+}
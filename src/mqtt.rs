@@ -0,0 +1,27 @@
+//! Publishing to and receiving from MQTT brokers.
+//!
+//! # Receiving messages
+//!
+//! The `mqtt` interface only supports publishing: a [`Connection`](crate::mqtt::Connection) cannot subscribe to topics
+//! or wait for messages, so a request cannot publish a command and then await the reply on
+//! another topic. Messages are received by an [`mqtt_component`](crate::mqtt_component)
+//! instead, which the MQTT trigger calls for each message on the topics it subscribes to.
+//!
+//! A request/response exchange can be split across the two: the HTTP handler publishes the
+//! command with a correlation id in the payload and returns, and the MQTT component stores
+//! the reply under that id in a [key-value store](crate::key_value), where later requests can
+//! find it.
+//!
+//! # Publishing
+//!
+//! Messages are published with a quality of service only: the `mqtt` interface cannot set the
+//! retain or duplicate flags.
+
+pub use super::wit::v2::mqtt::{Connection, Error, Payload, Qos};
+
+mod builder;
+mod error;
+mod message;
+pub use builder::{BuildError, ConnectionBuilder};
+pub use error::ErrorKind;
+pub use message::Message;
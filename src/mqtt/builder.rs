@@ -0,0 +1,273 @@
+use std::time::Duration;
+
+use super::{Connection, Error, ErrorKind};
+
+/// The keep-alive interval used when none is set.
+const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Structured settings for opening a [`Connection`], as an alternative to the positional
+/// arguments of [`Connection::open`].
+///
+/// ```ignore
+/// let conn = ConnectionBuilder::new("mqtt://broker.example.com:8883")
+///     .client_id("orders")
+///     .clean_session(false)
+///     .reconnect(3)
+///     .username("orders")
+///     .password(&variables::get("mqtt_password")?)
+///     .tls(true)
+///     .open()?;
+/// ```
+///
+/// # Certificates
+///
+/// Enabling [`tls`](Self::tls) connects with the `mqtts` scheme, and the host verifies the
+/// broker's certificate against its own trusted roots. The `mqtt` interface has no way to pass
+/// certificate material to the host, so brokers with a private CA, or which require mutual
+/// TLS, cannot be connected to. Brokers which require mutual TLS, such as AWS IoT Core, usually
+/// also accept username and password authentication on a separate endpoint.
+///
+/// A last will cannot be passed to the host either. Instead, publishers can periodically
+/// publish a status with a timestamp, and consumers can treat a stale one as offline.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionBuilder {
+    address: String,
+    client_id: Option<String>,
+    clean_session: Option<bool>,
+    reconnect_attempts: u32,
+    username: Option<String>,
+    password: Option<String>,
+    keep_alive: Option<Duration>,
+    tls: Option<bool>,
+}
+
+impl ConnectionBuilder {
+    /// Create a builder connecting to the broker at `address`, such as
+    /// `mqtt://localhost:1883`.
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the identifier the broker knows the client by.
+    ///
+    /// Brokers only keep one connection per client id, so components which may run
+    /// concurrently need distinct ids unless they use a clean session.
+    pub fn client_id(&mut self, client_id: impl Into<String>) -> &mut Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// Set whether the broker discards the client's session, including its subscriptions and
+    /// undelivered messages, when the connection opens. `true` by default.
+    ///
+    /// MQTT 5 calls this "clean start".
+    pub fn clean_session(&mut self, clean: bool) -> &mut Self {
+        self.clean_session = Some(clean);
+        self
+    }
+
+    /// Set how many more times [`open`](Self::open) tries to connect when the broker cannot
+    /// be reached. No retries are made by default.
+    ///
+    /// Only opening is retried: a connection which is lost later reports the error from
+    /// [`Connection::publish`], and a new connection has to be opened.
+    pub fn reconnect(&mut self, attempts: u32) -> &mut Self {
+        self.reconnect_attempts = attempts;
+        self
+    }
+
+    /// Set the user name to authenticate as.
+    pub fn username(&mut self, username: impl Into<String>) -> &mut Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Set the password to authenticate with.
+    pub fn password(&mut self, password: impl Into<String>) -> &mut Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Set how often the connection is kept alive, in whole seconds. 30 seconds by default.
+    pub fn keep_alive(&mut self, interval: Duration) -> &mut Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+
+    /// Set whether the connection uses TLS, overriding the scheme of the address.
+    ///
+    /// The broker's certificate is verified against the host's trusted roots.
+    pub fn tls(&mut self, enabled: bool) -> &mut Self {
+        self.tls = Some(enabled);
+        self
+    }
+
+    /// The address to connect to, with its scheme set for the TLS setting and the session
+    /// settings added to its query.
+    pub fn address(&self) -> Result<String, BuildError> {
+        self.validate()?;
+        let (scheme, rest) = self.address.split_once("://").unwrap();
+        let secure = matches!(scheme, "mqtts" | "ssl");
+        let mut address = match self.tls {
+            Some(true) if !secure => format!("mqtts://{rest}"),
+            Some(false) if secure => format!("mqtt://{rest}"),
+            _ => self.address.clone(),
+        };
+
+        let mut params = Vec::new();
+        if let Some(client_id) = &self.client_id {
+            params.push(("client_id", client_id.clone()));
+        }
+        if let Some(clean) = self.clean_session {
+            params.push(("clean_session", clean.to_string()));
+        }
+        for (key, value) in params {
+            let separator = if address.contains('?') { '&' } else { '?' };
+            address.push_str(&format!("{separator}{key}={value}"));
+        }
+        Ok(address)
+    }
+
+    /// Open a connection with these settings.
+    pub fn open(&self) -> Result<Connection, BuildError> {
+        let address = self.address()?;
+        let keep_alive = self.keep_alive.unwrap_or(DEFAULT_KEEP_ALIVE).as_secs();
+        let mut attempts = self.reconnect_attempts;
+        loop {
+            match Connection::open(
+                &address,
+                self.username.as_deref().unwrap_or_default(),
+                self.password.as_deref().unwrap_or_default(),
+                keep_alive,
+            ) {
+                Err(e) if attempts > 0 && e.kind() == ErrorKind::Connectivity => attempts -= 1,
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), BuildError> {
+        let invalid = |option, reason: &str| {
+            Err(BuildError::Invalid {
+                option,
+                reason: reason.to_owned(),
+            })
+        };
+        match self.address.split_once("://") {
+            Some(("mqtt" | "mqtts" | "tcp" | "ssl", rest)) if !rest.is_empty() => {}
+            _ => {
+                return invalid(
+                    "address",
+                    "must be a URL with an `mqtt`, `mqtts`, `tcp` or `ssl` scheme",
+                )
+            }
+        }
+        let query = self.address.split_once('?').map(|(_, query)| query);
+        for (option, is_set) in [
+            ("client_id", self.client_id.is_some()),
+            ("clean_session", self.clean_session.is_some()),
+        ] {
+            let prefix = format!("{option}=");
+            if is_set && query.is_some_and(|q| q.split('&').any(|p| p.starts_with(&prefix))) {
+                return invalid(option, "it is also set in the address");
+            }
+        }
+        if let Some(client_id) = &self.client_id {
+            if client_id.is_empty()
+                || !client_id
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+            {
+                return invalid(
+                    "client_id",
+                    "must be letters, digits, `-`, `_` and `.` only",
+                );
+            }
+        }
+        if self.password.is_some() && self.username.is_none() {
+            return invalid("password", "a username must be set with it");
+        }
+        if self.keep_alive.is_some_and(|k| k.as_secs() == 0) {
+            return invalid("keep_alive", "must be at least one second");
+        }
+        Ok(())
+    }
+}
+
+/// An error opening a connection from [`ConnectionBuilder`].
+#[derive(Debug, thiserror::Error)]
+pub enum BuildError {
+    /// An option has an invalid value.
+    #[error("invalid `{option}` option: {reason}")]
+    Invalid {
+        /// The name of the option.
+        option: &'static str,
+        /// Why the value is invalid.
+        reason: String,
+    },
+    /// The connection could not be opened.
+    #[error(transparent)]
+    Connect(#[from] Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_scheme_for_tls() {
+        let address = ConnectionBuilder::new("mqtt://broker:8883?client_id=app")
+            .tls(true)
+            .address()
+            .unwrap();
+        assert_eq!(address, "mqtts://broker:8883?client_id=app");
+        let address = ConnectionBuilder::new("ssl://broker:1883")
+            .tls(false)
+            .address()
+            .unwrap();
+        assert_eq!(address, "mqtt://broker:1883");
+        let address = ConnectionBuilder::new("ssl://broker:8883")
+            .address()
+            .unwrap();
+        assert_eq!(address, "ssl://broker:8883");
+    }
+
+    #[test]
+    fn adds_session_settings() {
+        let address = ConnectionBuilder::new("mqtt://broker:1883?conn_timeout_secs=5")
+            .client_id("sensor-7")
+            .clean_session(false)
+            .address()
+            .unwrap();
+        assert_eq!(
+            address,
+            "mqtt://broker:1883?conn_timeout_secs=5&client_id=sensor-7&clean_session=false"
+        );
+        let err = ConnectionBuilder::new("mqtt://broker?client_id=a")
+            .client_id("b")
+            .address()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Invalid {
+                option: "client_id",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn validates() {
+        let err = ConnectionBuilder::new("broker:1883").address().unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Invalid {
+                option: "address",
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+use super::{Connection, Error, Payload, Qos};
+use crate::host_error::is_connection_failure;
+
+/// The broad cause of an [`Error`], for deciding whether an operation is worth retrying.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The connection settings are wrong, such as a malformed address. Retrying will not help.
+    Configuration,
+    /// The broker could not be reached or the connection was lost. Retrying may succeed.
+    Connectivity,
+    /// The broker refused the operation, for example rejecting the topic or failing to
+    /// complete the QoS handshake.
+    Protocol,
+}
+
+impl Error {
+    /// The broad cause of the error.
+    ///
+    /// The `mqtt` interface reports most failures as text, so I/O failures are recognised by
+    /// the messages the host reports for them and other failures are taken to come from the
+    /// broker.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::InvalidAddress => ErrorKind::Configuration,
+            Error::TooManyConnections | Error::ConnectionFailed(_) => ErrorKind::Connectivity,
+            Error::Other(message) if is_connection_failure(message) => ErrorKind::Connectivity,
+            Error::Other(_) => ErrorKind::Protocol,
+        }
+    }
+}
+
+impl Connection {
+    /// Publish a message with QoS 2, so the broker delivers it to each subscriber exactly
+    /// once.
+    ///
+    /// How much of the QoS 2 handshake has completed when the call returns is up to the host.
+    /// On failure, [`Error::kind`] tells a lost connection, after which the message may or may
+    /// not have been delivered, from the broker refusing it.
+    pub fn publish_exactly_once(&self, topic: &str, payload: &Payload) -> Result<(), Error> {
+        self.publish(topic, payload, Qos::ExactlyOnce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_errors() {
+        assert_eq!(Error::InvalidAddress.kind(), ErrorKind::Configuration);
+        assert_eq!(
+            Error::ConnectionFailed("address not allowed".to_owned()).kind(),
+            ErrorKind::Connectivity
+        );
+        assert_eq!(
+            Error::Other("I/O: Connection reset by peer (os error 104)".to_owned()).kind(),
+            ErrorKind::Connectivity
+        );
+        assert_eq!(
+            Error::Other("Unexpected PubRec packet for unknown pkid 7".to_owned()).kind(),
+            ErrorKind::Protocol
+        );
+    }
+}
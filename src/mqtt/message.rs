@@ -0,0 +1,35 @@
+use bytes::Bytes;
+
+/// A message received by an [`mqtt_component`](crate::mqtt_component).
+///
+/// The MQTT trigger passes only the topic and payload of each message to the component.
+#[derive(Clone, Debug)]
+pub struct Message {
+    topic: String,
+    payload: Bytes,
+}
+
+impl Message {
+    /// Create a message received on `topic`.
+    pub fn new(topic: impl Into<String>, payload: impl Into<Bytes>) -> Self {
+        Self {
+            topic: topic.into(),
+            payload: payload.into(),
+        }
+    }
+
+    /// The topic the message was published to.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// The message payload.
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Take the payload, discarding the rest of the message.
+    pub fn into_payload(self) -> Bytes {
+        self.payload
+    }
+}
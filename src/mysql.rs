@@ -17,12 +17,39 @@
 //! | `f64`     | floating64(float64) | DOUBLE                  |
 //! | `String`  | str(string)         | VARCHAR, CHAR, TEXT     |
 //! | `Vec<u8>` | binary(list\<u8\>)  | VARBINARY, BINARY, BLOB |
+//! | `rust_decimal::Decimal` | str(string) | DECIMAL, NUMERIC    |
+//!
+//! Unsigned integers decode from any integer value which fits, so `COUNT(*)` can be read as a
+//! `u64`. `rust_decimal::Decimal` requires the `decimal` feature.
+//!
+//! # JSON
+//!
+//! With the `json` feature, `serde_json::Value` and [`Json<T>`] (for any `T` implementing the
+//! serde traits) can be decoded from `JSON` columns, whether the host returns documents as text
+//! or as bytes, and used as parameters. Documents are sent as text, which MySQL converts when
+//! storing them in a `JSON` column.
+//!
+//! [`Json<T>`]: crate::mysql::Json
 
 #[doc(inline)]
 pub use super::wit::v2::mysql::{Connection, Error as MysqlError};
 #[doc(inline)]
 pub use super::wit::v2::rdbms_types::*;
 
+#[cfg(feature = "json")]
+mod de;
+mod exec;
+mod insert;
+mod options;
+mod transaction;
+#[cfg(feature = "json")]
+pub use crate::de::row::DeserializeError;
+#[cfg(feature = "json")]
+pub use crate::http::Json;
+pub use exec::ExecResult;
+pub use options::{ConnectionOptions, OptionsError, SslMode};
+pub use transaction::Transaction;
+
 /// A MySQL error
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -101,42 +128,50 @@ impl Decode for i64 {
     }
 }
 
+/// Decode an unsigned integer from any integer value which fits, as MySQL widens unsigned
+/// columns in some expressions (such as `SUM`) and returns signed results for others (such as
+/// `COUNT`).
+fn decode_unsigned<T: TryFrom<u64>>(value: &DbValue, types: &str) -> Result<T, Error> {
+    let n = match value {
+        DbValue::Uint8(n) => Some(u64::from(*n)),
+        DbValue::Uint16(n) => Some(u64::from(*n)),
+        DbValue::Uint32(n) => Some(u64::from(*n)),
+        DbValue::Uint64(n) => Some(*n),
+        DbValue::Int8(n) => u64::try_from(*n).ok(),
+        DbValue::Int16(n) => u64::try_from(*n).ok(),
+        DbValue::Int32(n) => u64::try_from(*n).ok(),
+        DbValue::Int64(n) => u64::try_from(*n).ok(),
+        _ => return Err(Error::Decode(format_decode_err(types, value))),
+    };
+    n.and_then(|n| T::try_from(n).ok()).ok_or_else(|| {
+        Error::Decode(format!(
+            "{value:?} is out of range for {}",
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
 impl Decode for u8 {
     fn decode(value: &DbValue) -> Result<Self, Error> {
-        match value {
-            DbValue::Uint8(n) => Ok(*n),
-            _ => Err(Error::Decode(format_decode_err("UNSIGNED TINYINT", value))),
-        }
+        decode_unsigned(value, "UNSIGNED TINYINT")
     }
 }
 
 impl Decode for u16 {
     fn decode(value: &DbValue) -> Result<Self, Error> {
-        match value {
-            DbValue::Uint16(n) => Ok(*n),
-            _ => Err(Error::Decode(format_decode_err("UNSIGNED SMALLINT", value))),
-        }
+        decode_unsigned(value, "UNSIGNED SMALLINT")
     }
 }
 
 impl Decode for u32 {
     fn decode(value: &DbValue) -> Result<Self, Error> {
-        match value {
-            DbValue::Uint32(n) => Ok(*n),
-            _ => Err(Error::Decode(format_decode_err(
-                "UNISIGNED MEDIUMINT, UNSIGNED INT",
-                value,
-            ))),
-        }
+        decode_unsigned(value, "UNSIGNED MEDIUMINT, UNSIGNED INT")
     }
 }
 
 impl Decode for u64 {
     fn decode(value: &DbValue) -> Result<Self, Error> {
-        match value {
-            DbValue::Uint64(n) => Ok(*n),
-            _ => Err(Error::Decode(format_decode_err("UNSIGNED BIGINT", value))),
-        }
+        decode_unsigned(value, "UNSIGNED BIGINT")
     }
 }
 
@@ -179,14 +214,217 @@ impl Decode for String {
     }
 }
 
+#[cfg(feature = "json")]
+impl Decode for serde_json::Value {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        Json::decode(value).map(|Json(v)| v)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Value> for ParameterValue {
+    fn from(v: serde_json::Value) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+/// Decode a document stored in a `JSON` column, whether the host returns it as text or bytes.
+///
+/// ```ignore
+/// #[derive(serde::Deserialize)]
+/// struct Settings { theme: String }
+///
+/// let rows = conn.query("SELECT settings FROM users WHERE id = ?", &[ParameterValue::Int32(id)])?;
+/// let Json(settings) = Json::<Settings>::decode(&rows.rows[0][0])?;
+/// ```
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> Decode for Json<T> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        let json = match value {
+            DbValue::Str(s) => s.as_bytes(),
+            DbValue::Binary(b) => b,
+            _ => return Err(Error::Decode(format_decode_err("JSON", value))),
+        };
+        serde_json::from_slice(json)
+            .map(Json)
+            .map_err(|e| Error::Decode(format!("invalid JSON: {e}")))
+    }
+}
+
+/// Fails if `T`'s `Serialize` implementation fails, for example for a map with non-string keys.
+#[cfg(feature = "json")]
+impl<T: serde::Serialize> TryFrom<Json<T>> for ParameterValue {
+    type Error = serde_json::Error;
+
+    fn try_from(Json(v): Json<T>) -> Result<Self, Self::Error> {
+        serde_json::to_string(&v).map(ParameterValue::Str)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Decode for rust_decimal::Decimal {
+    /// Decode a `DECIMAL`, which MySQL sends as text to avoid losing precision.
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        let text = match value {
+            DbValue::Str(s) => s.as_str(),
+            DbValue::Binary(b) => std::str::from_utf8(b)
+                .map_err(|_| Error::Decode(format_decode_err("DECIMAL", value)))?,
+            DbValue::Int64(n) => return Ok((*n).into()),
+            DbValue::Uint64(n) => return Ok((*n).into()),
+            DbValue::Int32(n) => return Ok((*n).into()),
+            DbValue::Uint32(n) => return Ok((*n).into()),
+            _ => return Err(Error::Decode(format_decode_err("DECIMAL", value))),
+        };
+        text.parse()
+            .map_err(|e| Error::Decode(format!("invalid DECIMAL {text:?}: {e}")))
+    }
+}
+
+macro_rules! impl_parameter_value_conversions {
+    ($($ty:ty => $id:ident),*) => {
+        $(
+            impl From<$ty> for ParameterValue {
+                fn from(v: $ty) -> ParameterValue {
+                    ParameterValue::$id(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_parameter_value_conversions! {
+    i8 => Int8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    u8 => Uint8,
+    u16 => Uint16,
+    u32 => Uint32,
+    u64 => Uint64,
+    f32 => Floating32,
+    f64 => Floating64,
+    bool => Boolean,
+    String => Str,
+    Vec<u8> => Binary
+}
+
+impl From<&str> for ParameterValue {
+    fn from(v: &str) -> ParameterValue {
+        ParameterValue::Str(v.to_owned())
+    }
+}
+
+impl<T: Into<ParameterValue>> From<Option<T>> for ParameterValue {
+    fn from(o: Option<T>) -> ParameterValue {
+        match o {
+            Some(v) => v.into(),
+            None => ParameterValue::DbNull,
+        }
+    }
+}
+
+/// Decimals are sent as text, which MySQL converts without losing precision.
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for ParameterValue {
+    fn from(v: rust_decimal::Decimal) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
 fn format_decode_err(types: &str, value: &DbValue) -> String {
     format!("Expected {} from the DB but got {:?}", types, value)
 }
 
+crate::rows::impl_named_rows! {
+    /// Execute a query and deserialize each row into a `T`.
+    ///
+    /// Struct fields are matched to columns by name, and tuples to columns by position. To
+    /// decode with [`Decode`] instead of serde, use [`RowSet::rows_as`] with a struct deriving
+    /// [`FromRow`](crate::rows::FromRow).
+    ///
+    /// ```ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct Pet { id: u64, name: String, prey: Option<String> }
+    ///
+    /// let pets: Vec<Pet> = conn.query_as("SELECT id, name, prey FROM pets", &[])?;
+    /// ```
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn unsigned_widening() {
+        assert_eq!(u64::decode(&DbValue::Int64(42)).unwrap(), 42);
+        assert_eq!(u64::decode(&DbValue::Uint32(7)).unwrap(), 7);
+        assert_eq!(u8::decode(&DbValue::Uint64(255)).unwrap(), 255);
+        assert!(u8::decode(&DbValue::Uint64(256)).is_err());
+        assert!(u32::decode(&DbValue::Int32(-1)).is_err());
+        assert!(u16::decode(&DbValue::Str("1".to_owned())).is_err());
+        assert!(matches!(
+            ParameterValue::from(u64::MAX),
+            ParameterValue::Uint64(u64::MAX)
+        ));
+        assert!(matches!(
+            ParameterValue::from(None::<u8>),
+            ParameterValue::DbNull
+        ));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimal() {
+        use rust_decimal::Decimal;
+
+        let price = Decimal::new(1234567890123456789, 4);
+        assert_eq!(
+            Decimal::decode(&DbValue::Str("123456789012345.6789".to_owned())).unwrap(),
+            price
+        );
+        assert_eq!(
+            Decimal::decode(&DbValue::Binary(b"0.10".to_vec())).unwrap(),
+            Decimal::new(10, 2)
+        );
+        assert_eq!(
+            Decimal::decode(&DbValue::Int64(3)).unwrap(),
+            Decimal::from(3)
+        );
+        assert!(Decimal::decode(&DbValue::Floating64(0.1)).is_err());
+        assert!(matches!(
+            ParameterValue::from(price),
+            ParameterValue::Str(s) if s == "123456789012345.6789"
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Settings {
+            theme: String,
+        }
+
+        let text = DbValue::Str(r#"{"theme":"dark"}"#.to_owned());
+        let bytes = DbValue::Binary(br#"{"theme":"light"}"#.to_vec());
+        assert_eq!(
+            serde_json::Value::decode(&text).unwrap(),
+            serde_json::json!({"theme": "dark"})
+        );
+        assert_eq!(Json::<Settings>::decode(&bytes).unwrap().theme, "light");
+        assert!(Json::<Settings>::decode(&DbValue::Str("[]".to_owned())).is_err());
+        assert!(serde_json::Value::decode(&DbValue::Int32(1)).is_err());
+        assert!(Option::<Json<Settings>>::decode(&DbValue::DbNull)
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            ParameterValue::try_from(Json(Settings { theme: "dark".to_owned() })),
+            Ok(ParameterValue::Str(s)) if s == r#"{"theme":"dark"}"#
+        ));
+        let non_string_keys = std::collections::BTreeMap::from([((1, 2), 3)]);
+        assert!(ParameterValue::try_from(Json(non_string_keys)).is_err());
+    }
+
     #[test]
     fn boolean() {
         assert!(bool::decode(&DbValue::Int8(1)).unwrap());
@@ -226,14 +464,16 @@ mod tests {
     #[test]
     fn uint8() {
         assert_eq!(u8::decode(&DbValue::Uint8(0)).unwrap(), 0);
-        assert!(u8::decode(&DbValue::Uint32(0)).is_err());
+        assert_eq!(u8::decode(&DbValue::Uint32(0)).unwrap(), 0);
+        assert!(u8::decode(&DbValue::Uint32(256)).is_err());
         assert!(Option::<u16>::decode(&DbValue::DbNull).unwrap().is_none());
     }
 
     #[test]
     fn uint16() {
         assert_eq!(u16::decode(&DbValue::Uint16(0)).unwrap(), 0);
-        assert!(u16::decode(&DbValue::Uint32(0)).is_err());
+        assert_eq!(u16::decode(&DbValue::Uint32(0)).unwrap(), 0);
+        assert!(u16::decode(&DbValue::Uint32(65536)).is_err());
         assert!(Option::<u16>::decode(&DbValue::DbNull).unwrap().is_none());
     }
 
@@ -0,0 +1,162 @@
+//! Deserialization of MySQL values, through the shared row deserializer.
+
+use serde::de::{self, Visitor};
+
+use super::{Column, DbValue};
+use crate::de::row::{self, DeserializeError};
+
+impl row::Column for Column {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl row::Value for DbValue {
+    fn visit<'de, V: Visitor<'de>>(&'de self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self {
+            DbValue::Boolean(b) => visitor.visit_bool(*b),
+            DbValue::Int8(i) => visitor.visit_i8(*i),
+            DbValue::Int16(i) => visitor.visit_i16(*i),
+            DbValue::Int32(i) => visitor.visit_i32(*i),
+            DbValue::Int64(i) => visitor.visit_i64(*i),
+            DbValue::Uint8(i) => visitor.visit_u8(*i),
+            DbValue::Uint16(i) => visitor.visit_u16(*i),
+            DbValue::Uint32(i) => visitor.visit_u32(*i),
+            DbValue::Uint64(i) => visitor.visit_u64(*i),
+            DbValue::Floating32(f) => visitor.visit_f32(*f),
+            DbValue::Floating64(f) => visitor.visit_f64(*f),
+            DbValue::Str(s) => visitor.visit_borrowed_str(s),
+            DbValue::Binary(b) => visitor.visit_borrowed_bytes(b),
+            DbValue::DbNull => visitor.visit_unit(),
+            DbValue::Unsupported => Err(de::Error::custom(
+                "the column has a type the mysql interface does not support",
+            )),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, DbValue::DbNull)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            DbValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            DbValue::Binary(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// MySQL has no boolean type, so booleans are stored as `TINYINT(1)`.
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            DbValue::Int8(i) => Some(*i != 0),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::super::DbDataType;
+    use super::*;
+    use crate::de::row::{RowDeserializer, ValueDeserializer};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Size {
+        Small,
+        Large,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Pet {
+        id: u64,
+        name: String,
+        size: Size,
+        vaccinated: bool,
+        weight: Option<f32>,
+        photo: Vec<u8>,
+    }
+
+    fn columns(names: &[&str]) -> Vec<Column> {
+        names
+            .iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                data_type: DbDataType::Other,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn struct_by_column_name() {
+        let columns = columns(&[
+            "name",
+            "id",
+            "size",
+            "vaccinated",
+            "weight",
+            "photo",
+            "extra",
+        ]);
+        let values = [
+            DbValue::Str("Rex".to_owned()),
+            DbValue::Uint64(7),
+            DbValue::Str("large".to_owned()),
+            DbValue::Int8(1),
+            DbValue::DbNull,
+            DbValue::Binary(vec![1, 2]),
+            DbValue::Unsupported,
+        ];
+        let pet = Pet::deserialize(RowDeserializer {
+            columns: &columns,
+            values: &values,
+        })
+        .unwrap();
+        assert_eq!(
+            pet,
+            Pet {
+                id: 7,
+                name: "Rex".to_owned(),
+                size: Size::Large,
+                vaccinated: true,
+                weight: None,
+                photo: vec![1, 2],
+            }
+        );
+        assert_ne!(pet.size, Size::Small);
+    }
+
+    #[test]
+    fn tuple_by_position() {
+        let columns = columns(&["a", "b"]);
+        let values = [DbValue::Int32(-3), DbValue::Floating64(0.5)];
+        let row: (i32, f64) = Deserialize::deserialize(RowDeserializer {
+            columns: &columns,
+            values: &values,
+        })
+        .unwrap();
+        assert_eq!(row, (-3, 0.5));
+    }
+
+    #[test]
+    fn errors() {
+        let columns = columns(&["id"]);
+        let missing = Pet::deserialize(RowDeserializer {
+            columns: &columns,
+            values: &[DbValue::Uint64(1)],
+        });
+        assert!(missing.unwrap_err().to_string().contains("missing field"));
+
+        let unsupported = i32::deserialize(ValueDeserializer(&DbValue::Unsupported));
+        assert!(unsupported.is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use super::{Connection, DbValue, Decode, MysqlError, ParameterValue};
+
+/// The outcome of a statement run with [`Connection::execute_with_result`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecResult {
+    /// The number of rows inserted, deleted or changed by the statement, as reported by
+    /// `ROW_COUNT()`.
+    ///
+    /// For an `UPDATE`, rows which matched the `WHERE` clause but already held the new values
+    /// are not counted.
+    pub affected_rows: u64,
+    /// The first `AUTO_INCREMENT` value generated by the most recent `INSERT` on the connection,
+    /// or 0 if none has been generated.
+    pub last_insert_id: u64,
+}
+
+impl Connection {
+    /// Execute a statement and report the rows it affected and the id it generated.
+    ///
+    /// The `mysql` interface does not return this information from
+    /// [`execute`](Connection::execute), so it is read with `SELECT ROW_COUNT(),
+    /// LAST_INSERT_ID()` straight after the statement, on the same connection.
+    ///
+    /// ```ignore
+    /// let result = conn.execute_with_result(
+    ///     "INSERT INTO pets (name) VALUES (?)",
+    ///     &[ParameterValue::Str(name)],
+    /// )?;
+    /// let id = result.last_insert_id;
+    /// ```
+    pub fn execute_with_result(
+        &self,
+        statement: &str,
+        params: &[ParameterValue],
+    ) -> Result<ExecResult, MysqlError> {
+        self.execute(statement, params)?;
+        let row = self.query_counters("SELECT ROW_COUNT(), LAST_INSERT_ID()")?;
+        Ok(ExecResult {
+            affected_rows: counter(row.first()),
+            last_insert_id: counter(row.get(1)),
+        })
+    }
+
+    /// The first `AUTO_INCREMENT` value generated by the most recent `INSERT` on this
+    /// connection, or 0 if none has been generated.
+    ///
+    /// Unlike the affected row count, the id is kept until the next `INSERT`, so it can be read
+    /// after any number of other statements.
+    pub fn last_insert_id(&self) -> Result<u64, MysqlError> {
+        let row = self.query_counters("SELECT LAST_INSERT_ID()")?;
+        Ok(counter(row.first()))
+    }
+
+    fn query_counters(&self, statement: &str) -> Result<Vec<DbValue>, MysqlError> {
+        self.query(statement, &[])?
+            .rows
+            .into_iter()
+            .next()
+            .ok_or_else(|| MysqlError::Other(format!("`{statement}` returned no rows")))
+    }
+}
+
+/// Read a counter, treating `NULL` and the -1 `ROW_COUNT()` returns after a `SELECT` as 0.
+fn counter(value: Option<&DbValue>) -> u64 {
+    value.and_then(|v| u64::decode(v).ok()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_counters() {
+        assert_eq!(counter(Some(&DbValue::Int64(3))), 3);
+        assert_eq!(counter(Some(&DbValue::Uint64(42))), 42);
+        assert_eq!(counter(Some(&DbValue::Int64(-1))), 0);
+        assert_eq!(counter(Some(&DbValue::DbNull)), 0);
+        assert_eq!(counter(None), 0);
+    }
+}
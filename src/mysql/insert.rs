@@ -0,0 +1,150 @@
+use super::{Connection, MysqlError, ParameterValue};
+
+/// The most placeholders MySQL accepts in one prepared statement.
+const MAX_PARAMETERS: usize = u16::MAX as usize;
+
+/// A conservative limit on the size of the parameters of one statement, well below the
+/// smallest default `max_allowed_packet` (4 MiB on MySQL 5.7).
+const MAX_STATEMENT_BYTES: usize = 1024 * 1024;
+
+impl Connection {
+    /// Insert many rows into `table` using multi-row `INSERT` statements.
+    ///
+    /// Rows are grouped into as few statements as possible while staying below MySQL's limits
+    /// on the number of placeholders and on the size of a packet. The statements are not
+    /// atomic on their own: call this inside a [`transaction`](Connection::transaction) so a
+    /// failure inserts nothing.
+    ///
+    /// `table` and `columns` are inserted into the statement as written, so they must be trusted
+    /// identifiers (quoted with backticks if necessary).
+    ///
+    /// ```ignore
+    /// conn.insert_many(
+    ///     "pets",
+    ///     &["name", "prey"],
+    ///     pets.iter().map(|p| vec![p.name.as_str().into(), p.prey.clone().into()]),
+    /// )?;
+    /// ```
+    pub fn insert_many(
+        &self,
+        table: &str,
+        columns: &[&str],
+        rows: impl IntoIterator<Item = Vec<ParameterValue>>,
+    ) -> Result<(), MysqlError> {
+        if columns.is_empty() {
+            return Err(MysqlError::BadParameter(
+                "insert_many requires at least one column".to_owned(),
+            ));
+        }
+        let mut batch = Batch::default();
+        for row in rows {
+            if row.len() != columns.len() {
+                return Err(MysqlError::BadParameter(format!(
+                    "expected {} values per row but got {}",
+                    columns.len(),
+                    row.len()
+                )));
+            }
+            if !batch.fits(&row) {
+                batch.flush(self, table, columns)?;
+            }
+            batch.push(row);
+        }
+        batch.flush(self, table, columns)
+    }
+}
+
+/// Rows waiting to be sent in one statement.
+#[derive(Default)]
+struct Batch {
+    params: Vec<ParameterValue>,
+    rows: usize,
+    bytes: usize,
+}
+
+impl Batch {
+    /// Whether `row` can be added without exceeding the statement limits. An empty batch
+    /// accepts any row, so oversized rows are still attempted on their own.
+    fn fits(&self, row: &[ParameterValue]) -> bool {
+        self.rows == 0
+            || (self.params.len() + row.len() <= MAX_PARAMETERS
+                && self.bytes + row_size(row) <= MAX_STATEMENT_BYTES)
+    }
+
+    fn push(&mut self, row: Vec<ParameterValue>) {
+        self.bytes += row_size(&row);
+        self.rows += 1;
+        self.params.extend(row);
+    }
+
+    fn flush(
+        &mut self,
+        conn: &Connection,
+        table: &str,
+        columns: &[&str],
+    ) -> Result<(), MysqlError> {
+        if self.rows == 0 {
+            return Ok(());
+        }
+        let statement = insert_statement(table, columns, self.rows);
+        let result = conn.execute(&statement, &self.params);
+        *self = Self::default();
+        result
+    }
+}
+
+/// An estimate of the bytes `row` takes up in a statement.
+fn row_size(row: &[ParameterValue]) -> usize {
+    row.iter()
+        .map(|value| match value {
+            ParameterValue::Str(s) => s.len() + 9,
+            ParameterValue::Binary(b) => b.len() + 9,
+            _ => 9,
+        })
+        .sum()
+}
+
+/// Build `INSERT INTO table (a, b) VALUES (?, ?), (?, ?), ...` for `rows` rows.
+fn insert_statement(table: &str, columns: &[&str], rows: usize) -> String {
+    let row = format!("({})", vec!["?"; columns.len()].join(", "));
+    format!(
+        "INSERT INTO {table} ({}) VALUES {}",
+        columns.join(", "),
+        vec![row.as_str(); rows].join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_multi_row_insert() {
+        assert_eq!(
+            insert_statement("pets", &["name", "prey"], 2),
+            "INSERT INTO pets (name, prey) VALUES (?, ?), (?, ?)"
+        );
+        assert_eq!(
+            insert_statement("`order`", &["id"], 1),
+            "INSERT INTO `order` (id) VALUES (?)"
+        );
+    }
+
+    #[test]
+    fn splits_large_batches() {
+        let mut batch = Batch::default();
+        let row = || vec![ParameterValue::Str("x".repeat(400 * 1024))];
+        assert!(batch.fits(&row()));
+        batch.push(row());
+        batch.push(row());
+        assert!(!batch.fits(&row()));
+
+        let mut batch = Batch::default();
+        let wide = vec![ParameterValue::Int32(1); 1000];
+        for _ in 0..65 {
+            assert!(batch.fits(&wide));
+            batch.push(wide.clone());
+        }
+        assert!(!batch.fits(&wide));
+    }
+}
@@ -0,0 +1,71 @@
+use std::ops::Deref;
+
+use super::{Connection, MysqlError};
+use crate::transaction::{Execute, Guard};
+
+impl Connection {
+    /// Begin a transaction.
+    ///
+    /// The transaction is rolled back when the returned [`Transaction`] is dropped without
+    /// calling [`Transaction::commit`].
+    pub fn begin(&self) -> Result<Transaction<'_>, MysqlError> {
+        Guard::begin(self).map(Transaction)
+    }
+
+    /// Run `f` inside a transaction.
+    ///
+    /// The transaction is committed if `f` returns `Ok`, and rolled back if it returns `Err`
+    /// or the commit fails.
+    ///
+    /// ```ignore
+    /// conn.transaction(|tx| {
+    ///     tx.execute("UPDATE accounts SET balance = balance - ? WHERE id = ?", &[ParameterValue::Int32(10), ParameterValue::Int32(1)])?;
+    ///     tx.execute("UPDATE accounts SET balance = balance + ? WHERE id = ?", &[ParameterValue::Int32(10), ParameterValue::Int32(2)])?;
+    ///     Ok::<_, MysqlError>(())
+    /// })?;
+    /// ```
+    pub fn transaction<T, E: From<MysqlError>>(
+        &self,
+        f: impl FnOnce(&Transaction<'_>) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let tx = self.begin()?;
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+}
+
+impl Execute for Connection {
+    type Error = MysqlError;
+
+    const BEGIN: &'static str = "START TRANSACTION";
+
+    fn execute_statement(&self, statement: &str) -> Result<(), MysqlError> {
+        self.execute(statement, &[])
+    }
+}
+
+/// An open transaction on a [`Connection`].
+///
+/// The transaction dereferences to its connection, so statements are executed as usual.
+pub struct Transaction<'a>(Guard<'a, Connection>);
+
+impl Transaction<'_> {
+    /// Commit the transaction, or roll it back if committing fails.
+    pub fn commit(self) -> Result<(), MysqlError> {
+        self.0.commit()
+    }
+
+    /// Roll back the transaction.
+    pub fn rollback(self) -> Result<(), MysqlError> {
+        self.0.rollback()
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.0.conn()
+    }
+}
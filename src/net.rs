@@ -0,0 +1,114 @@
+//! Networking and name resolution over `wasi:sockets`, for protocols other than HTTP.
+//!
+//! Outbound connections are only possible where the host permits them; in Spin, the
+//! component's `allowed_outbound_hosts` must include the address, for example
+//! `"tcp://smtp.example.com:587"`. Errors are reported as [`std::io::Error`]s, with kinds
+//! matching the socket error where there is one.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+use crate::wit::wasi::sockets0_2_0::network::{
+    ErrorCode, IpAddress, IpAddressFamily, IpSocketAddress, Ipv4SocketAddress, Ipv6SocketAddress,
+};
+
+mod dns;
+mod tcp;
+
+pub use dns::resolve;
+pub use tcp::TcpStream;
+
+fn io_error(code: ErrorCode) -> io::Error {
+    let kind = match code {
+        ErrorCode::AccessDenied => io::ErrorKind::PermissionDenied,
+        ErrorCode::NotSupported => io::ErrorKind::Unsupported,
+        ErrorCode::InvalidArgument => io::ErrorKind::InvalidInput,
+        ErrorCode::OutOfMemory => io::ErrorKind::OutOfMemory,
+        ErrorCode::Timeout => io::ErrorKind::TimedOut,
+        ErrorCode::WouldBlock => io::ErrorKind::WouldBlock,
+        ErrorCode::AddressNotBindable => io::ErrorKind::AddrNotAvailable,
+        ErrorCode::AddressInUse => io::ErrorKind::AddrInUse,
+        ErrorCode::ConnectionRefused => io::ErrorKind::ConnectionRefused,
+        ErrorCode::ConnectionReset => io::ErrorKind::ConnectionReset,
+        ErrorCode::ConnectionAborted => io::ErrorKind::ConnectionAborted,
+        ErrorCode::NameUnresolvable => io::ErrorKind::NotFound,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, format!("{code:?}"))
+}
+
+fn family(address: &SocketAddr) -> IpAddressFamily {
+    match address {
+        SocketAddr::V4(_) => IpAddressFamily::Ipv4,
+        SocketAddr::V6(_) => IpAddressFamily::Ipv6,
+    }
+}
+
+fn to_wasi(address: SocketAddr) -> IpSocketAddress {
+    match address {
+        SocketAddr::V4(address) => {
+            let [a, b, c, d] = address.ip().octets();
+            IpSocketAddress::Ipv4(Ipv4SocketAddress {
+                port: address.port(),
+                address: (a, b, c, d),
+            })
+        }
+        SocketAddr::V6(address) => {
+            let [a, b, c, d, e, f, g, h] = address.ip().segments();
+            IpSocketAddress::Ipv6(Ipv6SocketAddress {
+                port: address.port(),
+                flow_info: address.flowinfo(),
+                address: (a, b, c, d, e, f, g, h),
+                scope_id: address.scope_id(),
+            })
+        }
+    }
+}
+
+fn from_wasi(address: IpSocketAddress) -> SocketAddr {
+    match address {
+        IpSocketAddress::Ipv4(Ipv4SocketAddress { port, address }) => {
+            let ip = ip_from_wasi(IpAddress::Ipv4(address));
+            SocketAddr::new(ip, port)
+        }
+        IpSocketAddress::Ipv6(address) => {
+            let IpAddr::V6(ip) = ip_from_wasi(IpAddress::Ipv6(address.address)) else {
+                unreachable!()
+            };
+            SocketAddr::V6(SocketAddrV6::new(
+                ip,
+                address.port,
+                address.flow_info,
+                address.scope_id,
+            ))
+        }
+    }
+}
+
+fn ip_from_wasi(address: IpAddress) -> IpAddr {
+    match address {
+        IpAddress::Ipv4((a, b, c, d)) => IpAddr::V4(Ipv4Addr::new(a, b, c, d)),
+        IpAddress::Ipv6((a, b, c, d, e, f, g, h)) => {
+            IpAddr::V6(Ipv6Addr::new(a, b, c, d, e, f, g, h))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddrV4;
+
+    #[test]
+    fn converts_addresses() {
+        let v4 = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 25));
+        let v6 = SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+            587,
+            3,
+            7,
+        ));
+        assert_eq!(from_wasi(to_wasi(v4)), v4);
+        assert_eq!(from_wasi(to_wasi(v6)), v6);
+    }
+}
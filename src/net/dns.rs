@@ -0,0 +1,35 @@
+use std::io;
+use std::net::IpAddr;
+
+use spin_executor::PollableFuture;
+
+use super::{io_error, ip_from_wasi};
+use crate::wit::wasi::sockets0_2_0::network::ErrorCode;
+use crate::wit::wasi::sockets0_2_0::{instance_network, ip_name_lookup};
+
+/// Look up the IP addresses of `host`.
+///
+/// ```ignore
+/// let address = net::resolve("redis.internal").await?[0];
+/// let stream = net::TcpStream::connect(SocketAddr::new(address, 6379)).await?;
+/// ```
+///
+/// `host` may also be an IP address, which resolves to itself. A name with no addresses is
+/// an error of kind [`NotFound`](io::ErrorKind::NotFound), so the result is never empty.
+pub async fn resolve(host: &str) -> io::Result<Vec<IpAddr>> {
+    let network = instance_network::instance_network();
+    let stream = ip_name_lookup::resolve_addresses(&network, host).map_err(io_error)?;
+    let mut addresses = Vec::new();
+    loop {
+        match stream.resolve_next_address() {
+            Ok(Some(address)) => addresses.push(ip_from_wasi(address)),
+            Ok(None) => break,
+            Err(ErrorCode::WouldBlock) => PollableFuture::new(stream.subscribe()).await,
+            Err(e) => return Err(io_error(e)),
+        }
+    }
+    if addresses.is_empty() {
+        return Err(io_error(ErrorCode::NameUnresolvable));
+    }
+    Ok(addresses)
+}
@@ -0,0 +1,184 @@
+use std::future::Future;
+use std::io;
+use std::net::{Shutdown, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use spin_executor::bindings::wasi::io::streams::{InputStream, OutputStream, StreamError};
+use spin_executor::PollableFuture;
+
+use super::{family, from_wasi, io_error, to_wasi};
+use crate::wit::wasi::sockets0_2_0::network::ErrorCode;
+use crate::wit::wasi::sockets0_2_0::tcp::{ShutdownType, TcpSocket};
+use crate::wit::wasi::sockets0_2_0::{instance_network, tcp_create_socket};
+
+/// A TCP connection to a remote host.
+///
+/// ```ignore
+/// use futures::{AsyncReadExt, AsyncWriteExt};
+///
+/// let mut stream = TcpStream::connect("192.0.2.1:6379".parse()?).await?;
+/// stream.write_all(b"PING\r\n").await?;
+/// let mut reply = [0; 7];
+/// stream.read_exact(&mut reply).await?;
+/// ```
+///
+/// The stream implements the `futures` crate's [`AsyncRead`](futures::AsyncRead) and
+/// [`AsyncWrite`](futures::AsyncWrite) traits, through which it is read and written, and so
+/// can be used with libraries built on them.
+#[derive(Debug)]
+pub struct TcpStream {
+    // The fields are dropped in order, and pollables must be dropped before the stream they
+    // belong to, and the streams before the socket.
+    read_ready: Option<PollableFuture>,
+    write_ready: Option<PollableFuture>,
+    flushing: bool,
+    input: InputStream,
+    output: OutputStream,
+    socket: TcpSocket,
+}
+
+impl TcpStream {
+    /// Connect to `address`.
+    pub async fn connect(address: SocketAddr) -> io::Result<Self> {
+        let network = instance_network::instance_network();
+        let socket = tcp_create_socket::create_tcp_socket(family(&address)).map_err(io_error)?;
+        socket
+            .start_connect(&network, to_wasi(address))
+            .map_err(io_error)?;
+        let (input, output) = loop {
+            match socket.finish_connect() {
+                Ok(streams) => break streams,
+                Err(ErrorCode::WouldBlock) => PollableFuture::new(socket.subscribe()).await,
+                Err(e) => return Err(io_error(e)),
+            }
+        };
+        Ok(Self {
+            read_ready: None,
+            write_ready: None,
+            flushing: false,
+            input,
+            output,
+            socket,
+        })
+    }
+
+    /// The address of the remote end of the connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.socket
+            .remote_address()
+            .map(from_wasi)
+            .map_err(io_error)
+    }
+
+    /// The local address of the connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_address().map(from_wasi).map_err(io_error)
+    }
+
+    /// Shut down reading, writing or both halves of the connection.
+    ///
+    /// Shutting down writing does not flush data already written; use
+    /// [`close`](futures::AsyncWriteExt::close) to flush and then shut down writing.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        let how = match how {
+            Shutdown::Read => ShutdownType::Receive,
+            Shutdown::Write => ShutdownType::Send,
+            Shutdown::Both => ShutdownType::Both,
+        };
+        self.socket.shutdown(how).map_err(io_error)
+    }
+
+    /// Wait for the stream to be ready to write, returning how many bytes may be written.
+    fn poll_write_ready(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        loop {
+            if let Some(ready) = &mut self.write_ready {
+                if Pin::new(ready).poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                self.write_ready = None;
+            }
+            match self.output.check_write() {
+                Ok(0) => self.write_ready = Some(PollableFuture::new(self.output.subscribe())),
+                Ok(count) => return Poll::Ready(Ok(usize::try_from(count).unwrap_or(usize::MAX))),
+                Err(e) => return Poll::Ready(Err(stream_error(e))),
+            }
+        }
+    }
+}
+
+impl futures::AsyncRead for TcpStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            if let Some(ready) = &mut self.read_ready {
+                if Pin::new(ready).poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                self.read_ready = None;
+            }
+            match self.input.read(buf.len() as u64) {
+                Ok(bytes) if bytes.is_empty() && !buf.is_empty() => {
+                    self.read_ready = Some(PollableFuture::new(self.input.subscribe()));
+                }
+                Ok(bytes) => {
+                    buf[..bytes.len()].copy_from_slice(&bytes);
+                    return Poll::Ready(Ok(bytes.len()));
+                }
+                Err(StreamError::Closed) => return Poll::Ready(Ok(0)),
+                Err(e) => return Poll::Ready(Err(stream_error(e))),
+            }
+        }
+    }
+}
+
+impl futures::AsyncWrite for TcpStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let count = match self.poll_write_ready(cx) {
+            Poll::Ready(Ok(count)) => count.min(buf.len()),
+            other => return other,
+        };
+        match self.output.write(&buf[..count]) {
+            Ok(()) => Poll::Ready(Ok(count)),
+            Err(e) => Poll::Ready(Err(stream_error(e))),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if !self.flushing {
+            if let Err(e) = self.output.flush() {
+                return Poll::Ready(Err(stream_error(e)));
+            }
+            self.flushing = true;
+        }
+        // The stream is ready to write again once the flush has completed.
+        match self.poll_write_ready(cx) {
+            Poll::Ready(result) => {
+                self.flushing = false;
+                Poll::Ready(result.map(drop))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.as_mut().poll_flush(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(self.shutdown(Shutdown::Write)),
+            other => other,
+        }
+    }
+}
+
+fn stream_error(error: StreamError) -> io::Error {
+    match error {
+        StreamError::Closed => io::Error::new(io::ErrorKind::BrokenPipe, "connection closed"),
+        StreamError::LastOperationFailed(e) => io::Error::other(e.to_debug_string()),
+    }
+}
@@ -0,0 +1,49 @@
+//! Tracing, metrics and logging for components.
+//!
+//! The Spin host has no observability interface for components yet, so spans are recorded in
+//! the component and written to standard error when they end, as one JSON object per line,
+//! and metric measurements and log records are written the same way as they are made. Spin captures standard
+//! error in the component's logs, from where a log pipeline can forward them to a tracing or
+//! metrics backend. Spans carry W3C trace and span ids, so traces can be reassembled from
+//! them, and can be [propagated](set_propagation) to the services a component calls.
+//!
+//! Nothing is written unless the component records it: HTTP handlers are only traced when
+//! declared with `#[http_component(trace)]`.
+
+pub mod baggage;
+mod context;
+mod current;
+mod log;
+mod metrics;
+mod span;
+#[cfg(feature = "tracing")]
+mod tracing;
+mod value;
+
+pub(crate) use context::inject;
+pub use context::{current_trace_context, set_propagation, TraceContext};
+pub use current::{record_error, span, SpanGuard};
+pub use log::{debug, enabled, error, info, log, set_level, warn, Level};
+pub use metrics::{
+    counter, gauge, histogram, up_down_counter, Counter, Gauge, Histogram, UpDownCounter,
+};
+pub use span::{Span, Status};
+#[cfg(feature = "tracing")]
+pub use tracing::{init_tracing, TracingSubscriber};
+pub use value::Value;
+
+#[doc(hidden)]
+pub mod __private {
+    use crate::http::IncomingRequest;
+
+    /// Take the baggage from an incoming request's headers.
+    pub fn extract_baggage(request: &IncomingRequest) {
+        let headers = request.headers().get(&"baggage".to_owned());
+        super::baggage::extract(headers.iter().map(Vec::as_slice));
+    }
+
+    /// Mark the handler's span as failed if it responded with a server error.
+    pub fn record_response_status(status: u16) {
+        super::current::record_response_status(status);
+    }
+}
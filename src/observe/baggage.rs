@@ -0,0 +1,174 @@
+//! W3C baggage: key-value pairs which travel with a trace, such as a tenant or customer id.
+//!
+//! ```ignore
+//! if let Some(tenant) = baggage::get("tenant") {
+//!     span.set_attribute("tenant", tenant);
+//! }
+//! baggage::set("plan", "enterprise")?;
+//! ```
+//!
+//! Handlers made with `#[http_component]` start with the baggage from the request's
+//! `baggage` headers. When [propagation](super::set_propagation) is on, requests sent with
+//! [`http::send`](crate::http::send) carry the baggage on in a `baggage` header, like the
+//! trace context. Baggage is sent to every service the component calls, so it should not
+//! hold secrets.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+
+thread_local! {
+    static BAGGAGE: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The value of `key`, if it is set.
+pub fn get(key: &str) -> Option<String> {
+    BAGGAGE.with(|baggage| {
+        baggage
+            .borrow()
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    })
+}
+
+/// Set `key` to `value`, replacing any earlier value.
+///
+/// Keys must be HTTP header tokens, such as `tenant_id` or `customer.tier`. Values may be any
+/// text.
+pub fn set(key: impl Into<String>, value: impl Into<String>) -> Result<(), InvalidKey> {
+    let key = key.into();
+    if !is_token(&key) {
+        return Err(InvalidKey(key));
+    }
+    let value = value.into();
+    BAGGAGE.with(|baggage| {
+        let mut baggage = baggage.borrow_mut();
+        match baggage.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => baggage.push((key, value)),
+        }
+    });
+    Ok(())
+}
+
+/// Remove `key`, returning its value if it was set.
+pub fn remove(key: &str) -> Option<String> {
+    BAGGAGE.with(|baggage| {
+        let mut baggage = baggage.borrow_mut();
+        let index = baggage.iter().position(|(k, _)| k == key)?;
+        Some(baggage.remove(index).1)
+    })
+}
+
+/// All the baggage, in the order it was set.
+pub fn all() -> Vec<(String, String)> {
+    BAGGAGE.with(|baggage| baggage.borrow().clone())
+}
+
+/// Remove all the baggage.
+pub fn clear() {
+    BAGGAGE.with(|baggage| baggage.borrow_mut().clear());
+}
+
+/// A baggage key which is not an HTTP header token.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid baggage key {0:?}")]
+pub struct InvalidKey(pub String);
+
+/// Replace the baggage with that given by `baggage` header values.
+pub(super) fn extract<'a>(headers: impl IntoIterator<Item = &'a [u8]>) {
+    let members = headers
+        .into_iter()
+        .filter_map(|header| std::str::from_utf8(header).ok())
+        .flat_map(parse)
+        .collect();
+    BAGGAGE.with(|baggage| *baggage.borrow_mut() = members);
+}
+
+/// The `baggage` header value for the current baggage, if there is any.
+pub(super) fn header() -> Option<String> {
+    BAGGAGE.with(|baggage| {
+        let baggage = baggage.borrow();
+        (!baggage.is_empty()).then(|| format(&baggage))
+    })
+}
+
+/// The members of a `baggage` header, leaving out any which are malformed. Member properties
+/// are dropped.
+fn parse(header: &str) -> Vec<(String, String)> {
+    header
+        .split(',')
+        .filter_map(|member| {
+            let member = member.split(';').next().unwrap_or_default();
+            let (key, value) = member.split_once('=')?;
+            let key = key.trim();
+            if !is_token(key) {
+                return None;
+            }
+            Some((key.to_owned(), percent_decode(value.trim())?))
+        })
+        .collect()
+}
+
+fn format(members: &[(String, String)]) -> String {
+    let mut header = String::new();
+    for (i, (key, value)) in members.iter().enumerate() {
+        if i > 0 {
+            header.push(',');
+        }
+        header.push_str(key);
+        header.push('=');
+        for byte in value.bytes() {
+            // Characters allowed unencoded in a baggage value, other than `%`.
+            if matches!(byte, 0x21 | 0x23..=0x24 | 0x26..=0x2b | 0x2d..=0x3a | 0x3c..=0x5b | 0x5d..=0x7e)
+            {
+                header.push(byte as char);
+            } else {
+                write!(header, "%{byte:02X}").unwrap();
+            }
+        }
+    }
+    header
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_headers() {
+        let members = parse("tenant = acme ;ttl=60, name=J%C3%B6rg%2C%20Jr, bad key=1, broken=%zz");
+        assert_eq!(
+            members,
+            [
+                ("tenant".to_owned(), "acme".to_owned()),
+                ("name".to_owned(), "Jörg, Jr".to_owned()),
+            ]
+        );
+        assert_eq!(format(&members), "tenant=acme,name=J%C3%B6rg%2C%20Jr");
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::baggage;
+use super::current::current;
+use super::span::hex;
+
+/// The W3C trace context of a span, as sent to other services so that their spans join the
+/// same trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+}
+
+impl TraceContext {
+    /// The trace id, as 32 lowercase hex digits.
+    pub fn trace_id(&self) -> String {
+        hex(&self.trace_id)
+    }
+
+    /// The span id, as 16 lowercase hex digits.
+    pub fn span_id(&self) -> String {
+        hex(&self.span_id)
+    }
+
+    /// The value of the W3C `traceparent` header, marking the trace as sampled.
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id(), self.span_id())
+    }
+}
+
+/// The trace context of the innermost open [`span`](super::span), if there is one.
+///
+/// ```ignore
+/// let _span = observe::span("publish");
+/// if let Some(context) = observe::current_trace_context() {
+///     message.set_header("traceparent", context.traceparent());
+/// }
+/// ```
+pub fn current_trace_context() -> Option<TraceContext> {
+    current().map(|(trace_id, span_id)| TraceContext { trace_id, span_id })
+}
+
+static PROPAGATE: AtomicBool = AtomicBool::new(false);
+
+/// Set whether requests sent with [`http::send`](crate::http::send) carry the
+/// [current trace context](current_trace_context) in a `traceparent` header, and the
+/// [baggage](super::baggage) in a `baggage` header. Off by default.
+///
+/// The headers are added to [`Request`](crate::http::Request)s and `http` crate requests, unless
+/// they already have them. An [`OutgoingRequest`](crate::http::OutgoingRequest)'s headers
+/// cannot be changed once it is created, so those are sent as they are. Spans carry no
+/// vendor-specific trace state, so no `tracestate` header is added.
+pub fn set_propagation(enabled: bool) {
+    PROPAGATE.store(enabled, Ordering::Relaxed);
+}
+
+/// Add `traceparent` and `baggage` headers for the current trace context and
+/// [baggage](super::baggage) to `headers`, if propagation is on and they have none.
+pub(crate) fn inject(headers: &mut Vec<(String, Vec<u8>)>) {
+    if !PROPAGATE.load(Ordering::Relaxed) {
+        return;
+    }
+    let has = |headers: &[(String, Vec<u8>)], name: &str| {
+        headers.iter().any(|(n, _)| n.eq_ignore_ascii_case(name))
+    };
+    if let Some(context) = current_trace_context() {
+        if !has(headers, "traceparent") {
+            headers.push(("traceparent".to_owned(), context.traceparent().into_bytes()));
+        }
+    }
+    if let Some(baggage) = baggage::header() {
+        if !has(headers, "baggage") {
+            headers.push(("baggage".to_owned(), baggage.into_bytes()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_traceparent() {
+        let context = TraceContext {
+            trace_id: [0xab; 16],
+            span_id: [1; 8],
+        };
+        assert_eq!(
+            context.traceparent(),
+            "00-abababababababababababababababab-0101010101010101-01"
+        );
+    }
+}
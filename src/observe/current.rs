@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+
+use super::span::{exception, random_bytes, Event, Span, Status};
+
+thread_local! {
+    /// The open guarded spans, innermost last.
+    static OPEN: RefCell<Vec<Open>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An open guarded span, with what has been recorded on it without going through its guard.
+struct Open {
+    ids: ([u8; 16], [u8; 8]),
+    events: Vec<Event>,
+    status: Option<Status>,
+}
+
+/// Start a span within the innermost open [`span`], or at the root of a new trace if there is
+/// none, returning a guard which closes it when dropped.
+///
+/// ```ignore
+/// fn load_order(id: u64) -> anyhow::Result<Order> {
+///     let mut span = observe::span("load_order");
+///     span.set_attribute("order.id", id as i64);
+///     let row = db.query_one(...)?; // the span is closed here on error...
+///     Ok(Order::from_row(row)?)     // ...and here on success
+/// }
+/// ```
+///
+/// Components abort on panic without running destructors, so a span open when a component
+/// panics is never written out.
+pub fn span(name: impl Into<String>) -> SpanGuard {
+    let span = match current() {
+        Some((trace_id, span_id)) => Span::new(name.into(), trace_id, Some(span_id)),
+        None => Span::new(name.into(), random_bytes(), None),
+    };
+    enter(span.ids());
+    SpanGuard(Some(span))
+}
+
+/// Make the span with `ids` the innermost open span.
+pub(super) fn enter(ids: ([u8; 16], [u8; 8])) {
+    OPEN.with(|open| {
+        open.borrow_mut().push(Open {
+            ids,
+            events: Vec::new(),
+            status: None,
+        })
+    });
+}
+
+/// Remove the span with `ids` from the open spans, returning the events and status recorded on
+/// it while it was open.
+pub(super) fn exit(ids: ([u8; 16], [u8; 8])) -> (Vec<Event>, Option<Status>) {
+    // Spans are usually exited innermost first, but need not be.
+    OPEN.with(|open| {
+        let mut open = open.borrow_mut();
+        match open.iter().rposition(|open| open.ids == ids) {
+            Some(index) => {
+                let open = open.remove(index);
+                (open.events, open.status)
+            }
+            None => (Vec::new(), None),
+        }
+    })
+}
+
+/// The trace and span ids of the innermost open [`span`].
+pub(super) fn current() -> Option<([u8; 16], [u8; 8])> {
+    OPEN.with(|open| open.borrow().last().map(|open| open.ids))
+}
+
+/// Record that the operation of the innermost open [`span`] failed with `error`, as
+/// [`Span::record_error`] does. Does nothing if no span is open.
+///
+/// This is for code which has no access to the span's guard, such as a shared error handler.
+pub fn record_error(error: &dyn std::error::Error) {
+    OPEN.with(|open| {
+        if let Some(open) = open.borrow_mut().last_mut() {
+            let (event, status) = exception(error);
+            open.events.push(event);
+            open.status = Some(status);
+        }
+    });
+}
+
+/// Mark the innermost open span as failed if an HTTP handler responded with a server error,
+/// unless an error has already been recorded on it.
+pub(super) fn record_response_status(status: u16) {
+    if status < 500 {
+        return;
+    }
+    OPEN.with(|open| {
+        if let Some(open) = open.borrow_mut().last_mut() {
+            if !matches!(open.status, Some(Status::Error(_))) {
+                open.status = Some(Status::Error(format!("responded with status {status}")));
+            }
+        }
+    });
+}
+
+/// An open span, which is closed when the guard is dropped.
+///
+/// The guard dereferences to the [`Span`], so attributes and events can be recorded through
+/// it.
+#[derive(Debug)]
+pub struct SpanGuard(Option<Span>);
+
+impl SpanGuard {
+    /// Close the span now rather than when the guard goes out of scope.
+    pub fn close(self) {
+        drop(self);
+    }
+}
+
+impl Deref for SpanGuard {
+    type Target = Span;
+
+    fn deref(&self) -> &Span {
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for SpanGuard {
+    fn deref_mut(&mut self) -> &mut Span {
+        self.0.as_mut().unwrap()
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let Some(mut span) = self.0.take() else {
+            return;
+        };
+        let (events, status) = exit(span.ids());
+        span.apply(events, status);
+        span.close();
+    }
+}
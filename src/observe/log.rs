@@ -0,0 +1,131 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::SystemTime;
+
+use super::current::current;
+use super::span::{hex, unix_nanos};
+use super::value::{write_attributes, write_json_string, Value};
+
+/// The severity of a log record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// Very detailed information, usually only of interest when diagnosing a problem.
+    Trace,
+    /// Information useful while debugging.
+    Debug,
+    /// Normal operation worth recording.
+    Info,
+    /// Something unexpected which the component recovered from.
+    Warn,
+    /// A failure.
+    Error,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Trace => "trace",
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// Set the least severe level which is written out. [`Level::Info`] by default.
+pub fn set_level(level: Level) {
+    MIN_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether records at `level` are written out.
+pub fn enabled(level: Level) -> bool {
+    level as u8 >= MIN_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Write a log record with structured fields.
+///
+/// ```ignore
+/// observe::log(Level::Warn, "payment retried", &[("attempt", 2.into()), ("provider", "acme".into())]);
+/// ```
+///
+/// The record is written to standard error as a line of JSON, like spans, and carries the ids
+/// of the innermost open [`span`](super::span) so that it can be shown as part of the trace.
+pub fn log(level: Level, message: &str, fields: &[(&str, Value)]) {
+    if enabled(level) {
+        eprintln!(
+            "{}",
+            record(level, message, fields, current(), SystemTime::now())
+        );
+    }
+}
+
+/// Write a log record at [`Level::Debug`].
+pub fn debug(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Debug, message, fields);
+}
+
+/// Write a log record at [`Level::Info`].
+pub fn info(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Info, message, fields);
+}
+
+/// Write a log record at [`Level::Warn`].
+pub fn warn(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Warn, message, fields);
+}
+
+/// Write a log record at [`Level::Error`].
+pub fn error(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Error, message, fields);
+}
+
+fn record(
+    level: Level,
+    message: &str,
+    fields: &[(&str, Value)],
+    span: Option<([u8; 16], [u8; 8])>,
+    time: SystemTime,
+) -> String {
+    let mut out = format!(r#"{{"type":"log","level":"{}","message":"#, level.name());
+    write_json_string(&mut out, message);
+    write!(out, r#","time_unix_nano":{}"#, unix_nanos(time)).unwrap();
+    if let Some((trace_id, span_id)) = span {
+        write!(
+            out,
+            r#","trace_id":"{}","span_id":"{}""#,
+            hex(&trace_id),
+            hex(&span_id)
+        )
+        .unwrap();
+    }
+    out.push_str(r#","attributes":"#);
+    write_attributes(&mut out, fields);
+    out.push('}');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    #[test]
+    fn records_log() {
+        assert_eq!(
+            record(
+                Level::Warn,
+                "retrying",
+                &[("attempt", 2.into())],
+                Some(([1; 16], [2; 8])),
+                UNIX_EPOCH
+            ),
+            r#"{"type":"log","level":"warn","message":"retrying","time_unix_nano":0,"trace_id":"01010101010101010101010101010101","span_id":"0202020202020202","attributes":{"attempt":2}}"#
+        );
+        assert!(enabled(Level::Error));
+        assert!(!enabled(Level::Debug));
+    }
+}
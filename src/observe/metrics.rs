@@ -0,0 +1,130 @@
+use std::fmt::Write;
+use std::time::SystemTime;
+
+use super::span::unix_nanos;
+use super::value::{write_attributes, write_json_string, Value};
+
+/// A count which only goes up, such as the number of requests handled.
+///
+/// ```ignore
+/// observe::counter("orders.placed").add(1, &[("region", region.into())]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Counter(String);
+
+/// A count which can go up and down, such as the number of items in a queue.
+#[derive(Clone, Debug)]
+pub struct UpDownCounter(String);
+
+/// A distribution of measurements, such as request latencies.
+#[derive(Clone, Debug)]
+pub struct Histogram(String);
+
+/// A value sampled at a point in time, such as a cache's size.
+#[derive(Clone, Debug)]
+pub struct Gauge(String);
+
+/// A [`Counter`] called `name`.
+pub fn counter(name: impl Into<String>) -> Counter {
+    Counter(name.into())
+}
+
+/// An [`UpDownCounter`] called `name`.
+pub fn up_down_counter(name: impl Into<String>) -> UpDownCounter {
+    UpDownCounter(name.into())
+}
+
+/// A [`Histogram`] called `name`.
+pub fn histogram(name: impl Into<String>) -> Histogram {
+    Histogram(name.into())
+}
+
+/// A [`Gauge`] called `name`.
+pub fn gauge(name: impl Into<String>) -> Gauge {
+    Gauge(name.into())
+}
+
+impl Counter {
+    /// Add `value` to the count.
+    pub fn add(&self, value: u64, attributes: &[(&str, Value)]) {
+        emit("counter", &self.0, &value.to_string(), attributes);
+    }
+}
+
+impl UpDownCounter {
+    /// Add `value`, which may be negative, to the count.
+    pub fn add(&self, value: i64, attributes: &[(&str, Value)]) {
+        emit("up_down_counter", &self.0, &value.to_string(), attributes);
+    }
+}
+
+impl Histogram {
+    /// Record a measurement.
+    pub fn record(&self, value: f64, attributes: &[(&str, Value)]) {
+        emit("histogram", &self.0, &number(value), attributes);
+    }
+}
+
+impl Gauge {
+    /// Record the current value.
+    pub fn record(&self, value: f64, attributes: &[(&str, Value)]) {
+        emit("gauge", &self.0, &number(value), attributes);
+    }
+}
+
+/// Write a measurement out as a line of JSON, in the same way as spans.
+///
+/// Each measurement is written separately, and aggregating them is left to the pipeline
+/// which collects them, since a component instance only lives for one request.
+fn emit(kind: &str, name: &str, value: &str, attributes: &[(&str, Value)]) {
+    eprintln!(
+        "{}",
+        record(kind, name, value, attributes, SystemTime::now())
+    );
+}
+
+fn record(
+    kind: &str,
+    name: &str,
+    value: &str,
+    attributes: &[(&str, Value)],
+    time: SystemTime,
+) -> String {
+    let mut out = format!(r#"{{"type":"metric","kind":"{kind}","name":"#);
+    write_json_string(&mut out, name);
+    write!(
+        out,
+        r#","value":{value},"time_unix_nano":{},"attributes":"#,
+        unix_nanos(time)
+    )
+    .unwrap();
+    write_attributes(&mut out, attributes);
+    out.push('}');
+    out
+}
+
+/// `value` as a JSON number, or `null` if it has no JSON representation.
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn records_measurements() {
+        let time = UNIX_EPOCH + Duration::from_nanos(7);
+        assert_eq!(
+            record("counter", "requests", "1", &[("route", "/".into())], time),
+            r#"{"type":"metric","kind":"counter","name":"requests","value":1,"time_unix_nano":7,"attributes":{"route":"/"}}"#
+        );
+        assert_eq!(number(f64::INFINITY), "null");
+    }
+}
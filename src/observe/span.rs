@@ -0,0 +1,280 @@
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::value::{write_attributes, write_json_string, Value};
+use crate::wit::wasi::random0_2_0::random;
+
+/// A timed operation within a trace, such as handling a request or calling a database.
+///
+/// ```ignore
+/// let mut span = Span::start("checkout");
+/// span.set_attribute("cart.items", cart.len() as i64);
+/// let payment = charge(&cart)?;
+/// span.add_event("payment.authorized", &[("amount_cents", payment.amount.into())]);
+/// span.close();
+/// ```
+///
+/// The span is written out when it is [closed](Self::close), with the attributes and events
+/// recorded on it.
+#[derive(Debug)]
+pub struct Span {
+    name: String,
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    start: SystemTime,
+    attributes: Vec<(String, Value)>,
+    events: Vec<Event>,
+    status: Status,
+}
+
+/// Whether the operation a [`Span`] describes succeeded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Status {
+    /// No status has been set.
+    #[default]
+    Unset,
+    /// The operation succeeded.
+    Ok,
+    /// The operation failed, for the reason given.
+    Error(String),
+}
+
+#[derive(Debug)]
+pub(super) struct Event {
+    name: String,
+    time: SystemTime,
+    attributes: Vec<(String, Value)>,
+}
+
+impl Span {
+    /// Start a span at the root of a new trace.
+    pub fn start(name: impl Into<String>) -> Self {
+        Self::new(name.into(), random_bytes(), None)
+    }
+
+    /// Start a span within this one.
+    pub fn start_child(&self, name: impl Into<String>) -> Self {
+        Self::new(name.into(), self.trace_id, Some(self.span_id))
+    }
+
+    pub(super) fn new(name: String, trace_id: [u8; 16], parent_span_id: Option<[u8; 8]>) -> Self {
+        Self {
+            name,
+            trace_id,
+            span_id: random_bytes(),
+            parent_span_id,
+            start: SystemTime::now(),
+            attributes: Vec::new(),
+            events: Vec::new(),
+            status: Status::Unset,
+        }
+    }
+
+    /// The name of the span.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The ids of the trace and of this span.
+    pub(super) fn ids(&self) -> ([u8; 16], [u8; 8]) {
+        (self.trace_id, self.span_id)
+    }
+
+    /// Set an attribute describing the operation, replacing any earlier value for `key`.
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        let key = key.into();
+        let value = value.into();
+        match self.attributes.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.attributes.push((key, value)),
+        }
+    }
+
+    /// Record something which happened during the operation.
+    pub fn add_event(&mut self, name: impl Into<String>, attributes: &[(&str, Value)]) {
+        self.events.push(Event {
+            name: name.into(),
+            time: SystemTime::now(),
+            attributes: attributes
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), v.clone()))
+                .collect(),
+        });
+    }
+
+    /// Set whether the operation succeeded.
+    pub fn set_status(&mut self, status: Status) {
+        self.status = status;
+    }
+
+    /// Record that the operation failed with `error`.
+    ///
+    /// This adds an `exception` event describing the error and its causes, and sets the status
+    /// to [`Status::Error`].
+    pub fn record_error(&mut self, error: &dyn std::error::Error) {
+        let (event, status) = exception(error);
+        self.events.push(event);
+        self.set_status(status);
+    }
+
+    /// Add events and a status recorded while the span was not at hand.
+    pub(super) fn apply(&mut self, events: Vec<Event>, status: Option<Status>) {
+        self.events.extend(events);
+        if let Some(status) = status {
+            self.set_status(status);
+        }
+    }
+
+    /// End the span and write it out.
+    pub fn close(self) {
+        eprintln!("{}", self.record(SystemTime::now()));
+    }
+
+    /// The span as a single line of JSON.
+    fn record(&self, end: SystemTime) -> String {
+        let mut out = String::from(r#"{"type":"span","name":"#);
+        write_json_string(&mut out, &self.name);
+        write!(
+            out,
+            r#","trace_id":"{}","span_id":"{}","parent_span_id":"#,
+            hex(&self.trace_id),
+            hex(&self.span_id)
+        )
+        .unwrap();
+        match &self.parent_span_id {
+            Some(parent) => write!(out, r#""{}""#, hex(parent)).unwrap(),
+            None => out.push_str("null"),
+        }
+        write!(
+            out,
+            r#","start_time_unix_nano":{},"end_time_unix_nano":{},"attributes":"#,
+            unix_nanos(self.start),
+            unix_nanos(end)
+        )
+        .unwrap();
+        write_attributes(&mut out, &self.attributes);
+        out.push_str(r#","status":"#);
+        match &self.status {
+            Status::Unset => out.push_str(r#"{"code":"unset"}"#),
+            Status::Ok => out.push_str(r#"{"code":"ok"}"#),
+            Status::Error(message) => {
+                out.push_str(r#"{"code":"error","message":"#);
+                write_json_string(&mut out, message);
+                out.push('}');
+            }
+        }
+        out.push_str(r#","events":["#);
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(r#"{"name":"#);
+            write_json_string(&mut out, &event.name);
+            write!(
+                out,
+                r#","time_unix_nano":{},"attributes":"#,
+                unix_nanos(event.time)
+            )
+            .unwrap();
+            write_attributes(&mut out, &event.attributes);
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+/// The `exception` event and error status recording `error`.
+pub(super) fn exception(error: &dyn std::error::Error) -> (Event, Status) {
+    let message = error.to_string();
+    let mut attributes = vec![(
+        "exception.message".to_owned(),
+        Value::from(message.as_str()),
+    )];
+    let causes = std::iter::successors(error.source(), |e| e.source())
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>();
+    if !causes.is_empty() {
+        attributes.push(("exception.causes".to_owned(), causes.join(": ").into()));
+    }
+    let event = Event {
+        name: "exception".to_owned(),
+        time: SystemTime::now(),
+        attributes,
+    };
+    (event, Status::Error(message))
+}
+
+pub(super) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&random::get_random_bytes(N as u64));
+    bytes
+}
+
+pub(super) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        write!(s, "{b:02x}").unwrap();
+        s
+    })
+}
+
+pub(super) fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn records_span() {
+        let start = UNIX_EPOCH + Duration::from_secs(1);
+        let mut span = Span {
+            name: "checkout".to_owned(),
+            trace_id: [0xab; 16],
+            span_id: [1; 8],
+            parent_span_id: None,
+            start,
+            attributes: Vec::new(),
+            events: Vec::new(),
+            status: Status::Unset,
+        };
+        span.set_attribute("items", 2);
+        span.set_attribute("items", 3);
+        span.events.push(Event {
+            name: "paid".to_owned(),
+            time: start,
+            attributes: vec![("ok".to_owned(), true.into())],
+        });
+        assert_eq!(
+            span.record(start + Duration::from_nanos(5)),
+            r#"{"type":"span","name":"checkout","trace_id":"abababababababababababababababab","span_id":"0101010101010101","parent_span_id":null,"start_time_unix_nano":1000000000,"end_time_unix_nano":1000000005,"attributes":{"items":3},"status":{"code":"unset"},"events":[{"name":"paid","time_unix_nano":1000000000,"attributes":{"ok":true}}]}"#
+        );
+    }
+
+    #[test]
+    fn records_errors() {
+        let error = anyhow::anyhow!("connection refused").context("loading order");
+        let mut span = Span {
+            name: "load".to_owned(),
+            trace_id: [0; 16],
+            span_id: [0; 8],
+            parent_span_id: None,
+            start: UNIX_EPOCH,
+            attributes: Vec::new(),
+            events: Vec::new(),
+            status: Status::Unset,
+        };
+        span.record_error(error.as_ref());
+        assert_eq!(span.status, Status::Error("loading order".to_owned()));
+        assert_eq!(
+            span.events[0].attributes[1],
+            ("exception.causes".to_owned(), "connection refused".into())
+        );
+    }
+}
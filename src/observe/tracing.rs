@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::subscriber::Interest;
+use tracing_core::{dispatcher, Dispatch, Event, Metadata};
+
+use super::current::{current, enter, exit};
+use super::log::{enabled, log, Level};
+use super::span::{random_bytes, Span};
+use super::value::Value;
+
+/// A [`tracing`](https://docs.rs/tracing) subscriber which records spans and events made with
+/// `tracing` through this module, so that libraries instrumented with `tracing` show up in the
+/// component's traces and logs.
+///
+/// ```ignore
+/// observe::init_tracing()?;
+///
+/// #[tracing::instrument]
+/// fn load_order(id: u64) -> anyhow::Result<Order> {
+///     tracing::info!(id, "loading order");
+///     ...
+/// }
+/// ```
+///
+/// `tracing` spans become [`Span`]s, nested within the innermost open span, and are written
+/// out when the last handle to them is dropped. Their fields become attributes. Events become
+/// [log records](super::log) at the corresponding [`Level`], with their `message` field as the
+/// message and their other fields as fields, and are filtered by [`set_level`](super::set_level).
+#[derive(Debug, Default)]
+pub struct TracingSubscriber {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    span: Span,
+    /// How many handles there are to the span.
+    refs: usize,
+    /// How many times the span has been entered and not yet exited.
+    entered: usize,
+}
+
+impl TracingSubscriber {
+    /// Create a subscriber.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The trace and span ids of the span with `id`.
+    fn ids(&self, id: &Id) -> Option<([u8; 16], [u8; 8])> {
+        let spans = self.spans.lock().unwrap();
+        spans.get(&id.into_u64()).map(|entry| entry.span.ids())
+    }
+}
+
+/// Set a [`TracingSubscriber`] as the global `tracing` subscriber.
+///
+/// This fails if a global subscriber has already been set.
+pub fn init_tracing() -> Result<(), dispatcher::SetGlobalDefaultError> {
+    dispatcher::set_global_default(Dispatch::new(TracingSubscriber::new()))
+}
+
+impl tracing_core::Subscriber for TracingSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The level can be changed at any time, so it is checked for each event.
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_span() || enabled(level(metadata.level()))
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let parent = if attributes.is_root() {
+            None
+        } else if let Some(parent) = attributes.parent() {
+            self.ids(parent)
+        } else {
+            current()
+        };
+        let mut span = match parent {
+            Some((trace_id, span_id)) => {
+                Span::new(attributes.metadata().name().into(), trace_id, Some(span_id))
+            }
+            None => Span::new(attributes.metadata().name().into(), random_bytes(), None),
+        };
+        let mut fields = Fields::default();
+        attributes.record(&mut fields);
+        for (key, value) in fields.fields {
+            span.set_attribute(key, value);
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let entry = Entry {
+            span,
+            refs: 1,
+            entered: 0,
+        };
+        self.spans.lock().unwrap().insert(id, entry);
+        Id::from_non_zero_u64(NonZeroU64::new(id).unwrap())
+    }
+
+    fn record(&self, id: &Id, values: &Record<'_>) {
+        let mut fields = Fields::default();
+        values.record(&mut fields);
+        if let Some(entry) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            for (key, value) in fields.fields {
+                entry.span.set_attribute(key, value);
+            }
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {
+        // Spans are written with a single parent and no links.
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let fields = fields
+            .fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect::<Vec<_>>();
+        let message = fields.iter().find(|(key, _)| *key == "message");
+        let message = match message {
+            Some((_, Value::String(message))) => message.clone(),
+            _ => event.metadata().name().to_owned(),
+        };
+        let fields = fields
+            .into_iter()
+            .filter(|(key, _)| *key != "message")
+            .collect::<Vec<_>>();
+        log(level(event.metadata().level()), &message, &fields);
+    }
+
+    fn enter(&self, id: &Id) {
+        let mut spans = self.spans.lock().unwrap();
+        if let Some(entry) = spans.get_mut(&id.into_u64()) {
+            entry.entered += 1;
+            enter(entry.span.ids());
+        }
+    }
+
+    fn exit(&self, id: &Id) {
+        let mut spans = self.spans.lock().unwrap();
+        if let Some(entry) = spans.get_mut(&id.into_u64()) {
+            entry.entered = entry.entered.saturating_sub(1);
+            let (events, status) = exit(entry.span.ids());
+            entry.span.apply(events, status);
+        }
+    }
+
+    fn clone_span(&self, id: &Id) -> Id {
+        if let Some(entry) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            entry.refs += 1;
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        let mut spans = self.spans.lock().unwrap();
+        let Some(entry) = spans.get_mut(&id.into_u64()) else {
+            return false;
+        };
+        entry.refs -= 1;
+        if entry.refs > 0 {
+            return false;
+        }
+        let mut entry = spans.remove(&id.into_u64()).unwrap();
+        drop(spans);
+        // A span dropped while entered, such as one held across a panic, is still open.
+        for _ in 0..entry.entered {
+            let (events, status) = exit(entry.span.ids());
+            entry.span.apply(events, status);
+        }
+        entry.span.close();
+        true
+    }
+}
+
+fn level(level: &tracing_core::Level) -> Level {
+    match *level {
+        tracing_core::Level::TRACE => Level::Trace,
+        tracing_core::Level::DEBUG => Level::Debug,
+        tracing_core::Level::INFO => Level::Info,
+        tracing_core::Level::WARN => Level::Warn,
+        tracing_core::Level::ERROR => Level::Error,
+    }
+}
+
+/// Collects the fields of a span or event as [`Value`]s.
+#[derive(Default)]
+struct Fields {
+    fields: Vec<(String, Value)>,
+}
+
+impl Fields {
+    fn push(&mut self, field: &Field, value: Value) {
+        self.fields.push((field.name().to_owned(), value));
+    }
+}
+
+impl Visit for Fields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.push(field, value.into()),
+            Err(_) => self.push(field, value.to_string().into()),
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.push(field, value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, format!("{value:?}").into());
+    }
+}
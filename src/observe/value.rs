@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+/// The value of a span or event attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// A string.
+    String(String),
+    /// An integer.
+    Int(i64),
+    /// A floating point number.
+    Float(f64),
+    /// A boolean.
+    Bool(bool),
+}
+
+impl Value {
+    /// Append the value to `out` as JSON.
+    pub(super) fn write_json(&self, out: &mut String) {
+        match self {
+            Value::String(s) => write_json_string(out, s),
+            Value::Int(i) => write!(out, "{i}").unwrap(),
+            // JSON has no representation of NaN or the infinities.
+            Value::Float(f) if f.is_finite() => write!(out, "{f}").unwrap(),
+            Value::Float(f) => write_json_string(out, &f.to_string()),
+            Value::Bool(b) => write!(out, "{b}").unwrap(),
+        }
+    }
+}
+
+/// Append `s` to `out` as a JSON string.
+pub(super) fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Append `attributes` to `out` as a JSON object.
+pub(super) fn write_attributes<K: AsRef<str>>(out: &mut String, attributes: &[(K, Value)]) {
+    out.push('{');
+    for (i, (key, value)) in attributes.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json_string(out, key.as_ref());
+        out.push(':');
+        value.write_json(out);
+    }
+    out.push('}');
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::Int(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_json() {
+        let mut out = String::new();
+        for value in [
+            Value::from("a \"quoted\"\nline\u{1}"),
+            Value::from(-3),
+            Value::from(1.5),
+            Value::from(f64::NAN),
+            Value::from(true),
+        ] {
+            value.write_json(&mut out);
+            out.push(' ');
+        }
+        assert_eq!(out, r#""a \"quoted\"\nline\u0001" -3 1.5 "NaN" true "#);
+    }
+}
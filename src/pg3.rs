@@ -1,5 +1,14 @@
 //! Conversions between Rust, WIT and **Postgres** types.
 //!
+//! # Text casts
+//!
+//! The postgres interface the host provides only has the values in the WIT column below, and
+//! none for types such as `UUID` or `JSONB`. Values of such types travel as text, and the host
+//! neither converts them to text nor parses text parameters back into them, so the casts have
+//! to be written in SQL: select the column as `id::text` and cast the parameter as
+//! `$1::text::uuid`. This is a limitation of the host, which the SDK cannot work around; the
+//! sections below list the types it affects.
+//!
 //! # Types
 //!
 //! | Rust type               | WIT (db-value)                                | Postgres type(s)             |
@@ -15,13 +24,103 @@
 //! | `chrono::NaiveDate`     | date(tuple<s32, u8, u8>)                      | DATE                         |
 //! | `chrono::NaiveTime`     | time(tuple<u8, u8, u8, u32>)                  | TIME                         |
 //! | `chrono::NaiveDateTime` | datetime(tuple<s32, u8, u8, u8, u8, u8, u32>) | TIMESTAMP                    |
-//! | `chrono::Duration`      | timestamp(s64)                                | BIGINT                       |
+//! | `chrono::DateTime<Tz>`  | str(string)                                   | TIMESTAMPTZ (see below)      |
+//! | `chrono::Duration`      | timestamp(s64)                                | BIGINT (seconds)             |
+//! | [`Interval`]            | str(string)                                   | INTERVAL (see below)         |
+//! | `uuid::Uuid`            | str(string)                                   | UUID (see below)             |
+//! | [`Numeric`]             | str(string)                                   | NUMERIC, DECIMAL (see below) |
+//! | `rust_decimal::Decimal` | str(string)                                   | NUMERIC, DECIMAL (see below) |
+//! | `std::net::IpAddr`      | str(string)                                   | INET, CIDR (see below)       |
+//! | `ipnet::IpNet`          | str(string)                                   | INET, CIDR (see below)       |
+//! | [`MacAddr`]             | str(string)                                   | MACADDR (see below)          |
+//! | `serde_json::Value`     | str(string)                                   | JSON, JSONB (see below)      |
+//! | `Json<T>`               | str(string)                                   | JSON, JSONB (see below)      |
+//!
+//! # Time zones
+//!
+//! `chrono::DateTime<Utc>` and `chrono::DateTime<FixedOffset>` map to `TIMESTAMPTZ`. They are
+//! passed as RFC 3339 text so the offset is not lost, and are decoded from Postgres' text
+//! output (which includes the offset) or from a `datetime` value, which is taken to be UTC.
+//! Select such columns as `created_at::text`, and cast parameters as `$1::text::timestamptz`.
+//!
+//! # Intervals
+//!
+//! Despite its name, the `timestamp` value carries a plain count of seconds, which is how
+//! `chrono::Duration` parameters are sent. For `INTERVAL` columns use [`Interval`], which keeps
+//! months, days and microseconds apart as Postgres does. Intervals are exchanged as text: select
+//! them as `elapsed::text` and cast parameters as `$1::text::interval`. A `chrono::Duration`
+//! can also be decoded from interval text, counting months as 30 days.
+//!
+//! # UUIDs
+//!
+//! With the `uuid` feature, `uuid::Uuid` can be decoded and used as a parameter. The postgres
+//! interface has no UUID value, so UUIDs are exchanged as text: select UUID columns as
+//! `id::text`, and type UUID parameters as text before casting them, as in
+//! `WHERE id = $1::text::uuid`.
+//!
+//! # Numerics
+//!
+//! `NUMERIC` values are exchanged as text to avoid lossy float conversions: select them as
+//! `price::text` and decode a [`Numeric`] (or, with the `decimal` feature, a
+//! `rust_decimal::Decimal`), and pass parameters as `$1::text::numeric`.
+//!
+//! # Enums
+//!
+//! Rust enums can be mapped to Postgres enum or text columns with [`PgEnum`](macro@PgEnum).
+//!
+//! # Network addresses
+//!
+//! `INET`, `CIDR` and `MACADDR` values are exchanged as text: select them as `addr::text` and
+//! cast parameters as `$1::text::inet`. `std::net::IpAddr` keeps only the address of a value;
+//! with the `ipnet` feature, `ipnet::IpNet` keeps the prefix length too.
+//!
+//! # JSON
+//!
+//! With the `json` feature, `serde_json::Value` and [`Json<T>`] (for any `T` implementing the
+//! serde traits) can be decoded from, and used as parameters for, `json` and `jsonb` columns.
+//! Documents are exchanged as text, so they can be read from `TEXT` columns and text
+//! expressions too. As with the other [text casts](#text-casts), select `json`/`jsonb` columns
+//! as `doc::text` and pass parameters as `$1::text::jsonb`.
+//!
+//! [`Interval`]: crate::pg3::Interval
+//! [`Numeric`]: crate::pg3::Numeric
+//! [`MacAddr`]: crate::pg3::MacAddr
+//! [`Json<T>`]: crate::pg3::Json
 
 #[doc(inline)]
 pub use super::wit::pg3::{Error as PgError, *};
 
 use chrono::{Datelike, Timelike};
 
+mod batch;
+mod copy;
+mod cursor;
+#[cfg(feature = "json")]
+mod de;
+mod enums;
+mod interval;
+mod network;
+mod notify;
+mod numeric;
+mod options;
+mod shared;
+mod sqlstate;
+mod transaction;
+#[cfg(feature = "json")]
+pub use crate::de::row::DeserializeError;
+#[cfg(feature = "json")]
+pub use crate::http::Json;
+pub use cursor::RowIter;
+#[doc(hidden)]
+pub use enums::__private;
+pub use enums::PgEnum;
+pub use interval::Interval;
+pub use network::MacAddr;
+pub use numeric::Numeric;
+pub use options::{ConnectionOptions, OptionsError, SslMode};
+pub use shared::{shared, with_shared};
+pub use transaction::Transaction;
+
 /// A pg error
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -33,6 +132,27 @@ pub enum Error {
     PgError(#[from] PgError),
 }
 
+/// An error from [`Connection::query_one`], [`Connection::query_opt`] or
+/// [`Connection::query_scalar`].
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    /// A query expected to return one row returned none
+    #[error("query returned no rows")]
+    NoRows,
+    /// A query expected to return at most one row returned more
+    #[error("query returned {0} rows where at most one was expected")]
+    TooManyRows(usize),
+    /// The query failed, or its result could not be decoded
+    #[error(transparent)]
+    Pg(#[from] Error),
+}
+
+impl From<PgError> for QueryError {
+    fn from(e: PgError) -> Self {
+        QueryError::Pg(e.into())
+    }
+}
+
 /// A type that can be decoded from the database.
 pub trait Decode: Sized {
     /// Decode a new value of this type using a [`DbValue`].
@@ -200,11 +320,38 @@ impl Decode for chrono::NaiveDateTime {
     }
 }
 
+/// The format of `TIMESTAMPTZ` values in Postgres' text output, such as
+/// `2024-01-02 03:04:05.678+05:30`.
+const TIMESTAMPTZ_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%#z";
+
+impl Decode for chrono::DateTime<chrono::FixedOffset> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) => chrono::DateTime::parse_from_str(s, TIMESTAMPTZ_FORMAT)
+                .or_else(|_| chrono::DateTime::parse_from_rfc3339(s))
+                .map_err(|e| Error::Decode(format!("invalid TIMESTAMPTZ {s:?}: {e}"))),
+            DbValue::Datetime(_) => Ok(chrono::NaiveDateTime::decode(value)?
+                .and_utc()
+                .fixed_offset()),
+            _ => Err(Error::Decode(format_decode_err("TIMESTAMPTZ", value))),
+        }
+    }
+}
+
+impl Decode for chrono::DateTime<chrono::Utc> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        chrono::DateTime::<chrono::FixedOffset>::decode(value).map(|dt| dt.to_utc())
+    }
+}
+
 impl Decode for chrono::Duration {
     fn decode(value: &DbValue) -> Result<Self, Error> {
         match value {
             DbValue::Timestamp(n) => Ok(chrono::Duration::seconds(*n)),
-            _ => Err(Error::Decode(format_decode_err("BIGINT", value))),
+            DbValue::Str(_) => Interval::decode(value)?.to_duration().ok_or_else(|| {
+                Error::Decode(format!("interval {value:?} is too long for a duration"))
+            }),
+            _ => Err(Error::Decode(format_decode_err("BIGINT, INTERVAL", value))),
         }
     }
 }
@@ -247,6 +394,12 @@ impl From<chrono::NaiveDateTime> for ParameterValue {
     }
 }
 
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for ParameterValue {
+    fn from(v: chrono::DateTime<Tz>) -> ParameterValue {
+        ParameterValue::Str(v.fixed_offset().to_rfc3339())
+    }
+}
+
 impl From<chrono::NaiveTime> for ParameterValue {
     fn from(v: chrono::NaiveTime) -> ParameterValue {
         ParameterValue::Time((
@@ -279,16 +432,212 @@ impl<T: Into<ParameterValue>> From<Option<T>> for ParameterValue {
     }
 }
 
+/// Decodes the text form of a UUID, so the column must be selected as `id::text`; see
+/// [text casts](self#text-casts).
+#[cfg(feature = "uuid")]
+impl Decode for uuid::Uuid {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) => s
+                .parse()
+                .map_err(|e| Error::Decode(format!("invalid UUID {s:?}: {e}"))),
+            DbValue::Binary(b) => uuid::Uuid::from_slice(b)
+                .map_err(|e| Error::Decode(format!("invalid UUID {b:?}: {e}"))),
+            _ => Err(Error::Decode(format_decode_err("UUID (as TEXT)", value))),
+        }
+    }
+}
+
+/// Sends the UUID as text, so the parameter must be cast as `$1::text::uuid`; see
+/// [text casts](self#text-casts).
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for ParameterValue {
+    fn from(v: uuid::Uuid) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+#[cfg(feature = "json")]
+impl Decode for serde_json::Value {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        Json::decode(value).map(|Json(v)| v)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Value> for ParameterValue {
+    fn from(v: serde_json::Value) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+/// Decode a JSON document stored in a `json` or `jsonb` column.
+///
+/// ```ignore
+/// #[derive(serde::Deserialize)]
+/// struct Settings { theme: String }
+///
+/// let rows = conn.query("SELECT settings::text FROM users WHERE id = $1", &[id.into()])?;
+/// let Json(settings) = Json::<Settings>::decode(&rows.rows[0][0])?;
+/// ```
+///
+/// The host does not convert `json`/`jsonb` values to or from text, so columns must be selected
+/// as `settings::text` and parameters cast as `$1::text::jsonb`; see
+/// [text casts](crate::pg3#text-casts).
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> Decode for Json<T> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        let json = match value {
+            DbValue::Str(s) => s.as_bytes(),
+            DbValue::Binary(b) => b,
+            _ => return Err(Error::Decode(format_decode_err("JSON, JSONB", value))),
+        };
+        serde_json::from_slice(json)
+            .map(Json)
+            .map_err(|e| Error::Decode(format!("invalid JSON: {e}")))
+    }
+}
+
+/// Fails if `T`'s `Serialize` implementation fails, for example for a map with non-string keys.
+#[cfg(feature = "json")]
+impl<T: serde::Serialize> TryFrom<Json<T>> for ParameterValue {
+    type Error = serde_json::Error;
+
+    fn try_from(Json(v): Json<T>) -> Result<Self, Self::Error> {
+        serde_json::to_string(&v).map(ParameterValue::Str)
+    }
+}
+
 fn format_decode_err(types: &str, value: &DbValue) -> String {
     format!("Expected {} from the DB but got {:?}", types, value)
 }
 
+impl Connection {
+    /// Execute a query which must return exactly one row.
+    pub fn query_one(&self, statement: &str, params: &[ParameterValue]) -> Result<Row, QueryError> {
+        self.query_opt(statement, params)?.ok_or(QueryError::NoRows)
+    }
+
+    /// Execute a query which must return at most one row.
+    pub fn query_opt(
+        &self,
+        statement: &str,
+        params: &[ParameterValue],
+    ) -> Result<Option<Row>, QueryError> {
+        at_most_one(self.query(statement, params)?.rows)
+    }
+
+    /// Execute a query which must return exactly one row, and decode its first column.
+    ///
+    /// ```ignore
+    /// let count: i64 = conn.query_scalar("SELECT COUNT(*) FROM articles", &[])?;
+    /// ```
+    pub fn query_scalar<T: Decode>(
+        &self,
+        statement: &str,
+        params: &[ParameterValue],
+    ) -> Result<T, QueryError> {
+        let row = self.query_one(statement, params)?;
+        let value = row
+            .first()
+            .ok_or_else(|| Error::Decode("query returned no columns".to_owned()))?;
+        Ok(T::decode(value)?)
+    }
+}
+
+fn at_most_one(rows: Vec<Row>) -> Result<Option<Row>, QueryError> {
+    match rows.len() {
+        0 | 1 => Ok(rows.into_iter().next()),
+        n => Err(QueryError::TooManyRows(n)),
+    }
+}
+
+crate::rows::impl_named_rows! {
+    /// Execute a query and deserialize each row into a `T`.
+    ///
+    /// Struct fields are matched to columns by name, and tuples to columns by position. Dates
+    /// and times deserialize as ISO 8601 strings, or into their `chrono` types with the
+    /// `chrono` feature, which enables `chrono`'s serde support.
+    ///
+    /// ```ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct Article { id: i32, title: String, published: Option<chrono::NaiveDate> }
+    ///
+    /// let articles: Vec<Article> = conn.query_as("SELECT id, title, published FROM articles", &[])?;
+    /// ```
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
 
     use super::*;
 
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid() {
+        let id = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let text = DbValue::Str("67e55044-10b1-426f-9247-bb680e5fe0c8".to_owned());
+        assert_eq!(uuid::Uuid::decode(&text).unwrap(), id);
+        assert_eq!(
+            uuid::Uuid::decode(&DbValue::Binary(id.as_bytes().to_vec())).unwrap(),
+            id
+        );
+        assert!(uuid::Uuid::decode(&DbValue::Str("nope".to_owned())).is_err());
+        assert!(Option::<uuid::Uuid>::decode(&DbValue::DbNull)
+            .unwrap()
+            .is_none());
+        assert!(matches!(ParameterValue::from(id), ParameterValue::Str(s) if s == id.to_string()));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Settings {
+            theme: String,
+            tags: Vec<String>,
+        }
+
+        let doc = DbValue::Str(r#"{"theme":"dark","tags":["a"]}"#.to_owned());
+        assert_eq!(
+            serde_json::Value::decode(&doc).unwrap(),
+            serde_json::json!({"theme": "dark", "tags": ["a"]})
+        );
+        let settings = Json::<Settings>::decode(&doc).unwrap();
+        assert_eq!(settings.theme, "dark");
+        assert!(Json::<Settings>::decode(&DbValue::Str("{}".to_owned())).is_err());
+        assert!(serde_json::Value::decode(&DbValue::Int32(1)).is_err());
+        assert!(Option::<Json<Settings>>::decode(&DbValue::DbNull)
+            .unwrap()
+            .is_none());
+
+        let param = ParameterValue::try_from(settings).unwrap();
+        assert!(
+            matches!(&param, ParameterValue::Str(s) if s == r#"{"theme":"dark","tags":["a"]}"#)
+        );
+        assert!(matches!(
+            ParameterValue::from(serde_json::json!([1, 2])),
+            ParameterValue::Str(s) if s == "[1,2]"
+        ));
+        let non_string_keys = std::collections::BTreeMap::from([((1, 2), 3)]);
+        assert!(ParameterValue::try_from(Json(non_string_keys)).is_err());
+    }
+
+    #[test]
+    fn row_counts() {
+        let row = || vec![DbValue::Int64(1)];
+        assert!(at_most_one(vec![]).unwrap().is_none());
+        assert!(matches!(
+            at_most_one(vec![row()]).unwrap().as_deref(),
+            Some([DbValue::Int64(1)])
+        ));
+        assert!(matches!(
+            at_most_one(vec![row(), row()]),
+            Err(QueryError::TooManyRows(2))
+        ));
+    }
+
     #[test]
     fn boolean() {
         assert!(bool::decode(&DbValue::Boolean(true)).unwrap());
@@ -402,6 +751,46 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn timestamptz() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+        let offset = FixedOffset::east_opt(5 * 3600 + 1800).unwrap();
+        let local = offset
+            .with_ymd_and_hms(2024, 1, 2, 3, 4, 5)
+            .unwrap()
+            .with_nanosecond(678_000_000)
+            .unwrap();
+        let text = DbValue::Str("2024-01-02 03:04:05.678+05:30".to_owned());
+        assert_eq!(DateTime::<FixedOffset>::decode(&text).unwrap(), local);
+        assert_eq!(
+            DateTime::<Utc>::decode(&text).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 21, 34, 5).unwrap()
+                + chrono::Duration::milliseconds(678)
+        );
+        assert_eq!(
+            DateTime::<Utc>::decode(&DbValue::Str("2024-01-02 03:04:05+00".to_owned())).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()
+        );
+        assert_eq!(
+            DateTime::<Utc>::decode(&DbValue::Datetime((2024, 1, 2, 3, 4, 5, 0))).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()
+        );
+        assert!(DateTime::<Utc>::decode(&DbValue::Str("yesterday".to_owned())).is_err());
+        assert!(Option::<DateTime<Utc>>::decode(&DbValue::DbNull)
+            .unwrap()
+            .is_none());
+
+        let ParameterValue::Str(param) = ParameterValue::from(local) else {
+            panic!("expected a text parameter");
+        };
+        assert_eq!(param, "2024-01-02T03:04:05.678+05:30");
+        assert_eq!(
+            DateTime::<FixedOffset>::decode(&DbValue::Str(param)).unwrap(),
+            local
+        );
+    }
+
     #[test]
     fn timestamp() {
         assert_eq!(
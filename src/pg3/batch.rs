@@ -0,0 +1,27 @@
+use super::{Connection, ParameterValue, PgError};
+
+impl Connection {
+    /// Execute several statements in order, returning the number of rows each one affected.
+    ///
+    /// This is a convenience, not an optimization: the `spin:postgres` interface executes one
+    /// statement per call and has no pipelining, so each statement is a separate, sequential
+    /// round-trip, exactly as if [`execute`](Connection::execute) were called in a loop.
+    /// Execution stops at the first error, and statements which succeeded before it are not
+    /// rolled back; run the batch inside [`Connection::transaction`] to make it atomic.
+    ///
+    /// ```ignore
+    /// conn.execute_batch(&[
+    ///     ("UPDATE stock SET count = count - 1 WHERE item = $1", &[item.into()]),
+    ///     ("INSERT INTO orders (item) VALUES ($1)", &[item.into()]),
+    /// ])?;
+    /// ```
+    pub fn execute_batch(
+        &self,
+        statements: &[(&str, &[ParameterValue])],
+    ) -> Result<Vec<u64>, PgError> {
+        statements
+            .iter()
+            .map(|(statement, params)| self.execute(statement, params))
+            .collect()
+    }
+}
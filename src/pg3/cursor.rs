@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{Column, Connection, ParameterValue, PgError, Row};
+
+/// The number of rows fetched per round trip unless [`RowIter::batch_size`] is used.
+const DEFAULT_BATCH_SIZE: u32 = 500;
+
+impl Connection {
+    /// Execute a query and iterate over its rows, fetched in batches rather than materialized
+    /// all at once in a [`RowSet`](super::RowSet).
+    ///
+    /// The query runs through a server-side cursor declared `WITH HOLD`, so it works both inside
+    /// and outside a [`Transaction`](super::Transaction). Postgres keeps the results on the
+    /// server until they have been fetched or the iterator is dropped.
+    ///
+    /// Each batch is fetched by a blocking call to the host, like any other query.
+    ///
+    /// ```ignore
+    /// for row in conn.query_iter("SELECT id, body FROM events", &[])?.batch_size(1000) {
+    ///     export(&row?)?;
+    /// }
+    /// ```
+    pub fn query_iter(
+        &self,
+        statement: &str,
+        params: &[ParameterValue],
+    ) -> Result<RowIter<'_>, PgError> {
+        static CURSORS: AtomicU64 = AtomicU64::new(0);
+
+        let cursor = format!("spin_cursor_{}", CURSORS.fetch_add(1, Ordering::Relaxed));
+        self.execute(
+            &format!("DECLARE {cursor} NO SCROLL CURSOR WITH HOLD FOR {statement}"),
+            params,
+        )?;
+        Ok(RowIter {
+            conn: self,
+            cursor,
+            batch_size: DEFAULT_BATCH_SIZE,
+            columns: Vec::new(),
+            buffer: VecDeque::new(),
+            exhausted: false,
+        })
+    }
+}
+
+/// An iterator over the rows returned by [`Connection::query_iter`].
+pub struct RowIter<'a> {
+    conn: &'a Connection,
+    cursor: String,
+    batch_size: u32,
+    columns: Vec<Column>,
+    buffer: VecDeque<Row>,
+    exhausted: bool,
+}
+
+impl RowIter<'_> {
+    /// Set the number of rows fetched per round trip to the database.
+    pub fn batch_size(mut self, rows: u32) -> Self {
+        self.batch_size = rows.max(1);
+        self
+    }
+
+    /// The columns of the result, known once the first row has been fetched.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    fn fetch(&mut self) -> Result<(), PgError> {
+        let batch = self.conn.query(
+            &format!("FETCH {} FROM {}", self.batch_size, self.cursor),
+            &[],
+        )?;
+        self.exhausted = batch.rows.len() < self.batch_size as usize;
+        self.columns = batch.columns;
+        self.buffer.extend(batch.rows);
+        Ok(())
+    }
+}
+
+impl Iterator for RowIter<'_> {
+    type Item = Result<Row, PgError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.exhausted {
+            if let Err(e) = self.fetch() {
+                self.exhausted = true;
+                return Some(Err(e));
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+impl Drop for RowIter<'_> {
+    fn drop(&mut self) {
+        // Closing frees the server-side results; a failure leaves them until the session ends.
+        let _ = self.conn.execute(&format!("CLOSE {}", self.cursor), &[]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Deserialization of Postgres values, through the shared row deserializer.
+
+use serde::de::{self, Visitor};
+
+use super::{Column, DbValue, Decode};
+use crate::de::row::{self, DeserializeError};
+
+impl row::Column for Column {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl row::Value for DbValue {
+    /// Dates and times are exposed as ISO 8601 strings, which is what `chrono`'s serde support
+    /// expects.
+    fn visit<'de, V: Visitor<'de>>(&'de self, visitor: V) -> Result<V::Value, DeserializeError> {
+        let decode_err = |e: super::Error| de::Error::custom(e);
+        match self {
+            DbValue::Boolean(b) => visitor.visit_bool(*b),
+            DbValue::Int8(i) => visitor.visit_i8(*i),
+            DbValue::Int16(i) => visitor.visit_i16(*i),
+            DbValue::Int32(i) => visitor.visit_i32(*i),
+            DbValue::Int64(i) => visitor.visit_i64(*i),
+            DbValue::Floating32(f) => visitor.visit_f32(*f),
+            DbValue::Floating64(f) => visitor.visit_f64(*f),
+            DbValue::Str(s) => visitor.visit_borrowed_str(s),
+            DbValue::Binary(b) => visitor.visit_borrowed_bytes(b),
+            DbValue::Date(_) => visitor.visit_string(
+                chrono::NaiveDate::decode(self)
+                    .map_err(decode_err)?
+                    .to_string(),
+            ),
+            DbValue::Time(_) => visitor.visit_string(
+                chrono::NaiveTime::decode(self)
+                    .map_err(decode_err)?
+                    .to_string(),
+            ),
+            DbValue::Datetime(_) => visitor.visit_string(
+                chrono::NaiveDateTime::decode(self)
+                    .map_err(decode_err)?
+                    .format("%Y-%m-%dT%H:%M:%S%.f")
+                    .to_string(),
+            ),
+            DbValue::Timestamp(i) => visitor.visit_i64(*i),
+            DbValue::DbNull => visitor.visit_unit(),
+            DbValue::Unsupported => Err(de::Error::custom(
+                "the column has a type the postgres interface does not support",
+            )),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, DbValue::DbNull)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            DbValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            DbValue::Binary(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::super::DbDataType;
+    use super::*;
+    use crate::de::row::{RowDeserializer, ValueDeserializer};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Draft,
+        Published,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Article {
+        id: i32,
+        title: String,
+        status: Status,
+        published: Option<String>,
+        read_time: Option<i64>,
+        thumbnail: Vec<u8>,
+    }
+
+    fn columns(names: &[&str]) -> Vec<Column> {
+        names
+            .iter()
+            .map(|name| Column {
+                name: name.to_string(),
+                data_type: DbDataType::Other,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn struct_by_column_name() {
+        let columns = columns(&[
+            "title",
+            "id",
+            "status",
+            "published",
+            "read_time",
+            "thumbnail",
+            "extra",
+        ]);
+        let values = [
+            DbValue::Str("Hello".to_owned()),
+            DbValue::Int32(7),
+            DbValue::Str("published".to_owned()),
+            DbValue::Datetime((2024, 1, 2, 3, 4, 5, 6_000)),
+            DbValue::DbNull,
+            DbValue::Binary(vec![1, 2]),
+            DbValue::Unsupported,
+        ];
+        let article = Article::deserialize(RowDeserializer {
+            columns: &columns,
+            values: &values,
+        })
+        .unwrap();
+        assert_eq!(
+            article,
+            Article {
+                id: 7,
+                title: "Hello".to_owned(),
+                status: Status::Published,
+                published: Some("2024-01-02T03:04:05.000006".to_owned()),
+                read_time: None,
+                thumbnail: vec![1, 2],
+            }
+        );
+        assert_ne!(article.status, Status::Draft);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn tuple_by_position() {
+        let columns = columns(&["a", "b", "c"]);
+        let values = [
+            DbValue::Boolean(true),
+            DbValue::Date((2024, 2, 29)),
+            DbValue::Floating64(0.5),
+        ];
+        let row: (bool, chrono::NaiveDate, f64) = Deserialize::deserialize(RowDeserializer {
+            columns: &columns,
+            values: &values,
+        })
+        .unwrap();
+        assert_eq!(
+            row,
+            (
+                true,
+                chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                0.5
+            )
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let columns = columns(&["id"]);
+        let missing = Article::deserialize(RowDeserializer {
+            columns: &columns,
+            values: &[DbValue::Int32(1)],
+        });
+        assert!(missing.unwrap_err().to_string().contains("missing field"));
+
+        let unsupported = i32::deserialize(ValueDeserializer(&DbValue::Unsupported));
+        assert!(unsupported.is_err());
+    }
+}
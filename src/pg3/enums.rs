@@ -0,0 +1,74 @@
+use super::{format_decode_err, DbValue, Error};
+
+/// A Rust enum stored in a Postgres enum or text column as one of a fixed set of labels.
+///
+/// Derive it with [`PgEnum`](macro@PgEnum), which also implements [`Decode`](super::Decode)
+/// and the conversion into a [`ParameterValue`](super::ParameterValue) for the enum.
+///
+/// The postgres interface has no enum value, so labels are exchanged as text: select enum
+/// columns as `status::text`, and cast parameters as `$1::text::status`. Text columns need no
+/// casts.
+pub trait PgEnum: Sized {
+    /// The labels of every variant, in declaration order.
+    const LABELS: &'static [&'static str];
+
+    /// The label of this variant.
+    fn as_label(&self) -> &'static str;
+
+    /// The variant with `label`, if there is one.
+    fn from_label(label: &str) -> Option<Self>;
+}
+
+pub use spin_macro::PgEnum;
+
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    pub fn decode_enum<T: PgEnum>(value: &DbValue) -> Result<T, Error> {
+        match value {
+            DbValue::Str(label) => T::from_label(label).ok_or_else(|| {
+                Error::Decode(format!(
+                    "unknown label {label:?}, expected one of {:?}",
+                    T::LABELS
+                ))
+            }),
+            _ => Err(Error::Decode(format_decode_err("ENUM (as TEXT)", value))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Decode, ParameterValue};
+    use super::*;
+
+    #[derive(Debug, PartialEq, PgEnum)]
+    #[pg_enum(rename_all = "snake_case")]
+    enum Status {
+        Draft,
+        InReview,
+        #[pg_enum(rename = "live")]
+        Published,
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(Status::LABELS, ["draft", "in_review", "live"]);
+        assert_eq!(
+            Status::decode(&DbValue::Str("in_review".to_owned())).unwrap(),
+            Status::InReview
+        );
+        assert_eq!(
+            Option::<Status>::decode(&DbValue::Str("live".to_owned())).unwrap(),
+            Some(Status::Published)
+        );
+        let err = Status::decode(&DbValue::Str("Draft".to_owned())).unwrap_err();
+        assert!(err.to_string().contains("expected one of"));
+        assert!(Status::decode(&DbValue::Int32(0)).is_err());
+        assert!(matches!(
+            ParameterValue::from(Status::Draft),
+            ParameterValue::Str(s) if s == "draft"
+        ));
+    }
+}
@@ -0,0 +1,307 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{format_decode_err, DbValue, Decode, Error, ParameterValue};
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * MICROS_PER_SECOND;
+
+/// A Postgres `INTERVAL`.
+///
+/// Like Postgres, an interval keeps months, days and microseconds apart, since months and days
+/// vary in length. Intervals are exchanged as text: select them as `elapsed::text` and cast
+/// parameters as `$1::text::interval`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Interval {
+    /// The number of months.
+    pub months: i32,
+    /// The number of days.
+    pub days: i32,
+    /// The number of microseconds.
+    pub microseconds: i64,
+}
+
+impl Interval {
+    /// Create an interval.
+    pub fn new(months: i32, days: i32, microseconds: i64) -> Self {
+        Self {
+            months,
+            days,
+            microseconds,
+        }
+    }
+
+    /// The length of this interval, counting months as 30 days and days as 24 hours as Postgres
+    /// does when it needs a fixed length, as in `EXTRACT(EPOCH FROM ...)`.
+    ///
+    /// Returns `None` if the length does not fit in `i64::MAX` microseconds (about 292,000
+    /// years).
+    pub fn to_duration(&self) -> Option<chrono::Duration> {
+        let days = i64::from(self.months)
+            .checked_mul(30)?
+            .checked_add(i64::from(self.days))?;
+        let micros = days
+            .checked_mul(MICROS_PER_DAY)?
+            .checked_add(self.microseconds)?;
+        Some(chrono::Duration::microseconds(micros))
+    }
+}
+
+impl From<chrono::Duration> for Interval {
+    /// Convert a duration to an interval of microseconds, truncating any nanoseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration is longer than `i64::MAX` microseconds (about 292,000 years).
+    fn from(d: chrono::Duration) -> Self {
+        Self::new(
+            0,
+            0,
+            d.num_microseconds()
+                .expect("duration out of range for INTERVAL"),
+        )
+    }
+}
+
+/// Formats the interval in ISO 8601 format, such as `P1Y2M3DT4.5S`, which Postgres accepts as
+/// input whatever its `IntervalStyle`.
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (years, months) = (self.months / 12, self.months % 12);
+        write!(f, "P{years}Y{months}M{}DT", self.days)?;
+        let sign = if self.microseconds < 0 { "-" } else { "" };
+        let micros = self.microseconds.unsigned_abs();
+        let (secs, frac) = (micros / 1_000_000, micros % 1_000_000);
+        if frac == 0 {
+            write!(f, "{sign}{secs}S")
+        } else {
+            let frac = format!("{frac:06}");
+            write!(f, "{sign}{secs}.{}S", frac.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    /// Parse an interval in Postgres' default (`postgres`) output style, such as
+    /// `1 year 2 mons -3 days 04:05:06.7`, or in ISO 8601 format, such as `P1Y2M-3DT4H5M6.7S`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parsed = match s.strip_prefix('P') {
+            Some(iso) => parse_iso_8601(iso),
+            None => parse_postgres(s),
+        };
+        parsed.ok_or_else(|| Error::Decode(format!("invalid INTERVAL {s:?}")))
+    }
+}
+
+impl Decode for Interval {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) => s.parse(),
+            _ => Err(Error::Decode(format_decode_err(
+                "INTERVAL (as TEXT)",
+                value,
+            ))),
+        }
+    }
+}
+
+impl From<Interval> for ParameterValue {
+    fn from(v: Interval) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+fn parse_postgres(s: &str) -> Option<Interval> {
+    let mut interval = Interval::default();
+    let mut tokens = s.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token.contains(':') {
+            interval.microseconds = interval.microseconds.checked_add(parse_clock(token)?)?;
+            continue;
+        }
+        let n: i32 = token.parse().ok()?;
+        match tokens.next()?.trim_end_matches('s') {
+            "year" => interval.months = interval.months.checked_add(n.checked_mul(12)?)?,
+            "mon" => interval.months = interval.months.checked_add(n)?,
+            "day" => interval.days = interval.days.checked_add(n)?,
+            _ => return None,
+        }
+    }
+    (!s.is_empty()).then_some(interval)
+}
+
+/// Parse a `[-]HH:MM:SS[.ffffff]` clock into microseconds.
+fn parse_clock(clock: &str) -> Option<i64> {
+    let (negative, clock) = match clock.strip_prefix('-') {
+        Some(clock) => (true, clock),
+        None => (false, clock.strip_prefix('+').unwrap_or(clock)),
+    };
+    let mut parts = clock.splitn(3, ':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let seconds = parse_seconds(parts.next().unwrap_or("0"))?;
+    let micros = hours
+        .checked_mul(60)?
+        .checked_add(minutes)?
+        .checked_mul(60 * MICROS_PER_SECOND)?
+        .checked_add(seconds)?;
+    Some(if negative { -micros } else { micros })
+}
+
+/// Parse `[-]S[.ffffff]` seconds into microseconds.
+fn parse_seconds(s: &str) -> Option<i64> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() || frac.len() > 6 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let whole: i64 = whole.parse().ok()?;
+    let frac: i64 = format!("{frac:0<6}").parse().ok()?;
+    let micros = whole.checked_mul(MICROS_PER_SECOND)?.checked_add(frac)?;
+    Some(if negative { -micros } else { micros })
+}
+
+fn parse_iso_8601(s: &str) -> Option<Interval> {
+    let mut interval = Interval::default();
+    let (date, time) = s.split_once('T').unwrap_or((s, ""));
+    for (value, unit) in designated(date)? {
+        let n: i32 = value.parse().ok()?;
+        match unit {
+            'Y' => interval.months = interval.months.checked_add(n.checked_mul(12)?)?,
+            'M' => interval.months = interval.months.checked_add(n)?,
+            'W' => interval.days = interval.days.checked_add(n.checked_mul(7)?)?,
+            'D' => interval.days = interval.days.checked_add(n)?,
+            _ => return None,
+        }
+    }
+    for (value, unit) in designated(time)? {
+        let micros = match unit {
+            'H' => value
+                .parse::<i64>()
+                .ok()?
+                .checked_mul(60 * 60 * MICROS_PER_SECOND)?,
+            'M' => value
+                .parse::<i64>()
+                .ok()?
+                .checked_mul(60 * MICROS_PER_SECOND)?,
+            'S' => parse_seconds(value)?,
+            _ => return None,
+        };
+        interval.microseconds = interval.microseconds.checked_add(micros)?;
+    }
+    Some(interval)
+}
+
+/// Split an ISO 8601 duration part such as `1Y-2M3D` into its values and unit designators.
+fn designated(s: &str) -> Option<Vec<(&str, char)>> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c.is_ascii_alphabetic() {
+            if i == start {
+                return None;
+            }
+            parts.push((&s[start..i], c));
+            start = i + 1;
+        }
+    }
+    (start == s.len()).then_some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_postgres_style() {
+        let cases = [
+            ("00:00:00", Interval::new(0, 0, 0)),
+            ("1 day", Interval::new(0, 1, 0)),
+            (
+                "1 year 2 mons 3 days 04:05:06.789",
+                Interval::new(14, 3, 14_706_789_000),
+            ),
+            ("-1 days +02:03:00", Interval::new(0, -1, 7_380_000_000)),
+            ("1 mon -2 days", Interval::new(1, -2, 0)),
+            ("-00:00:01.5", Interval::new(0, 0, -1_500_000)),
+            ("100:00:00", Interval::new(0, 0, 360_000_000_000)),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(text.parse::<Interval>().unwrap(), expected, "{text}");
+        }
+        for invalid in ["", "1", "1 fortnight", "1:xx:00", "00:00:00.1234567"] {
+            assert!(invalid.parse::<Interval>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn round_trips_iso_8601() {
+        let cases = [
+            (Interval::new(14, 3, 4_500_000), "P1Y2M3DT4.5S"),
+            (Interval::new(-1, 0, -1_250_000), "P0Y-1M0DT-1.25S"),
+            (Interval::new(0, 0, 3_600_000_000), "P0Y0M0DT3600S"),
+        ];
+        for (interval, text) in cases {
+            assert_eq!(interval.to_string(), text);
+            assert_eq!(text.parse::<Interval>().unwrap(), interval);
+        }
+        assert_eq!(
+            "P1WT1H30M".parse::<Interval>().unwrap(),
+            Interval::new(0, 7, 5_400_000_000)
+        );
+        assert!("P1X".parse::<Interval>().is_err());
+        assert!("PT1H5".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        for text in [
+            "2147483647 mons 1 mon",
+            "-2147483648 days -1 day",
+            "2562047789:00:00",
+            "9223372036854775807:00:00",
+            "00:00:9223372036854775807",
+            "PT2562047789H",
+            "P2147483647D1D",
+            "PT9223372036854S9223372036854S",
+        ] {
+            assert!(text.parse::<Interval>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn decodes() {
+        let interval = Interval::decode(&DbValue::Str("1 mon 1 day 00:00:01".to_owned())).unwrap();
+        assert_eq!(interval, Interval::new(1, 1, 1_000_000));
+        assert_eq!(
+            interval.to_duration(),
+            Some(chrono::Duration::days(31) + chrono::Duration::seconds(1))
+        );
+        assert_eq!(
+            chrono::Duration::decode(&DbValue::Str("01:30:00".to_owned())).unwrap(),
+            chrono::Duration::minutes(90)
+        );
+        assert!(Interval::decode(&DbValue::Int64(1)).is_err());
+
+        // Postgres accepts intervals of up to about 178 million years, far longer than a
+        // duration can hold.
+        let long = DbValue::Str("178000000 years".to_owned());
+        let interval = Interval::decode(&long).unwrap();
+        assert_eq!(interval, Interval::new(2_136_000_000, 0, 0));
+        assert_eq!(interval.to_duration(), None);
+        assert_eq!(Interval::new(0, i32::MAX, i64::MAX).to_duration(), None);
+        assert!(matches!(
+            chrono::Duration::decode(&long),
+            Err(Error::Decode(_))
+        ));
+        assert!(matches!(
+            ParameterValue::from(Interval::from(chrono::Duration::milliseconds(-1500))),
+            ParameterValue::Str(s) if s == "P0Y0M0DT-1.5S"
+        ));
+    }
+}
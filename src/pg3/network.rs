@@ -0,0 +1,161 @@
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use super::{format_decode_err, DbValue, Decode, Error, ParameterValue};
+
+impl Decode for IpAddr {
+    /// Decode the address of an `INET` or `CIDR` value, dropping any network prefix length.
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) => {
+                let address = s.split_once('/').map_or(s.as_str(), |(address, _)| address);
+                address
+                    .parse()
+                    .map_err(|e| Error::Decode(format!("invalid INET {s:?}: {e}")))
+            }
+            _ => Err(Error::Decode(format_decode_err("INET (as TEXT)", value))),
+        }
+    }
+}
+
+impl From<IpAddr> for ParameterValue {
+    fn from(v: IpAddr) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+#[cfg(feature = "ipnet")]
+impl Decode for ipnet::IpNet {
+    /// Decode an `INET` or `CIDR` value. Addresses without a prefix length, as Postgres writes
+    /// single-host `INET` values, get the full-length prefix.
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) if s.contains('/') => s
+                .parse()
+                .map_err(|e| Error::Decode(format!("invalid CIDR {s:?}: {e}"))),
+            DbValue::Str(_) => Ok(IpAddr::decode(value)?.into()),
+            _ => Err(Error::Decode(format_decode_err(
+                "INET, CIDR (as TEXT)",
+                value,
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "ipnet")]
+impl From<ipnet::IpNet> for ParameterValue {
+    fn from(v: ipnet::IpNet) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+/// A Postgres `MACADDR`, a six-byte hardware address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MacAddr(pub [u8; 6]);
+
+/// Formats the address as Postgres does, such as `08:00:2b:01:02:03`.
+impl Display for MacAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = Error;
+
+    /// Parse six hexadecimal bytes separated by `:` or `-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Decode(format!("invalid MACADDR {s:?}"));
+        let mut bytes = [0; 6];
+        let mut parts = s.split([':', '-']);
+        for byte in &mut bytes {
+            let part = parts.next().filter(|p| p.len() == 2).ok_or_else(invalid)?;
+            *byte = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+        }
+        match parts.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(Self(bytes)),
+        }
+    }
+}
+
+impl Decode for MacAddr {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) => s.parse(),
+            _ => Err(Error::Decode(format_decode_err("MACADDR (as TEXT)", value))),
+        }
+    }
+}
+
+impl From<MacAddr> for ParameterValue {
+    fn from(v: MacAddr) -> ParameterValue {
+        ParameterValue::Str(v.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    fn text(s: &str) -> DbValue {
+        DbValue::Str(s.to_owned())
+    }
+
+    #[test]
+    fn ip_addr() {
+        assert_eq!(
+            IpAddr::decode(&text("192.168.0.1")).unwrap(),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1))
+        );
+        assert_eq!(
+            IpAddr::decode(&text("10.1.2.3/8")).unwrap(),
+            IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))
+        );
+        assert_eq!(
+            IpAddr::decode(&text("::1")).unwrap(),
+            IpAddr::V6(Ipv6Addr::LOCALHOST)
+        );
+        assert!(IpAddr::decode(&text("localhost")).is_err());
+        assert!(IpAddr::decode(&DbValue::Binary(vec![127, 0, 0, 1])).is_err());
+        assert!(matches!(
+            ParameterValue::from(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+            ParameterValue::Str(s) if s == "::1"
+        ));
+    }
+
+    #[cfg(feature = "ipnet")]
+    #[test]
+    fn ip_net() {
+        use ipnet::IpNet;
+
+        let net: IpNet = "10.0.0.0/8".parse().unwrap();
+        assert_eq!(IpNet::decode(&text("10.0.0.0/8")).unwrap(), net);
+        assert_eq!(
+            IpNet::decode(&text("192.168.0.1")).unwrap(),
+            "192.168.0.1/32".parse::<IpNet>().unwrap()
+        );
+        assert!(IpNet::decode(&text("10.0.0.0/40")).is_err());
+        assert!(matches!(ParameterValue::from(net), ParameterValue::Str(s) if s == "10.0.0.0/8"));
+    }
+
+    #[test]
+    fn mac_addr() {
+        let mac = MacAddr([0x08, 0x00, 0x2b, 0x01, 0x02, 0x03]);
+        assert_eq!(MacAddr::decode(&text("08:00:2b:01:02:03")).unwrap(), mac);
+        assert_eq!("08-00-2B-01-02-03".parse::<MacAddr>().unwrap(), mac);
+        assert_eq!(mac.to_string(), "08:00:2b:01:02:03");
+        for invalid in [
+            "08:00:2b:01:02",
+            "08:00:2b:01:02:03:04",
+            "8:00:2b:01:02:03",
+            "zz:00:2b:01:02:03",
+        ] {
+            assert!(invalid.parse::<MacAddr>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::{Connection, PgError};
+
+impl Connection {
+    /// Send a notification with `payload` to everyone listening on `channel`, as with Postgres'
+    /// `NOTIFY` command.
+    ///
+    /// As with `NOTIFY`, the notification is delivered when the current transaction commits, or
+    /// immediately outside a transaction.
+    ///
+    /// Receiving notifications requires a connection which stays open and is polled for
+    /// messages, which the postgres interface does not offer, so there is no `listen`
+    /// counterpart: listen from a long-running service, or have listeners poll a table instead.
+    ///
+    /// ```ignore
+    /// conn.notify("orders", &order_id.to_string())?;
+    /// ```
+    pub fn notify(&self, channel: &str, payload: &str) -> Result<(), PgError> {
+        // `pg_notify` takes the channel as a parameter, so it does not need quoting.
+        self.query(
+            "SELECT pg_notify($1, $2)",
+            &[channel.to_owned().into(), payload.to_owned().into()],
+        )
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,192 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{format_decode_err, DbValue, Decode, Error, ParameterValue};
+
+/// An exact-precision `NUMERIC`/`DECIMAL` value, held as its decimal text.
+///
+/// The postgres interface has no numeric value, so numerics are exchanged as text without any
+/// loss of precision. Use [`Numeric::parse`] to convert to a decimal type of your choice; with
+/// the `decimal` feature, `rust_decimal::Decimal` can also be decoded and used as a parameter
+/// directly.
+///
+/// ```ignore
+/// let rows = conn.query("SELECT price::text FROM products WHERE id = $1", &[id.into()])?;
+/// let price: rust_decimal::Decimal = Numeric::decode(&rows.rows[0][0])?.parse()?;
+///
+/// conn.execute(
+///     "UPDATE products SET price = $1::text::numeric WHERE id = $2",
+///     &[Numeric::from(price).into(), id.into()],
+/// )?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Numeric(String);
+
+impl Numeric {
+    /// The decimal text of this value, such as `-12.50`, `1e-3` or `NaN`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Parse this value into another type, such as a decimal or big integer type.
+    pub fn parse<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.0.parse()
+    }
+}
+
+impl FromStr for Numeric {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_numeric(s) {
+            Ok(Self(s.to_owned()))
+        } else {
+            Err(Error::Decode(format!("invalid NUMERIC {s:?}")))
+        }
+    }
+}
+
+impl Display for Numeric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! numeric_from_display {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Numeric {
+                fn from(v: $ty) -> Numeric {
+                    Numeric(v.to_string())
+                }
+            }
+        )*
+    };
+}
+
+numeric_from_display!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+#[cfg(feature = "decimal")]
+numeric_from_display!(rust_decimal::Decimal);
+
+#[cfg(feature = "decimal")]
+impl Decode for rust_decimal::Decimal {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        let numeric = Numeric::decode(value)?;
+        numeric
+            .parse::<rust_decimal::Decimal>()
+            .or_else(|_| rust_decimal::Decimal::from_scientific(numeric.as_str()))
+            .map_err(|e| Error::Decode(format!("NUMERIC {numeric} out of range: {e}")))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for ParameterValue {
+    fn from(v: rust_decimal::Decimal) -> ParameterValue {
+        Numeric::from(v).into()
+    }
+}
+
+impl Decode for Numeric {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Str(s) => s.parse(),
+            DbValue::Int8(n) => Ok((*n).into()),
+            DbValue::Int16(n) => Ok((*n).into()),
+            DbValue::Int32(n) => Ok((*n).into()),
+            DbValue::Int64(n) => Ok((*n).into()),
+            _ => Err(Error::Decode(format_decode_err("NUMERIC (as TEXT)", value))),
+        }
+    }
+}
+
+impl From<Numeric> for ParameterValue {
+    fn from(v: Numeric) -> ParameterValue {
+        ParameterValue::Str(v.0)
+    }
+}
+
+/// Whether `s` is a value Postgres accepts as a NUMERIC literal.
+fn is_numeric(s: &str) -> bool {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if ["NaN", "Infinity"]
+        .iter()
+        .any(|v| unsigned.eq_ignore_ascii_case(v))
+    {
+        return true;
+    }
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e.strip_prefix(['+', '-']).unwrap_or(e))),
+        None => (unsigned, None),
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    digits(int)
+        && digits(frac)
+        && !(int.is_empty() && frac.is_empty())
+        && exponent.map_or(true, |e| !e.is_empty() && digits(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_literals() {
+        for valid in [
+            "0",
+            "-12.50",
+            "+.5",
+            "3.",
+            "1e-3",
+            "2.5E+10",
+            "NaN",
+            "-infinity",
+        ] {
+            assert!(is_numeric(valid), "{valid}");
+        }
+        for invalid in ["", ".", "-", "1.2.3", "1e", "e5", "12a", "1 000"] {
+            assert!(!is_numeric(invalid), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn decodes() {
+        let price = Numeric::decode(&DbValue::Str("19.990".to_owned())).unwrap();
+        assert_eq!(price.as_str(), "19.990");
+        assert_eq!(price.parse::<f64>().unwrap(), 19.99);
+        assert_eq!(
+            Numeric::decode(&DbValue::Int64(-7)).unwrap(),
+            Numeric::from(-7i64)
+        );
+        assert!(Numeric::decode(&DbValue::Str("abc".to_owned())).is_err());
+        assert!(Numeric::decode(&DbValue::Floating64(1.5)).is_err());
+        assert!(Option::<Numeric>::decode(&DbValue::DbNull)
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            ParameterValue::from(price),
+            ParameterValue::Str(s) if s == "19.990"
+        ));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimal() {
+        use rust_decimal::Decimal;
+
+        let price = Decimal::new(1999, 2);
+        assert_eq!(
+            Decimal::decode(&DbValue::Str("19.99".to_owned())).unwrap(),
+            price
+        );
+        assert_eq!(
+            Decimal::decode(&DbValue::Str("1.5e2".to_owned())).unwrap(),
+            Decimal::new(150, 0)
+        );
+        assert!(Decimal::decode(&DbValue::Str("NaN".to_owned())).is_err());
+        assert!(matches!(
+            ParameterValue::from(price),
+            ParameterValue::Str(s) if s == "19.99"
+        ));
+    }
+}
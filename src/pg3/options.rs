@@ -0,0 +1,313 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use super::{Connection, PgError};
+
+/// How a connection negotiates TLS with the server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SslMode {
+    /// Never use TLS.
+    Disable,
+    /// Use TLS if the server supports it.
+    #[default]
+    Prefer,
+    /// Fail to connect unless TLS is used.
+    Require,
+}
+
+impl SslMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+        }
+    }
+}
+
+/// Structured settings for opening a [`Connection`], as an alternative to writing a connection
+/// string by hand.
+///
+/// ```ignore
+/// let conn = ConnectionOptions::new("db.example.com")
+///     .user("app")
+///     .password(&variables::get("db_password")?)
+///     .database("orders")
+///     .ssl_mode(SslMode::Require)
+///     .statement_timeout(Duration::from_secs(5))
+///     .open()?;
+/// ```
+///
+/// The password is redacted from the `Debug` output.
+#[derive(Clone, Default)]
+pub struct ConnectionOptions {
+    host: String,
+    port: Option<u16>,
+    database: Option<String>,
+    user: Option<String>,
+    password: Option<String>,
+    ssl_mode: Option<SslMode>,
+    connect_timeout: Option<Duration>,
+    statement_timeout: Option<Duration>,
+    application_name: Option<String>,
+}
+
+impl ConnectionOptions {
+    /// Create options for connecting to the server at `host`.
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the port, 5432 by default.
+    pub fn port(&mut self, port: u16) -> &mut Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Set the database name, which defaults to the user name.
+    pub fn database(&mut self, database: impl Into<String>) -> &mut Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// Set the user to authenticate as.
+    pub fn user(&mut self, user: impl Into<String>) -> &mut Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Set the password to authenticate with.
+    pub fn password(&mut self, password: impl Into<String>) -> &mut Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Set whether TLS is used.
+    pub fn ssl_mode(&mut self, ssl_mode: SslMode) -> &mut Self {
+        self.ssl_mode = Some(ssl_mode);
+        self
+    }
+
+    /// Set how long to wait for the connection to be established, in whole seconds.
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long the server lets a statement run before cancelling it, in whole
+    /// milliseconds.
+    pub fn statement_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the name the server reports for this connection, as in `pg_stat_activity`.
+    pub fn application_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.application_name = Some(name.into());
+        self
+    }
+
+    /// Build the connection string for these options.
+    pub fn connection_string(&self) -> Result<String, OptionsError> {
+        if self.host.trim().is_empty() {
+            return Err(OptionsError::MissingHost);
+        }
+        if self.port == Some(0) {
+            return Err(OptionsError::Invalid {
+                option: "port",
+                reason: "must not be 0".to_owned(),
+            });
+        }
+        if self.user.as_deref().is_some_and(str::is_empty) {
+            return Err(OptionsError::Invalid {
+                option: "user",
+                reason: "must not be empty".to_owned(),
+            });
+        }
+        if self.password.is_some() && self.user.is_none() {
+            return Err(OptionsError::Invalid {
+                option: "password",
+                reason: "a user must be set with it".to_owned(),
+            });
+        }
+        let connect_timeout = match self.connect_timeout {
+            Some(timeout) if timeout.as_secs() == 0 => {
+                return Err(OptionsError::Invalid {
+                    option: "connect_timeout",
+                    reason: "must be at least one second".to_owned(),
+                })
+            }
+            timeout => timeout.map(|t| t.as_secs()),
+        };
+        let statement_timeout = match self.statement_timeout {
+            Some(timeout) if timeout.as_millis() == 0 => {
+                return Err(OptionsError::Invalid {
+                    option: "statement_timeout",
+                    reason: "must be at least one millisecond".to_owned(),
+                })
+            }
+            timeout => timeout.map(|t| t.as_millis()),
+        };
+
+        let mut s = String::new();
+        let mut param = |key: &str, value: &str| {
+            if !s.is_empty() {
+                s.push(' ');
+            }
+            write!(s, "{key}={}", quote(value)).unwrap();
+        };
+        param("host", &self.host);
+        if let Some(port) = self.port {
+            param("port", &port.to_string());
+        }
+        if let Some(database) = &self.database {
+            param("dbname", database);
+        }
+        if let Some(user) = &self.user {
+            param("user", user);
+        }
+        if let Some(password) = &self.password {
+            param("password", password);
+        }
+        if let Some(ssl_mode) = self.ssl_mode {
+            param("sslmode", ssl_mode.as_str());
+        }
+        if let Some(timeout) = connect_timeout {
+            param("connect_timeout", &timeout.to_string());
+        }
+        if let Some(timeout) = statement_timeout {
+            param("options", &format!("-c statement_timeout={timeout}"));
+        }
+        if let Some(name) = &self.application_name {
+            param("application_name", name);
+        }
+        Ok(s)
+    }
+
+    /// Open a connection with these options.
+    pub fn open(&self) -> Result<Connection, OptionsError> {
+        Ok(Connection::open(&self.connection_string()?)?)
+    }
+}
+
+impl std::fmt::Debug for ConnectionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionOptions")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("database", &self.database)
+            .field("user", &self.user)
+            .field("password", &self.password.as_ref().map(|_| "[REDACTED]"))
+            .field("ssl_mode", &self.ssl_mode)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("statement_timeout", &self.statement_timeout)
+            .field("application_name", &self.application_name)
+            .finish()
+    }
+}
+
+/// Quote a connection string value if it is empty or contains spaces, quotes or backslashes.
+fn quote(value: &str) -> String {
+    if !value.is_empty() && !value.contains([' ', '\'', '\\']) {
+        return value.to_owned();
+    }
+    let mut quoted = String::from("'");
+    for c in value.chars() {
+        if c == '\'' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// An error opening a connection from [`ConnectionOptions`].
+#[derive(Debug, thiserror::Error)]
+pub enum OptionsError {
+    /// No host was given.
+    #[error("a host is required to connect to Postgres")]
+    MissingHost,
+    /// An option has an invalid value.
+    #[error("invalid `{option}` option: {reason}")]
+    Invalid {
+        /// The name of the option.
+        option: &'static str,
+        /// Why the value is invalid.
+        reason: String,
+    },
+    /// The connection could not be opened.
+    #[error(transparent)]
+    Connect(#[from] PgError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_connection_string() {
+        let s = ConnectionOptions::new("db.internal")
+            .port(6543)
+            .database("orders")
+            .user("app")
+            .password("it's a s\\ecret")
+            .ssl_mode(SslMode::Require)
+            .connect_timeout(Duration::from_secs(3))
+            .statement_timeout(Duration::from_millis(2500))
+            .application_name("checkout")
+            .connection_string()
+            .unwrap();
+        assert_eq!(
+            s,
+            "host=db.internal port=6543 dbname=orders user=app password='it\\'s a s\\\\ecret' \
+             sslmode=require connect_timeout=3 options='-c statement_timeout=2500' \
+             application_name=checkout"
+        );
+        assert_eq!(
+            ConnectionOptions::new("localhost")
+                .connection_string()
+                .unwrap(),
+            "host=localhost"
+        );
+    }
+
+    #[test]
+    fn validates() {
+        assert!(matches!(
+            ConnectionOptions::new(" ").connection_string(),
+            Err(OptionsError::MissingHost)
+        ));
+        let err = ConnectionOptions::new("localhost")
+            .password("secret")
+            .connection_string()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid `password` option: a user must be set with it"
+        );
+        assert!(ConnectionOptions::new("localhost")
+            .port(0)
+            .connection_string()
+            .is_err());
+        assert!(ConnectionOptions::new("localhost")
+            .connect_timeout(Duration::from_millis(500))
+            .connection_string()
+            .is_err());
+    }
+
+    #[test]
+    fn redacts_password() {
+        let options = ConnectionOptions::new("db.internal")
+            .user("app")
+            .password("hunter2")
+            .clone();
+        let debug = format!("{options:?}");
+        assert!(debug.contains("\"app\""));
+        assert!(debug.contains("[REDACTED]"));
+        assert!(!debug.contains("hunter2"));
+    }
+}
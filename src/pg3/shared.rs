@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::{Connection, PgError};
+use crate::host_error::is_connection_failure;
+
+thread_local! {
+    static SHARED: RefCell<HashMap<String, Rc<Connection>>> = RefCell::new(HashMap::new());
+}
+
+/// Get a connection to `address` which is opened on first use and then shared for the life of
+/// the component instance.
+///
+/// This saves every part of a component, such as the handlers of a
+/// [`Router`](crate::http::Router), from paying for its own connection and authentication.
+/// Spin currently creates a fresh instance for each request, so the connection is shared within
+/// a request; on hosts that reuse instances it is also reused across requests. To also recover
+/// from a connection which has been dropped, for example by a database restart, use
+/// [`with_shared`].
+///
+/// Statements on a shared connection run in order, so avoid leaving a
+/// [`Transaction`](super::Transaction) open on one between uses.
+///
+/// ```ignore
+/// let conn = pg3::shared(&std::env::var("DB_URL")?)?;
+/// let rows = conn.query("SELECT title FROM articles", &[])?;
+/// ```
+pub fn shared(address: &str) -> Result<Rc<Connection>, PgError> {
+    if let Some(conn) = SHARED.with(|shared| shared.borrow().get(address).cloned()) {
+        return Ok(conn);
+    }
+    let conn = Rc::new(Connection::open(address)?);
+    SHARED.with(|shared| {
+        shared
+            .borrow_mut()
+            .insert(address.to_owned(), Rc::clone(&conn))
+    });
+    Ok(conn)
+}
+
+/// Run `f` with the [`shared`] connection to `address`, reconnecting and running it once more
+/// if it fails because the connection was lost.
+///
+/// `f` may run twice, so it should only run statements which are safe to repeat.
+///
+/// ```ignore
+/// let rows = pg3::with_shared(&address, |conn| conn.query("SELECT title FROM articles", &[]))?;
+/// ```
+pub fn with_shared<T>(
+    address: &str,
+    f: impl Fn(&Connection) -> Result<T, PgError>,
+) -> Result<T, PgError> {
+    match f(&*shared(address)?) {
+        Err(e) if is_connection_error(&e) => {
+            SHARED.with(|shared| shared.borrow_mut().remove(address));
+            f(&*shared(address)?)
+        }
+        result => result,
+    }
+}
+
+/// Whether an error means the connection itself failed, rather than the statement.
+fn is_connection_error(error: &PgError) -> bool {
+    match error {
+        PgError::ConnectionFailed(_) => true,
+        PgError::QueryFailed(message) | PgError::Other(message) => is_connection_failure(message),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_connection_errors() {
+        assert!(is_connection_error(&PgError::QueryFailed(
+            "error communicating with database: Connection reset by peer (os error 104)".to_owned()
+        )));
+        assert!(is_connection_error(&PgError::Other(
+            "connection closed".to_owned()
+        )));
+        assert!(!is_connection_error(&PgError::QueryFailed(
+            "relation \"articles\" does not exist".to_owned()
+        )));
+        assert!(!is_connection_error(&PgError::BadParameter(
+            "expected 2 parameters".to_owned()
+        )));
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Error, PgError, QueryError};
+
+/// `unique_violation`
+const UNIQUE_VIOLATION: &str = "23505";
+/// `foreign_key_violation`
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+/// `not_null_violation`
+const NOT_NULL_VIOLATION: &str = "23502";
+/// `check_violation`
+const CHECK_VIOLATION: &str = "23514";
+/// `serialization_failure`
+const SERIALIZATION_FAILURE: &str = "40001";
+/// `deadlock_detected`
+const DEADLOCK_DETECTED: &str = "40P01";
+
+impl PgError {
+    /// The five-character SQLSTATE code of the error, such as `23505`, if the server reported
+    /// one.
+    ///
+    /// The `spin:postgres` interface reports errors as text, so the code is recovered from the
+    /// host's error message. It is `None` for errors which did not come from the server, such as
+    /// connection failures.
+    pub fn sqlstate(&self) -> Option<&str> {
+        match self {
+            PgError::QueryFailed(message) | PgError::Other(message) => find_sqlstate(message),
+            _ => None,
+        }
+    }
+
+    /// Whether the error is a violation of a unique constraint (SQLSTATE `23505`).
+    pub fn is_unique_violation(&self) -> bool {
+        self.sqlstate() == Some(UNIQUE_VIOLATION)
+    }
+
+    /// Whether the error is a violation of a foreign key constraint (SQLSTATE `23503`).
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.sqlstate() == Some(FOREIGN_KEY_VIOLATION)
+    }
+
+    /// Whether the error is a violation of a `NOT NULL` constraint (SQLSTATE `23502`).
+    pub fn is_not_null_violation(&self) -> bool {
+        self.sqlstate() == Some(NOT_NULL_VIOLATION)
+    }
+
+    /// Whether the error is a violation of a `CHECK` constraint (SQLSTATE `23514`).
+    pub fn is_check_violation(&self) -> bool {
+        self.sqlstate() == Some(CHECK_VIOLATION)
+    }
+
+    /// Whether a serializable transaction failed because of a concurrent transaction
+    /// (SQLSTATE `40001`). Such transactions can be retried.
+    pub fn is_serialization_failure(&self) -> bool {
+        self.sqlstate() == Some(SERIALIZATION_FAILURE)
+    }
+
+    /// Whether the statement was aborted to resolve a deadlock (SQLSTATE `40P01`). Such
+    /// transactions can be retried.
+    pub fn is_deadlock(&self) -> bool {
+        self.sqlstate() == Some(DEADLOCK_DETECTED)
+    }
+}
+
+impl Error {
+    /// The SQLSTATE code of the error, if it came from the server. See [`PgError::sqlstate`].
+    pub fn sqlstate(&self) -> Option<&str> {
+        match self {
+            Error::PgError(e) => e.sqlstate(),
+            _ => None,
+        }
+    }
+
+    /// Whether the error is a violation of a unique constraint (SQLSTATE `23505`).
+    pub fn is_unique_violation(&self) -> bool {
+        self.sqlstate() == Some(UNIQUE_VIOLATION)
+    }
+
+    /// Whether the error is a violation of a foreign key constraint (SQLSTATE `23503`).
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.sqlstate() == Some(FOREIGN_KEY_VIOLATION)
+    }
+
+    /// Whether the error is a violation of a `NOT NULL` constraint (SQLSTATE `23502`).
+    pub fn is_not_null_violation(&self) -> bool {
+        self.sqlstate() == Some(NOT_NULL_VIOLATION)
+    }
+
+    /// Whether the error is a violation of a `CHECK` constraint (SQLSTATE `23514`).
+    pub fn is_check_violation(&self) -> bool {
+        self.sqlstate() == Some(CHECK_VIOLATION)
+    }
+
+    /// Whether a serializable transaction failed because of a concurrent transaction
+    /// (SQLSTATE `40001`).
+    pub fn is_serialization_failure(&self) -> bool {
+        self.sqlstate() == Some(SERIALIZATION_FAILURE)
+    }
+
+    /// Whether the statement was aborted to resolve a deadlock (SQLSTATE `40P01`).
+    pub fn is_deadlock(&self) -> bool {
+        self.sqlstate() == Some(DEADLOCK_DETECTED)
+    }
+}
+
+impl QueryError {
+    /// The SQLSTATE code of the error, if it came from the server. See [`PgError::sqlstate`].
+    pub fn sqlstate(&self) -> Option<&str> {
+        match self {
+            QueryError::Pg(e) => e.sqlstate(),
+            _ => None,
+        }
+    }
+}
+
+/// Find a SQLSTATE code in an error message, as formatted by `tokio-postgres`
+/// (`SqlState(E23505)`, `SqlState(Other("XX000"))`) or as a `SQLSTATE 23505` suffix.
+fn find_sqlstate(message: &str) -> Option<&str> {
+    const MARKERS: [&str; 3] = ["SqlState(E", "SqlState(Other(\"", "SQLSTATE "];
+    MARKERS.iter().find_map(|marker| {
+        let start = message.find(marker)? + marker.len();
+        let code = message.get(start..start + 5)?;
+        code.bytes()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+            .then_some(code)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_sqlstate() {
+        let unique = PgError::QueryFailed(
+            "Error { kind: Db, cause: Some(DbError { severity: \"ERROR\", code: SqlState(E23505), \
+             message: \"duplicate key value violates unique constraint \\\"users_email_key\\\"\" }) }"
+                .to_owned(),
+        );
+        assert_eq!(unique.sqlstate(), Some("23505"));
+        assert!(unique.is_unique_violation());
+        assert!(!unique.is_foreign_key_violation());
+
+        let deadlock = Error::PgError(PgError::QueryFailed(
+            "db error: ERROR: deadlock detected (SQLSTATE 40P01)".to_owned(),
+        ));
+        assert!(deadlock.is_deadlock());
+
+        let other = PgError::Other("code: SqlState(Other(\"XX001\"))".to_owned());
+        assert_eq!(other.sqlstate(), Some("XX001"));
+
+        assert_eq!(
+            PgError::ConnectionFailed("SqlState(E23505)".to_owned()).sqlstate(),
+            None
+        );
+        assert_eq!(
+            PgError::QueryFailed("syntax error".to_owned()).sqlstate(),
+            None
+        );
+        assert_eq!(QueryError::NoRows.sqlstate(), None);
+        assert_eq!(QueryError::from(deadlock).sqlstate(), Some("40P01"));
+    }
+}
@@ -0,0 +1,113 @@
+use std::hash::{Hash, Hasher};
+
+pub use super::wit::v2::redis::{Connection, Error, Payload, RedisParameter, RedisResult};
+
+#[cfg(feature = "json")]
+mod channel;
+mod codec;
+mod expiry;
+mod hash;
+mod list;
+#[cfg(feature = "json")]
+mod message;
+mod outcome;
+mod pipeline;
+mod reply;
+mod script;
+mod sets;
+mod shared;
+mod strings;
+#[cfg(feature = "json")]
+pub use crate::http::Json;
+#[cfg(feature = "json")]
+pub use channel::Channel;
+pub use expiry::Ttl;
+#[doc(hidden)]
+pub use outcome::__private;
+pub use outcome::{IntoOutcome, Outcome};
+pub use pipeline::Pipeline;
+pub use reply::FromRedisReply;
+pub use script::Script;
+pub use shared::{shared, with_shared};
+pub use strings::SetOptions;
+
+impl PartialEq for RedisResult {
+    fn eq(&self, other: &Self) -> bool {
+        use RedisResult::*;
+        match (self, other) {
+            (Nil, Nil) => true,
+            (Status(a), Status(b)) => a == b,
+            (Int64(a), Int64(b)) => a == b,
+            (Binary(a), Binary(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for RedisResult {}
+
+impl Hash for RedisResult {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        use RedisResult::*;
+
+        match self {
+            Nil => (),
+            Status(s) => s.hash(state),
+            Int64(v) => v.hash(state),
+            Binary(v) => v.hash(state),
+        }
+    }
+}
+
+impl From<i64> for RedisParameter {
+    fn from(v: i64) -> Self {
+        RedisParameter::Int64(v)
+    }
+}
+
+impl From<&str> for RedisParameter {
+    fn from(v: &str) -> Self {
+        RedisParameter::Binary(v.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for RedisParameter {
+    fn from(v: String) -> Self {
+        RedisParameter::Binary(v.into_bytes())
+    }
+}
+
+impl From<&[u8]> for RedisParameter {
+    fn from(v: &[u8]) -> Self {
+        RedisParameter::Binary(v.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for RedisParameter {
+    fn from(v: Vec<u8>) -> Self {
+        RedisParameter::Binary(v)
+    }
+}
+
+/// Read the reply of a command which returns a single integer.
+fn integer(results: Vec<RedisResult>) -> Result<i64, Error> {
+    match results.as_slice() {
+        [RedisResult::Int64(n)] => Ok(*n),
+        _ => Err(Error::TypeError),
+    }
+}
+
+/// Read the reply of a command which returns a single value or nil.
+fn optional(results: Vec<RedisResult>) -> Result<Option<Payload>, Error> {
+    match results.into_iter().next() {
+        None | Some(RedisResult::Nil) => Ok(None),
+        Some(RedisResult::Binary(b)) => Ok(Some(b)),
+        Some(RedisResult::Status(s)) => Ok(Some(s.into_bytes())),
+        Some(RedisResult::Int64(n)) => Ok(Some(n.to_string().into_bytes())),
+    }
+}
+
+/// Read a reply made of values, such as a list of members.
+fn values(results: Vec<RedisResult>) -> Result<Vec<Payload>, Error> {
+    results.into_iter().map(TryInto::try_into).collect()
+}
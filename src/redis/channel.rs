@@ -0,0 +1,88 @@
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::Connection;
+
+/// A pub/sub channel whose messages are `T`s, sent as JSON.
+///
+/// Sharing one `Channel` definition between the publishing component and the
+/// [`redis_component`](crate::redis_component) subscribed to it keeps both sides agreed on the
+/// channel name and the message type.
+///
+/// ```ignore
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct OrderPlaced { id: u64, total_cents: u64 }
+///
+/// const ORDERS: Channel<OrderPlaced> = Channel::new("orders");
+///
+/// // Publishing side
+/// ORDERS.publish(&conn, &OrderPlaced { id: 7, total_cents: 1250 })?;
+///
+/// // Subscribing side, with `channel = "orders"` in `spin.toml`
+/// #[redis_component]
+/// fn on_message(message: Bytes) -> anyhow::Result<()> {
+///     let order = ORDERS.decode(&message)?;
+///     ...
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Channel<T> {
+    name: &'static str,
+    message: PhantomData<fn() -> T>,
+}
+
+impl<T> Channel<T> {
+    /// Create a channel with the given name.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            message: PhantomData,
+        }
+    }
+
+    /// The name of the channel.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: Serialize> Channel<T> {
+    /// Serialize `message` to JSON and publish it on the channel.
+    pub fn publish(&self, conn: &Connection, message: &T) -> Result<(), anyhow::Error> {
+        Ok(conn.publish(self.name, &serde_json::to_vec(message)?)?)
+    }
+}
+
+impl<T: DeserializeOwned> Channel<T> {
+    /// Deserialize a message received on the channel.
+    pub fn decode(&self, payload: &[u8]) -> Result<T, anyhow::Error> {
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Channel<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_messages() {
+        #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+        struct Ping {
+            seq: u32,
+        }
+
+        const PINGS: Channel<Ping> = Channel::new("pings");
+        assert_eq!(PINGS.name(), "pings");
+        assert_eq!(PINGS.decode(br#"{"seq":3}"#).unwrap(), Ping { seq: 3 });
+        assert!(PINGS.decode(b"not json").is_err());
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(any(feature = "json", feature = "msgpack"))]
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::Connection;
+
+impl Connection {
+    #[cfg(feature = "json")]
+    /// Serialize the given data to JSON, then set it as the value for the specified `key`.
+    pub fn set_json<T: Serialize>(
+        &self,
+        key: impl AsRef<str>,
+        value: &T,
+    ) -> Result<(), anyhow::Error> {
+        Ok(self.set(key.as_ref(), &serde_json::to_vec(value)?)?)
+    }
+
+    #[cfg(feature = "json")]
+    /// Deserialize an instance of type `T` from the JSON value of `key`.
+    pub fn get_json<T: DeserializeOwned>(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<Option<T>, anyhow::Error> {
+        let Some(value) = self.get(key.as_ref())? else {
+            return Ok(None);
+        };
+        Ok(serde_json::from_slice(&value)?)
+    }
+
+    #[cfg(feature = "msgpack")]
+    /// Serialize the given data to MessagePack, then set it as the value for the specified
+    /// `key`.
+    ///
+    /// Structs are encoded as maps, so fields can be added or reordered without breaking
+    /// values which are already stored.
+    pub fn set_msgpack<T: Serialize>(
+        &self,
+        key: impl AsRef<str>,
+        value: &T,
+    ) -> Result<(), anyhow::Error> {
+        Ok(self.set(key.as_ref(), &rmp_serde::to_vec_named(value)?)?)
+    }
+
+    #[cfg(feature = "msgpack")]
+    /// Deserialize an instance of type `T` from the MessagePack value of `key`.
+    pub fn get_msgpack<T: DeserializeOwned>(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<Option<T>, anyhow::Error> {
+        let Some(value) = self.get(key.as_ref())? else {
+            return Ok(None);
+        };
+        Ok(rmp_serde::from_slice(&value)?)
+    }
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use super::{integer, Connection, Error, SetOptions};
+
+/// The time to live of a key, as reported by [`Connection::ttl`] and [`Connection::pttl`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ttl {
+    /// The key does not exist.
+    NoKey,
+    /// The key exists but does not expire.
+    NoExpiry,
+    /// The key expires after this long.
+    Expires(Duration),
+}
+
+impl Ttl {
+    fn from_reply(n: i64, unit: fn(u64) -> Duration) -> Result<Self, Error> {
+        match n {
+            -2 => Ok(Ttl::NoKey),
+            -1 => Ok(Ttl::NoExpiry),
+            n => u64::try_from(n)
+                .map(|n| Ttl::Expires(unit(n)))
+                .map_err(|_| Error::TypeError),
+        }
+    }
+}
+
+impl Connection {
+    /// Set `key` to expire after `ttl`, with millisecond precision.
+    ///
+    /// Returns `false` if the key does not exist.
+    pub fn expire(&self, key: &str, ttl: Duration) -> Result<bool, Error> {
+        let millis = millis(ttl)?;
+        Ok(integer(self.execute("PEXPIRE", &[key.into(), millis.into()])?)? == 1)
+    }
+
+    /// Remove the expiry of `key`, so it is kept until deleted.
+    ///
+    /// Returns `false` if the key does not exist or had no expiry.
+    pub fn persist(&self, key: &str) -> Result<bool, Error> {
+        Ok(integer(self.execute("PERSIST", &[key.into()])?)? == 1)
+    }
+
+    /// Get the time to live of `key`, in whole seconds.
+    pub fn ttl(&self, key: &str) -> Result<Ttl, Error> {
+        Ttl::from_reply(
+            integer(self.execute("TTL", &[key.into()])?)?,
+            Duration::from_secs,
+        )
+    }
+
+    /// Get the time to live of `key`, in milliseconds.
+    pub fn pttl(&self, key: &str) -> Result<Ttl, Error> {
+        Ttl::from_reply(
+            integer(self.execute("PTTL", &[key.into()])?)?,
+            Duration::from_millis,
+        )
+    }
+
+    /// Set `key` to `value`, expiring after `ttl`, with millisecond precision.
+    pub fn set_ex(&self, key: &str, value: &[u8], ttl: Duration) -> Result<(), Error> {
+        let options = SetOptions {
+            px: Some(millis(ttl)?.unsigned_abs()),
+            ..Default::default()
+        };
+        self.set_with_options(key, value, options).map(|_| ())
+    }
+}
+
+/// Convert a time to live to milliseconds, which Redis requires to be positive.
+fn millis(ttl: Duration) -> Result<i64, Error> {
+    i64::try_from(ttl.as_millis())
+        .ok()
+        .filter(|ms| *ms > 0)
+        .ok_or_else(|| Error::Other(format!("invalid time to live: {ttl:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ttl_replies() {
+        assert_eq!(
+            Ttl::from_reply(-2, Duration::from_secs).unwrap(),
+            Ttl::NoKey
+        );
+        assert_eq!(
+            Ttl::from_reply(-1, Duration::from_secs).unwrap(),
+            Ttl::NoExpiry
+        );
+        assert_eq!(
+            Ttl::from_reply(1500, Duration::from_millis).unwrap(),
+            Ttl::Expires(Duration::from_millis(1500))
+        );
+        assert!(millis(Duration::from_micros(10)).is_err());
+        assert_eq!(millis(Duration::from_secs(2)).unwrap(), 2000);
+    }
+}
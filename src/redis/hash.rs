@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use super::{integer, optional, values, Connection, Error, Payload, RedisParameter};
+
+impl Connection {
+    /// Set `fields` of the hash at `key`, creating the hash if needed, and return the number of
+    /// fields which were added rather than updated.
+    pub fn hset(&self, key: &str, fields: &[(&str, &[u8])]) -> Result<u32, Error> {
+        let mut args: Vec<RedisParameter> = vec![key.into()];
+        for (field, value) in fields {
+            args.push((*field).into());
+            args.push((*value).into());
+        }
+        count(integer(self.execute("HSET", &args)?)?)
+    }
+
+    /// Get the value of `field` in the hash at `key`.
+    pub fn hget(&self, key: &str, field: &str) -> Result<Option<Payload>, Error> {
+        optional(self.execute("HGET", &[key.into(), field.into()])?)
+    }
+
+    /// Get every field and value of the hash at `key`, which is empty if the key does not
+    /// exist.
+    pub fn hgetall(&self, key: &str) -> Result<HashMap<String, Payload>, Error> {
+        pairs(values(self.execute("HGETALL", &[key.into()])?)?)
+            .map(|pairs| pairs.into_iter().collect())
+    }
+
+    /// Get the hash at `key` as a `T`, such as a struct whose fields are named after the
+    /// hash's fields.
+    ///
+    /// Values are parsed from their text according to the type of the field, so a hash
+    /// written with `HSET user:1 name Ada visits 3` can be read as:
+    ///
+    /// ```ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct User { name: String, visits: u32, email: Option<String> }
+    ///
+    /// let user: User = conn.hgetall_as("user:1")?;
+    /// ```
+    ///
+    /// A missing key is read as an empty hash, so deserialization fails unless every field of
+    /// `T` is optional.
+    #[cfg(feature = "json")]
+    pub fn hgetall_as<T: serde::de::DeserializeOwned>(&self, key: &str) -> Result<T, Error> {
+        let fields = pairs(values(self.execute("HGETALL", &[key.into()])?)?)?;
+        crate::de::from_fields(&fields).map_err(|e| Error::Other(e.to_string()))
+    }
+
+    /// Remove `fields` from the hash at `key` and return the number of fields removed.
+    pub fn hdel(&self, key: &str, fields: &[&str]) -> Result<u32, Error> {
+        let mut args: Vec<RedisParameter> = vec![key.into()];
+        args.extend(fields.iter().map(|field| (*field).into()));
+        count(integer(self.execute("HDEL", &args)?)?)
+    }
+
+    /// Add `increment` to the integer stored in `field` of the hash at `key`, treating a
+    /// missing field as 0, and return the new value.
+    pub fn hincrby(&self, key: &str, field: &str, increment: i64) -> Result<i64, Error> {
+        integer(self.execute("HINCRBY", &[key.into(), field.into(), increment.into()])?)
+    }
+}
+
+/// Group a flat `field, value, field, value, ...` reply into pairs.
+fn pairs(values: Vec<Payload>) -> Result<Vec<(String, Payload)>, Error> {
+    if values.len() % 2 != 0 {
+        return Err(Error::TypeError);
+    }
+    let mut values = values.into_iter();
+    let mut pairs = Vec::with_capacity(values.len() / 2);
+    while let (Some(field), Some(value)) = (values.next(), values.next()) {
+        let field = String::from_utf8(field).map_err(|_| Error::TypeError)?;
+        pairs.push((field, value));
+    }
+    Ok(pairs)
+}
+
+fn count(n: i64) -> Result<u32, Error> {
+    u32::try_from(n).map_err(|_| Error::TypeError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_pairs() {
+        let reply = vec![
+            b"name".to_vec(),
+            b"Ada".to_vec(),
+            b"visits".to_vec(),
+            b"3".to_vec(),
+        ];
+        assert_eq!(
+            pairs(reply).unwrap(),
+            [
+                ("name".to_owned(), b"Ada".to_vec()),
+                ("visits".to_owned(), b"3".to_vec())
+            ]
+        );
+        assert!(pairs(vec![b"name".to_vec()]).is_err());
+    }
+}
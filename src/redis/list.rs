@@ -0,0 +1,50 @@
+use super::{integer, optional, values, Connection, Error, Payload, RedisParameter};
+
+impl Connection {
+    /// Insert `values` at the head of the list at `key`, creating the list if needed, and
+    /// return the new length of the list.
+    ///
+    /// The values are inserted one after the other, so the last one ends up first.
+    pub fn lpush(&self, key: &str, values: &[&[u8]]) -> Result<u64, Error> {
+        length(integer(self.execute("LPUSH", &push_args(key, values))?)?)
+    }
+
+    /// Append `values` to the tail of the list at `key`, creating the list if needed, and
+    /// return the new length of the list.
+    pub fn rpush(&self, key: &str, values: &[&[u8]]) -> Result<u64, Error> {
+        length(integer(self.execute("RPUSH", &push_args(key, values))?)?)
+    }
+
+    /// Remove and return the first value of the list at `key`, or `None` if the list is empty.
+    pub fn lpop(&self, key: &str) -> Result<Option<Payload>, Error> {
+        optional(self.execute("LPOP", &[key.into()])?)
+    }
+
+    /// Remove and return the last value of the list at `key`, or `None` if the list is empty.
+    pub fn rpop(&self, key: &str) -> Result<Option<Payload>, Error> {
+        optional(self.execute("RPOP", &[key.into()])?)
+    }
+
+    /// Get the values of the list at `key` between `start` and `stop`, inclusive.
+    ///
+    /// Negative indexes count from the end of the list, so `lrange(key, 0, -1)` returns the
+    /// whole list.
+    pub fn lrange(&self, key: &str, start: i64, stop: i64) -> Result<Vec<Payload>, Error> {
+        values(self.execute("LRANGE", &[key.into(), start.into(), stop.into()])?)
+    }
+
+    /// Get the length of the list at `key`, which is 0 if the key does not exist.
+    pub fn llen(&self, key: &str) -> Result<u64, Error> {
+        length(integer(self.execute("LLEN", &[key.into()])?)?)
+    }
+}
+
+fn push_args(key: &str, values: &[&[u8]]) -> Vec<RedisParameter> {
+    let mut args = vec![key.into()];
+    args.extend(values.iter().map(|value| (*value).into()));
+    args
+}
+
+fn length(n: i64) -> Result<u64, Error> {
+    u64::try_from(n).map_err(|_| Error::TypeError)
+}
@@ -0,0 +1,25 @@
+use crate::http::Json;
+
+/// Deserialize a JSON message payload, for handlers which take a typed message.
+///
+/// ```ignore
+/// #[derive(serde::Deserialize)]
+/// struct OrderPlaced { id: u64 }
+///
+/// #[redis_component]
+/// fn on_message(Json(order): Json<OrderPlaced>) -> anyhow::Result<()> {
+///     println!("order {} placed", order.id);
+///     Ok(())
+/// }
+/// ```
+///
+/// By default a payload which cannot be deserialized fails the handler without calling it.
+/// Use `#[redis_component(on_malformed = "skip")]` to log and acknowledge such messages
+/// instead.
+impl<T: serde::de::DeserializeOwned> TryFrom<Vec<u8>> for Json<T> {
+    type Error = serde_json::Error;
+
+    fn try_from(payload: Vec<u8>) -> Result<Self, Self::Error> {
+        serde_json::from_slice(&payload).map(Json)
+    }
+}
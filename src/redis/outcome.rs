@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+/// What a [`redis_component`](crate::redis_component) handler did with a message.
+///
+/// Handlers can return an `Outcome` (or a `Result` of one) instead of `()` to say why a
+/// message was not processed:
+///
+/// ```ignore
+/// #[redis_component]
+/// fn on_message(Json(order): Json<Order>) -> anyhow::Result<Outcome> {
+///     if order.items.is_empty() {
+///         return Ok(Outcome::DeadLetter);
+///     }
+///     match reserve_stock(&order) {
+///         Err(OutOfStock) => Ok(Outcome::Retry { after: Some(Duration::from_secs(30)) }),
+///         Ok(()) => Ok(Outcome::Ack),
+///     }
+/// }
+/// ```
+///
+/// Redis pub/sub does not redeliver messages, so the Spin Redis trigger can only be told
+/// whether a message succeeded. [`Ack`](Outcome::Ack) is reported as a success; `Retry` and
+/// `DeadLetter` are logged with their reason and reported as a failure, which the host logs
+/// in turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The message was processed.
+    Ack,
+    /// The message could not be processed now, but could be later.
+    Retry {
+        /// How long to wait before trying again, if known.
+        after: Option<Duration>,
+    },
+    /// The message can never be processed, for example because it is malformed.
+    DeadLetter,
+}
+
+/// A value a [`redis_component`](crate::redis_component) handler can return.
+pub trait IntoOutcome {
+    /// Convert the value to an [`Outcome`].
+    fn into_outcome(self) -> Outcome;
+}
+
+impl IntoOutcome for Outcome {
+    fn into_outcome(self) -> Outcome {
+        self
+    }
+}
+
+impl IntoOutcome for () {
+    fn into_outcome(self) -> Outcome {
+        Outcome::Ack
+    }
+}
+
+/// An error is logged and treated as a failure which may be retried.
+impl<T: IntoOutcome, E: std::fmt::Display> IntoOutcome for Result<T, E> {
+    fn into_outcome(self) -> Outcome {
+        match self {
+            Ok(outcome) => outcome.into_outcome(),
+            Err(e) => {
+                eprintln!("{}", e);
+                Outcome::Retry { after: None }
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use super::{IntoOutcome, Outcome};
+
+    /// Whether the trigger should be told that the message succeeded.
+    pub fn acknowledge(outcome: impl IntoOutcome) -> bool {
+        match outcome.into_outcome() {
+            Outcome::Ack => true,
+            Outcome::Retry { after: Some(after) } => {
+                eprintln!("message should be retried after {after:?}");
+                false
+            }
+            Outcome::Retry { after: None } => false,
+            Outcome::DeadLetter => {
+                eprintln!("message cannot be processed and was dead-lettered");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_results() {
+        assert_eq!(Ok::<(), String>(()).into_outcome(), Outcome::Ack);
+        assert_eq!(
+            Err::<(), _>("boom").into_outcome(),
+            Outcome::Retry { after: None }
+        );
+        assert_eq!(
+            Ok::<_, String>(Outcome::DeadLetter).into_outcome(),
+            Outcome::DeadLetter
+        );
+        assert!(__private::acknowledge(()));
+        assert!(!__private::acknowledge(Outcome::DeadLetter));
+    }
+}
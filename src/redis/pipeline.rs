@@ -0,0 +1,168 @@
+use super::{Connection, Error, RedisParameter, RedisResult, Script};
+
+/// Runs each command packed into `ARGV` as `argc, command, arguments...`, and returns the
+/// replies flattened into one list, each preceded by its length, or by -1 and a message if the
+/// command failed.
+static SCRIPT: Script = Script::new(
+    r#"
+local results = {}
+local function push(value)
+  if type(value) == 'table' and value.ok == nil and value.err == nil then
+    local n = 0
+    for _, v in ipairs(value) do n = n + push(v) end
+    return n
+  end
+  results[#results + 1] = value
+  return 1
+end
+local i = 1
+while i <= #ARGV do
+  local argc = tonumber(ARGV[i])
+  local reply = redis.pcall(unpack(ARGV, i + 1, i + argc))
+  i = i + 1 + argc
+  if type(reply) == 'table' and reply.err then
+    results[#results + 1] = -1
+    results[#results + 1] = reply.err
+  else
+    local at = #results + 1
+    results[at] = 0
+    results[at] = push(reply)
+  end
+end
+return results
+"#,
+);
+
+/// A batch of commands sent to Redis in a single host call.
+///
+/// The `redis` interface can only run one command per call, so the batch is sent to a Lua
+/// [`Script`] which runs the commands in order and returns all of their replies. As with any
+/// script, the commands run atomically, cannot block, and must not touch keys on other
+/// nodes of a cluster.
+///
+/// ```ignore
+/// let mut pipeline = Pipeline::new();
+/// pipeline
+///     .cmd("INCR", &["hits".into()])
+///     .cmd("EXPIRE", &["hits".into(), 60.into()])
+///     .cmd("LRANGE", &["recent".into(), 0.into(), (-1).into()]);
+/// let [hits, _, recent] = &pipeline.execute(&conn)?[..] else { unreachable!() };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    args: Vec<RedisParameter>,
+    commands: Vec<String>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a command to the pipeline.
+    pub fn cmd(&mut self, command: &str, arguments: &[RedisParameter]) -> &mut Self {
+        let argc = i64::try_from(arguments.len() + 1).expect("too many arguments");
+        self.args.push(argc.into());
+        self.args.push(command.into());
+        self.args.extend_from_slice(arguments);
+        self.commands.push(command.to_owned());
+        self
+    }
+
+    /// The number of commands in the pipeline.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether the pipeline has no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Send the commands and return the reply of each, in order.
+    ///
+    /// Replies are flattened as they are by [`Connection::execute`]. If a command fails the
+    /// error names it, but the commands after it will still have run.
+    pub fn execute(&self, conn: &Connection) -> Result<Vec<Vec<RedisResult>>, Error> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.split(SCRIPT.invoke(conn, &[], &self.args)?)
+    }
+
+    /// Split the script's reply into the replies of each command.
+    fn split(&self, results: Vec<RedisResult>) -> Result<Vec<Vec<RedisResult>>, Error> {
+        let mut results = results.into_iter();
+        let mut replies = Vec::with_capacity(self.len());
+        for (index, command) in self.commands.iter().enumerate() {
+            match results.next() {
+                Some(RedisResult::Int64(-1)) => {
+                    let message = match results.next() {
+                        Some(RedisResult::Binary(b)) => String::from_utf8_lossy(&b).into_owned(),
+                        Some(RedisResult::Status(s)) => s,
+                        _ => return Err(Error::TypeError),
+                    };
+                    return Err(Error::Other(format!(
+                        "command {index} ({command}) failed: {message}"
+                    )));
+                }
+                Some(RedisResult::Int64(n)) => {
+                    let n = usize::try_from(n).map_err(|_| Error::TypeError)?;
+                    let reply: Vec<_> = results.by_ref().take(n).collect();
+                    if reply.len() != n {
+                        return Err(Error::TypeError);
+                    }
+                    replies.push(reply);
+                }
+                _ => return Err(Error::TypeError),
+            }
+        }
+        Ok(replies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_replies() {
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .cmd("INCR", &["hits".into()])
+            .cmd("LRANGE", &["recent".into(), 0.into(), (-1).into()])
+            .cmd("GET", &["missing".into()]);
+        assert_eq!(pipeline.len(), 3);
+        assert!(matches!(pipeline.args[0], RedisParameter::Int64(2)));
+
+        let reply = vec![
+            RedisResult::Int64(1),
+            RedisResult::Int64(7),
+            RedisResult::Int64(2),
+            RedisResult::Binary(b"a".to_vec()),
+            RedisResult::Binary(b"b".to_vec()),
+            RedisResult::Int64(1),
+            RedisResult::Nil,
+        ];
+        assert_eq!(
+            pipeline.split(reply).unwrap(),
+            [
+                vec![RedisResult::Int64(7)],
+                vec![
+                    RedisResult::Binary(b"a".to_vec()),
+                    RedisResult::Binary(b"b".to_vec())
+                ],
+                vec![RedisResult::Nil],
+            ]
+        );
+
+        let failed = vec![
+            RedisResult::Int64(-1),
+            RedisResult::Binary(b"WRONGTYPE Operation against a key".to_vec()),
+        ];
+        let err = pipeline.split(failed).unwrap_err();
+        assert!(matches!(err, Error::Other(m) if m.starts_with("command 0 (INCR) failed")));
+        assert!(pipeline.split(vec![RedisResult::Int64(5)]).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Error, Payload, RedisResult};
+
+/// A type which can be decoded from the reply of a command, as returned by
+/// [`Connection::execute`](super::Connection::execute).
+///
+/// Single values are decoded with the type's `TryFrom<RedisResult>` implementation, and
+/// replies made of several values into a `Vec`. As a `Vec<u8>` is a single binary value, lists
+/// can only be decoded from a whole reply, not from one `RedisResult`:
+///
+/// ```ignore
+/// let members: Vec<String> = FromRedisReply::from_reply(conn.execute("SMEMBERS", &["tags".into()])?)?;
+/// let hits: i64 = conn.execute("GET", &["hits".into()])?.remove(0).try_into()?;
+/// ```
+pub trait FromRedisReply: Sized {
+    /// Decode the reply, failing with [`Error::TypeError`] if it has the wrong shape.
+    fn from_reply(results: Vec<RedisResult>) -> Result<Self, Error>;
+}
+
+/// Take the only value of a reply.
+fn single(results: Vec<RedisResult>) -> Result<RedisResult, Error> {
+    let mut results = results.into_iter();
+    match (results.next(), results.next()) {
+        (Some(result), None) => Ok(result),
+        _ => Err(Error::TypeError),
+    }
+}
+
+macro_rules! impl_from_reply_for_single_values {
+    ($($ty:ty),*) => {
+        $(
+            impl FromRedisReply for $ty {
+                fn from_reply(results: Vec<RedisResult>) -> Result<Self, Error> {
+                    single(results)?.try_into()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_reply_for_single_values!(i64, f64, bool, String, Payload);
+
+impl FromRedisReply for () {
+    fn from_reply(_results: Vec<RedisResult>) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl FromRedisReply for Vec<RedisResult> {
+    fn from_reply(results: Vec<RedisResult>) -> Result<Self, Error> {
+        Ok(results)
+    }
+}
+
+macro_rules! impl_from_reply_for_lists {
+    ($($ty:ty),*) => {
+        $(
+            impl FromRedisReply for Vec<$ty> {
+                fn from_reply(results: Vec<RedisResult>) -> Result<Self, Error> {
+                    results.into_iter().map(TryInto::try_into).collect()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_reply_for_lists!(
+    i64,
+    f64,
+    bool,
+    String,
+    Payload,
+    Option<String>,
+    Option<Payload>
+);
+
+impl<T: FromRedisReply> FromRedisReply for Option<T> {
+    fn from_reply(results: Vec<RedisResult>) -> Result<Self, Error> {
+        match results.as_slice() {
+            [] | [RedisResult::Nil] => Ok(None),
+            _ => T::from_reply(results).map(Some),
+        }
+    }
+}
+
+/// Integers are also decoded from their text, as returned by `GET` or `HGET`.
+impl TryFrom<RedisResult> for i64 {
+    type Error = Error;
+
+    fn try_from(result: RedisResult) -> Result<Self, Error> {
+        match result {
+            RedisResult::Int64(n) => Ok(n),
+            RedisResult::Binary(b) => parse(&b),
+            _ => Err(Error::TypeError),
+        }
+    }
+}
+
+/// Redis has no floating point replies, so numbers such as sorted set scores are decoded from
+/// their text.
+impl TryFrom<RedisResult> for f64 {
+    type Error = Error;
+
+    fn try_from(result: RedisResult) -> Result<Self, Error> {
+        match result {
+            RedisResult::Int64(n) => Ok(n as f64),
+            RedisResult::Binary(b) => parse(&b),
+            _ => Err(Error::TypeError),
+        }
+    }
+}
+
+/// Lua scripts return `true` as 1 and `false` as nil, and commands such as `SISMEMBER` reply
+/// with 1 or 0.
+impl TryFrom<RedisResult> for bool {
+    type Error = Error;
+
+    fn try_from(result: RedisResult) -> Result<Self, Error> {
+        match result {
+            RedisResult::Int64(n) => Ok(n != 0),
+            RedisResult::Nil => Ok(false),
+            RedisResult::Status(_) => Ok(true),
+            RedisResult::Binary(_) => Err(Error::TypeError),
+        }
+    }
+}
+
+impl TryFrom<RedisResult> for Payload {
+    type Error = Error;
+
+    fn try_from(result: RedisResult) -> Result<Self, Error> {
+        match result {
+            RedisResult::Binary(b) => Ok(b),
+            RedisResult::Status(s) => Ok(s.into_bytes()),
+            RedisResult::Int64(n) => Ok(n.to_string().into_bytes()),
+            RedisResult::Nil => Err(Error::TypeError),
+        }
+    }
+}
+
+impl TryFrom<RedisResult> for String {
+    type Error = Error;
+
+    fn try_from(result: RedisResult) -> Result<Self, Error> {
+        String::from_utf8(result.try_into()?).map_err(|_| Error::TypeError)
+    }
+}
+
+impl<T: TryFrom<RedisResult, Error = Error>> TryFrom<RedisResult> for Option<T> {
+    type Error = Error;
+
+    fn try_from(result: RedisResult) -> Result<Self, Error> {
+        match result {
+            RedisResult::Nil => Ok(None),
+            result => result.try_into().map(Some),
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(text: &[u8]) -> Result<T, Error> {
+    std::str::from_utf8(text)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or(Error::TypeError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_results() {
+        let n: i64 = RedisResult::Int64(3).try_into().unwrap();
+        assert_eq!(n, 3);
+        let n: i64 = RedisResult::Binary(b"-4".to_vec()).try_into().unwrap();
+        assert_eq!(n, -4);
+        let score: f64 = RedisResult::Binary(b"1.5".to_vec()).try_into().unwrap();
+        assert_eq!(score, 1.5);
+        let s: String = RedisResult::Status("OK".to_owned()).try_into().unwrap();
+        assert_eq!(s, "OK");
+        let missing: Option<String> = RedisResult::Nil.try_into().unwrap();
+        assert_eq!(missing, None);
+        assert!(String::try_from(RedisResult::Nil).is_err());
+        assert!(i64::try_from(RedisResult::Binary(b"x".to_vec())).is_err());
+    }
+
+    #[test]
+    fn decodes_replies() {
+        assert_eq!(i64::from_reply(vec![RedisResult::Int64(3)]).unwrap(), 3);
+        assert!(i64::from_reply(vec![]).is_err());
+        assert!(bool::from_reply(vec![RedisResult::Int64(1)]).unwrap());
+        assert!(!bool::from_reply(vec![RedisResult::Nil]).unwrap());
+        assert_eq!(
+            Option::<String>::from_reply(vec![RedisResult::Nil]).unwrap(),
+            None
+        );
+        assert_eq!(
+            Option::<String>::from_reply(vec![RedisResult::Status("OK".to_owned())]).unwrap(),
+            Some("OK".to_owned())
+        );
+        assert_eq!(
+            Vec::<i64>::from_reply(vec![RedisResult::Int64(1), RedisResult::Int64(2)]).unwrap(),
+            [1, 2]
+        );
+        assert_eq!(
+            Vec::<Option<String>>::from_reply(vec![
+                RedisResult::Binary(b"a".to_vec()),
+                RedisResult::Nil
+            ])
+            .unwrap(),
+            [Some("a".to_owned()), None]
+        );
+        assert!(Vec::<String>::from_reply(vec![RedisResult::Nil]).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::OnceLock;
+
+use super::{Connection, Error, FromRedisReply, RedisParameter};
+
+impl Connection {
+    /// Run a Lua script with `EVAL` and decode its reply.
+    ///
+    /// `keys` and `args` are available to the script as `KEYS` and `ARGV`. To avoid sending
+    /// the script on every call, use a [`Script`] instead.
+    ///
+    /// ```ignore
+    /// let count: i64 = conn.eval(
+    ///     "return redis.call('INCRBY', KEYS[1], ARGV[1])",
+    ///     &["hits"],
+    ///     &[5.into()],
+    /// )?;
+    /// ```
+    pub fn eval<T: FromRedisReply>(
+        &self,
+        script: &str,
+        keys: &[&str],
+        args: &[RedisParameter],
+    ) -> Result<T, Error> {
+        T::from_reply(self.execute("EVAL", &script_args(script, keys, args))?)
+    }
+}
+
+/// A Lua script which is run by its SHA1 hash with `EVALSHA`, so its source is only sent when
+/// the server does not have it cached.
+///
+/// The hash is requested from the server with `SCRIPT LOAD` on first use and then kept, so a
+/// `Script` is best stored in a `static`:
+///
+/// ```ignore
+/// static RATE_LIMIT: Script = Script::new(r#"
+///     local count = redis.call('INCR', KEYS[1])
+///     if count == 1 then redis.call('EXPIRE', KEYS[1], ARGV[1]) end
+///     return count
+/// "#);
+///
+/// let count: i64 = RATE_LIMIT.invoke(&conn, &[&client_key], &[60.into()])?;
+/// ```
+#[derive(Debug)]
+pub struct Script {
+    source: &'static str,
+    sha: OnceLock<String>,
+}
+
+impl Script {
+    /// Create a script from its Lua source.
+    pub const fn new(source: &'static str) -> Self {
+        Self {
+            source,
+            sha: OnceLock::new(),
+        }
+    }
+
+    /// Run the script and decode its reply.
+    ///
+    /// If the server no longer has the script cached, for example after a restart, it is run
+    /// with `EVAL` instead, which caches it again.
+    pub fn invoke<T: FromRedisReply>(
+        &self,
+        conn: &Connection,
+        keys: &[&str],
+        args: &[RedisParameter],
+    ) -> Result<T, Error> {
+        let sha = match self.sha.get() {
+            Some(sha) => sha,
+            None => {
+                let sha = conn.execute("SCRIPT", &["LOAD".into(), self.source.into()])?;
+                let sha = String::from_reply(sha)?;
+                self.sha.get_or_init(|| sha)
+            }
+        };
+        match conn.execute("EVALSHA", &script_args(sha, keys, args)) {
+            Err(Error::Other(message)) if message.contains("NOSCRIPT") => {
+                conn.eval(self.source, keys, args)
+            }
+            results => T::from_reply(results?),
+        }
+    }
+}
+
+/// The arguments to `EVAL` or `EVALSHA`: the script, the number of keys, the keys and then the
+/// other arguments.
+fn script_args(script: &str, keys: &[&str], args: &[RedisParameter]) -> Vec<RedisParameter> {
+    let numkeys = i64::try_from(keys.len()).expect("too many keys");
+    let mut params = Vec::with_capacity(2 + keys.len() + args.len());
+    params.push(script.into());
+    params.push(numkeys.into());
+    params.extend(keys.iter().map(|key| (*key).into()));
+    params.extend_from_slice(args);
+    params
+}
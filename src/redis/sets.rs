@@ -0,0 +1,104 @@
+use super::{integer, optional, values, Connection, Error, Payload, RedisParameter};
+
+impl Connection {
+    /// Whether `value` is a member of the set at `key`.
+    ///
+    /// Members are added and listed with [`sadd`](Connection::sadd) and
+    /// [`smembers`](Connection::smembers).
+    pub fn sismember(&self, key: &str, value: &str) -> Result<bool, Error> {
+        Ok(integer(self.execute("SISMEMBER", &[key.into(), value.into()])?)? == 1)
+    }
+
+    /// Add `members` with their scores to the sorted set at `key`, updating the scores of
+    /// existing members, and return the number of members which were added.
+    pub fn zadd(&self, key: &str, members: &[(f64, &str)]) -> Result<u32, Error> {
+        let mut args: Vec<RedisParameter> = vec![key.into()];
+        for (score, member) in members {
+            args.push(score_arg(*score)?);
+            args.push((*member).into());
+        }
+        let added = integer(self.execute("ZADD", &args)?)?;
+        u32::try_from(added).map_err(|_| Error::TypeError)
+    }
+
+    /// Get the members of the sorted set at `key` with scores between `min` and `max`
+    /// inclusive, from the lowest score to the highest.
+    ///
+    /// Use `f64::NEG_INFINITY` and `f64::INFINITY` for open ranges.
+    pub fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> Result<Vec<String>, Error> {
+        let args = [key.into(), score_arg(min)?, score_arg(max)?];
+        values(self.execute("ZRANGEBYSCORE", &args)?)?
+            .into_iter()
+            .map(member)
+            .collect()
+    }
+
+    /// Like [`zrangebyscore`](Connection::zrangebyscore), but also returns the score of each
+    /// member.
+    pub fn zrangebyscore_with_scores(
+        &self,
+        key: &str,
+        min: f64,
+        max: f64,
+    ) -> Result<Vec<(String, f64)>, Error> {
+        let args = [
+            key.into(),
+            score_arg(min)?,
+            score_arg(max)?,
+            "WITHSCORES".into(),
+        ];
+        let values = values(self.execute("ZRANGEBYSCORE", &args)?)?;
+        if values.len() % 2 != 0 {
+            return Err(Error::TypeError);
+        }
+        values
+            .chunks_exact(2)
+            .map(|pair| Ok((member(pair[0].clone())?, score(&pair[1])?)))
+            .collect()
+    }
+
+    /// Add `increment` to the score of `member` in the sorted set at `key`, adding the member
+    /// with a score of 0 first if needed, and return the new score.
+    pub fn zincrby(&self, key: &str, increment: f64, member: &str) -> Result<f64, Error> {
+        let args = [key.into(), score_arg(increment)?, member.into()];
+        score(&optional(self.execute("ZINCRBY", &args)?)?.ok_or(Error::TypeError)?)
+    }
+}
+
+/// Scores are sent as text, as Redis parameters cannot carry floating point numbers.
+fn score_arg(score: f64) -> Result<RedisParameter, Error> {
+    if score.is_nan() {
+        return Err(Error::Other("a score cannot be NaN".to_owned()));
+    }
+    Ok(score.to_string().into())
+}
+
+fn score(value: &Payload) -> Result<f64, Error> {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or(Error::TypeError)
+}
+
+fn member(value: Payload) -> Result<String, Error> {
+    String::from_utf8(value).map_err(|_| Error::TypeError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        let text = |p| match p {
+            RedisParameter::Binary(b) => String::from_utf8(b).unwrap(),
+            RedisParameter::Int64(_) => panic!("expected text"),
+        };
+        assert_eq!(text(score_arg(1.5).unwrap()), "1.5");
+        assert_eq!(text(score_arg(f64::NEG_INFINITY).unwrap()), "-inf");
+        assert!(score_arg(f64::NAN).is_err());
+        assert_eq!(score(&b"12".to_vec()).unwrap(), 12.0);
+        assert_eq!(score(&b"inf".to_vec()).unwrap(), f64::INFINITY);
+        assert!(score(&b"high".to_vec()).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::{Connection, Error};
+use crate::host_error::is_connection_failure;
+
+thread_local! {
+    static SHARED: RefCell<HashMap<String, Rc<Connection>>> = RefCell::new(HashMap::new());
+}
+
+/// Get a connection to `address` which is opened on first use and then shared for the life of
+/// the component instance.
+///
+/// This saves every request handled by an instance from paying for its own connection and
+/// authentication. To also recover from a connection which has been dropped, for example by
+/// a Redis restart, use [`with_shared`].
+///
+/// ```ignore
+/// let conn = redis::shared(&std::env::var("REDIS_ADDRESS")?)?;
+/// let hits = conn.incr("hits")?;
+/// ```
+pub fn shared(address: &str) -> Result<Rc<Connection>, Error> {
+    if let Some(conn) = SHARED.with(|shared| shared.borrow().get(address).cloned()) {
+        return Ok(conn);
+    }
+    let conn = Rc::new(Connection::open(address)?);
+    SHARED.with(|shared| {
+        shared
+            .borrow_mut()
+            .insert(address.to_owned(), Rc::clone(&conn))
+    });
+    Ok(conn)
+}
+
+/// Run `f` with the [`shared`] connection to `address`, reconnecting and running it once more
+/// if it fails because the connection was lost.
+///
+/// `f` may run twice, so it should only issue commands which are safe to repeat.
+///
+/// ```ignore
+/// let value = redis::with_shared(&address, |conn| conn.get("greeting"))?;
+/// ```
+pub fn with_shared<T>(
+    address: &str,
+    f: impl Fn(&Connection) -> Result<T, Error>,
+) -> Result<T, Error> {
+    match f(&*shared(address)?) {
+        Err(e) if is_connection_error(&e) => {
+            SHARED.with(|shared| shared.borrow_mut().remove(address));
+            f(&*shared(address)?)
+        }
+        result => result,
+    }
+}
+
+/// Whether an error means the connection itself failed, rather than the command.
+///
+/// The `redis` interface reports such failures as text, so they are recognised by the I/O
+/// errors the host reports.
+fn is_connection_error(error: &Error) -> bool {
+    match error {
+        Error::Other(message) => is_connection_failure(message),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_connection_errors() {
+        assert!(is_connection_error(&Error::Other(
+            "Broken pipe (os error 32)".to_owned()
+        )));
+        assert!(is_connection_error(&Error::Other(
+            "Connection reset by peer".to_owned()
+        )));
+        assert!(!is_connection_error(&Error::Other(
+            "WRONGTYPE Operation against a key holding the wrong kind of value".to_owned()
+        )));
+        assert!(!is_connection_error(&Error::TypeError));
+    }
+}
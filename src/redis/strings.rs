@@ -0,0 +1,137 @@
+use super::{Connection, Error, RedisParameter, RedisResult};
+
+/// Expiry and conditions for [`Connection::set_with_options`], mirroring the options of the
+/// Redis `SET` command.
+///
+/// ```ignore
+/// // Cache a value for five minutes, unless another request got there first.
+/// let stored = conn.set_with_options(
+///     "page:/home",
+///     &html,
+///     SetOptions { ex: Some(300), nx: true, ..Default::default() },
+/// )?;
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetOptions {
+    /// Expire the key after this many seconds (`EX`).
+    pub ex: Option<u64>,
+    /// Expire the key after this many milliseconds (`PX`).
+    pub px: Option<u64>,
+    /// Only set the key if it does not already exist (`NX`).
+    pub nx: bool,
+    /// Only set the key if it already exists (`XX`).
+    pub xx: bool,
+    /// Keep the key's existing time to live (`KEEPTTL`, Redis 6.0 and later).
+    pub keepttl: bool,
+}
+
+impl SetOptions {
+    /// The arguments following the key and value, or an error if the options conflict.
+    fn arguments(&self) -> Result<Vec<RedisParameter>, Error> {
+        let expiries = [self.ex.is_some(), self.px.is_some(), self.keepttl];
+        if expiries.iter().filter(|set| **set).count() > 1 {
+            return Err(Error::Other(
+                "only one of `ex`, `px` and `keepttl` can be set".to_owned(),
+            ));
+        }
+        if self.nx && self.xx {
+            return Err(Error::Other("`nx` and `xx` cannot both be set".to_owned()));
+        }
+
+        let mut args = Vec::new();
+        let mut expiry = |name: &str, value: u64| -> Result<(), Error> {
+            let value = i64::try_from(value)
+                .ok()
+                .filter(|v| *v > 0)
+                .ok_or_else(|| Error::Other(format!("invalid expiry for `{name}`: {value}")))?;
+            args.push(name.to_uppercase().as_str().into());
+            args.push(value.into());
+            Ok(())
+        };
+        if let Some(seconds) = self.ex {
+            expiry("ex", seconds)?;
+        }
+        if let Some(millis) = self.px {
+            expiry("px", millis)?;
+        }
+        if self.keepttl {
+            args.push("KEEPTTL".into());
+        }
+        if self.nx {
+            args.push("NX".into());
+        }
+        if self.xx {
+            args.push("XX".into());
+        }
+        Ok(args)
+    }
+}
+
+impl Connection {
+    /// Set `key` to `value` with an expiry or condition.
+    ///
+    /// Returns `false` if the value was not stored because an `nx` or `xx` condition was not
+    /// met.
+    pub fn set_with_options(
+        &self,
+        key: &str,
+        value: &[u8],
+        options: SetOptions,
+    ) -> Result<bool, Error> {
+        let mut args = vec![key.into(), value.into()];
+        args.extend(options.arguments()?);
+        let results = self.execute("SET", &args)?;
+        Ok(!matches!(results.as_slice(), [] | [RedisResult::Nil]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(args: Vec<RedisParameter>) -> Vec<String> {
+        args.into_iter()
+            .map(|arg| match arg {
+                RedisParameter::Int64(n) => n.to_string(),
+                RedisParameter::Binary(b) => String::from_utf8(b).unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn set_arguments() {
+        let options = SetOptions {
+            ex: Some(60),
+            nx: true,
+            ..Default::default()
+        };
+        assert_eq!(words(options.arguments().unwrap()), ["EX", "60", "NX"]);
+        let options = SetOptions {
+            keepttl: true,
+            xx: true,
+            ..Default::default()
+        };
+        assert_eq!(words(options.arguments().unwrap()), ["KEEPTTL", "XX"]);
+        assert!(SetOptions::default().arguments().unwrap().is_empty());
+
+        let conflicting = [
+            SetOptions {
+                ex: Some(1),
+                px: Some(1000),
+                ..Default::default()
+            },
+            SetOptions {
+                nx: true,
+                xx: true,
+                ..Default::default()
+            },
+            SetOptions {
+                px: Some(0),
+                ..Default::default()
+            },
+        ];
+        for options in conflicting {
+            assert!(options.arguments().is_err(), "{options:?}");
+        }
+    }
+}
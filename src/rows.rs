@@ -0,0 +1,304 @@
+//! Decoding database rows into Rust types, independently of the database.
+//!
+//! [`FromRow`] is usually derived, and the derived implementation works for rows from
+//! [`sqlite`](crate::sqlite), [`pg3`](crate::pg3) and [`mysql`](crate::mysql), so the same
+//! model can be used with any of them:
+//!
+//! ```ignore
+//! use spin_sdk::rows::FromRow;
+//!
+//! #[derive(FromRow)]
+//! struct User {
+//!     id: i64,
+//!     name: String,
+//! }
+//!
+//! let users: Vec<User> = sqlite_conn.execute("SELECT id, name FROM users", &[])?.rows_as()?;
+//! let users: Vec<User> = pg_conn.query("SELECT id, name FROM users", &[])?.rows_as()?;
+//! let users: Vec<User> = mysql_conn.query("SELECT id, name FROM users", &[])?.rows_as()?;
+//! ```
+
+#[doc(inline)]
+pub use spin_macro::FromRow;
+
+/// A row of a query result whose values can be looked up by column name.
+pub trait Row {
+    /// The type of the row's values.
+    type Value;
+
+    /// The value of `column`, or `None` if the row has no such column.
+    fn get_value(&self, column: &str) -> Option<&Self::Value>;
+
+    /// Whether `value` is `NULL`.
+    fn is_null(value: &Self::Value) -> bool;
+}
+
+/// A type which can be decoded from a database value of type `V`.
+pub trait DecodeValue<V>: Sized {
+    /// Decode a value, describing the problem if it has the wrong type.
+    fn decode_value(value: &V) -> Result<Self, String>;
+}
+
+/// A type which can be decoded from a [`Row`].
+pub trait FromRow<R: Row + ?Sized>: Sized {
+    /// Decode a row.
+    fn from_row(row: &R) -> Result<Self, FromRowError>;
+}
+
+/// An error decoding a row with [`FromRow`].
+#[derive(Debug, thiserror::Error)]
+pub enum FromRowError {
+    /// The row has no column with the given name.
+    #[error("column `{0}` is missing from the row")]
+    MissingColumn(String),
+    /// The value of a column could not be decoded.
+    #[error("failed to decode column `{column}`: {reason}")]
+    Decode {
+        /// The name of the column.
+        column: String,
+        /// Why the value could not be decoded.
+        reason: String,
+    },
+}
+
+/// Decode every row of `rows` into a `T`.
+pub(crate) fn decode_all<'a, R: Row + 'a, T: FromRow<R>>(
+    rows: impl Iterator<Item = R>,
+) -> Result<Vec<T>, FromRowError> {
+    rows.map(|row| T::from_row(&row)).collect()
+}
+
+/// Define `NamedRow` and the row-decoding methods of `RowSet` and `Connection` in a database
+/// module whose `RowSet` has `columns` and `rows`, whose `DbValue` has a `DbNull` variant and
+/// whose `Decode` reports type mismatches as `Error::Decode`. The attributes given are the docs
+/// of `Connection::query_as`.
+macro_rules! impl_named_rows {
+    ($(#[$query_as_attr:meta])*) => {
+        /// A row of a [`RowSet`] which can look up values by column name.
+        pub struct NamedRow<'a> {
+            columns: &'a [Column],
+            values: &'a [DbValue],
+        }
+
+        impl<'a> NamedRow<'a> {
+            /// The values of the row, in column order.
+            pub fn values(&self) -> &[DbValue] {
+                self.values
+            }
+
+            #[cfg(feature = "json")]
+            /// Deserialize the row into a `T`. See [`Connection::query_as`].
+            pub fn deserialize<T: serde::Deserialize<'a>>(&self) -> Result<T, DeserializeError> {
+                T::deserialize($crate::de::row::RowDeserializer {
+                    columns: self.columns,
+                    values: self.values,
+                })
+            }
+        }
+
+        impl $crate::rows::Row for NamedRow<'_> {
+            type Value = DbValue;
+
+            fn get_value(&self, column: &str) -> Option<&DbValue> {
+                let i = self.columns.iter().position(|c| c.name == column)?;
+                self.values.get(i)
+            }
+
+            fn is_null(value: &DbValue) -> bool {
+                matches!(value, DbValue::DbNull)
+            }
+        }
+
+        impl Connection {
+            #[cfg(feature = "json")]
+            $(#[$query_as_attr])*
+            pub fn query_as<T: serde::de::DeserializeOwned>(
+                &self,
+                statement: &str,
+                params: &[ParameterValue],
+            ) -> Result<Vec<T>, anyhow::Error> {
+                Ok(self.query(statement, params)?.deserialize()?)
+            }
+        }
+
+        impl RowSet {
+            /// Iterate over the rows, paired with their column names.
+            pub fn named_rows(&self) -> impl Iterator<Item = NamedRow<'_>> {
+                self.rows.iter().map(|values| NamedRow {
+                    columns: &self.columns,
+                    values,
+                })
+            }
+
+            #[cfg(feature = "json")]
+            /// Deserialize every row into a `T`. See [`Connection::query_as`].
+            pub fn deserialize<'a, T: serde::Deserialize<'a>>(
+                &'a self,
+            ) -> Result<Vec<T>, DeserializeError> {
+                self.named_rows().map(|row| row.deserialize()).collect()
+            }
+
+            /// Decode every row into a `T`, usually a struct deriving
+            /// [`FromRow`](crate::rows::FromRow).
+            pub fn rows_as<'a, T: $crate::rows::FromRow<NamedRow<'a>>>(
+                &'a self,
+            ) -> Result<Vec<T>, $crate::rows::FromRowError> {
+                $crate::rows::decode_all(self.named_rows())
+            }
+        }
+
+        impl<T: Decode> $crate::rows::DecodeValue<DbValue> for T {
+            fn decode_value(value: &DbValue) -> Result<Self, String> {
+                T::decode(value).map_err(|e| match e {
+                    Error::Decode(reason) => reason,
+                    e => e.to_string(),
+                })
+            }
+        }
+    };
+}
+pub(crate) use impl_named_rows;
+
+#[doc(hidden)]
+/// Support code for the `FromRow` derive.
+pub mod __private {
+    use super::{DecodeValue, FromRowError, Row};
+
+    fn decode<R: Row + ?Sized, T: DecodeValue<R::Value>>(
+        value: &R::Value,
+        column: &str,
+    ) -> Result<T, FromRowError> {
+        T::decode_value(value).map_err(|reason| FromRowError::Decode {
+            column: column.to_owned(),
+            reason,
+        })
+    }
+
+    pub fn required<R: Row + ?Sized, T: DecodeValue<R::Value>>(
+        row: &R,
+        column: &str,
+    ) -> Result<T, FromRowError> {
+        let value = row
+            .get_value(column)
+            .ok_or_else(|| FromRowError::MissingColumn(column.to_owned()))?;
+        decode::<R, T>(value, column)
+    }
+
+    pub fn or_default<R: Row + ?Sized, T: DecodeValue<R::Value> + Default>(
+        row: &R,
+        column: &str,
+    ) -> Result<T, FromRowError> {
+        match row.get_value(column) {
+            Some(value) if !R::is_null(value) => decode::<R, T>(value, column),
+            _ => Ok(T::default()),
+        }
+    }
+
+    pub fn optional<R: Row + ?Sized, T>(row: &R, column: &str) -> Result<Option<T>, FromRowError>
+    where
+        Option<T>: DecodeValue<R::Value>,
+    {
+        match row.get_value(column) {
+            Some(value) => decode::<R, Option<T>>(value, column),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FromRow;
+    use crate::sqlite::{QueryResult, RowResult, Value};
+
+    #[derive(Debug, PartialEq, FromRow)]
+    struct User {
+        id: i64,
+        #[row(rename = "display_name")]
+        name: String,
+        #[row(default)]
+        score: f64,
+        #[row(optional)]
+        email: Option<String>,
+    }
+
+    fn result(columns: &[&str], values: Vec<Value>) -> QueryResult {
+        QueryResult {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: vec![RowResult { values }],
+        }
+    }
+
+    #[test]
+    fn sqlite() {
+        let users: Vec<User> = result(
+            &["id", "display_name", "score"],
+            vec![Value::Integer(1), Value::Text("alice".into()), Value::Null],
+        )
+        .rows_as()
+        .unwrap();
+        assert_eq!(
+            users,
+            vec![User {
+                id: 1,
+                name: "alice".into(),
+                score: 0.0,
+                email: None,
+            }]
+        );
+
+        let err = result(&["id"], vec![Value::Integer(1)])
+            .rows_as::<User>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "column `display_name` is missing from the row"
+        );
+    }
+
+    #[test]
+    fn pg3() {
+        use crate::pg3::{Column, DbDataType, DbValue, RowSet};
+
+        let column = |name: &str| Column {
+            name: name.to_owned(),
+            data_type: DbDataType::Other,
+        };
+        let rows = RowSet {
+            columns: vec![column("id"), column("display_name"), column("email")],
+            rows: vec![vec![
+                DbValue::Int64(2),
+                DbValue::Str("bob".into()),
+                DbValue::Str("bob@example.com".into()),
+            ]],
+        };
+        let users: Vec<User> = rows.rows_as().unwrap();
+        assert_eq!(users[0].email.as_deref(), Some("bob@example.com"));
+
+        let rows = RowSet {
+            columns: vec![column("id"), column("display_name")],
+            rows: vec![vec![DbValue::Str("2".into()), DbValue::Str("bob".into())]],
+        };
+        assert!(matches!(
+            rows.rows_as::<User>(),
+            Err(super::FromRowError::Decode { column, .. }) if column == "id"
+        ));
+    }
+
+    #[test]
+    fn mysql() {
+        use crate::mysql::{Column, DbDataType, DbValue, RowSet};
+
+        let column = |name: &str| Column {
+            name: name.to_owned(),
+            data_type: DbDataType::Other,
+        };
+        let rows = RowSet {
+            columns: vec![column("display_name"), column("id")],
+            rows: vec![vec![DbValue::Str("carol".into()), DbValue::Int64(3)]],
+        };
+        let users: Vec<User> = rows.rows_as().unwrap();
+        assert_eq!(users[0].id, 3);
+        assert_eq!(users[0].name, "carol");
+        assert!(users[0].email.is_none());
+    }
+}
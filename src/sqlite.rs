@@ -1,13 +1,79 @@
 use super::wit::v2::sqlite;
+use crate::rows::{DecodeValue, FromRow, FromRowError};
+
+#[cfg(feature = "json")]
+use serde::{de::DeserializeOwned, Deserialize};
 
 #[doc(inline)]
 pub use sqlite::{Connection, Error, QueryResult, RowResult, Value};
 
+/// Schema migrations for SQLite databases.
+pub mod migrations;
+
+mod batch;
+mod chunked;
+#[cfg(feature = "json")]
+mod de;
+mod query;
+mod transaction;
+#[cfg(feature = "json")]
+pub use crate::de::row::DeserializeError;
+pub use chunked::Chunks;
+pub use query::{query, Query, QueryError};
+pub use transaction::Transaction;
+
 impl sqlite::Connection {
     /// Open a connection to the default database
     pub fn open_default() -> Result<Self, Error> {
         Self::open("default")
     }
+
+    /// The rowid of the row inserted by the most recent successful `INSERT` on this connection.
+    ///
+    /// The sqlite interface does not report this with each statement, so this costs an extra
+    /// round-trip to the host to run a `SELECT last_insert_rowid()` query.
+    pub fn last_insert_rowid(&self) -> Result<i64, QueryError> {
+        self.scalar("SELECT last_insert_rowid()")
+    }
+
+    /// The number of rows modified by the most recent `INSERT`, `UPDATE` or `DELETE` on this
+    /// connection.
+    ///
+    /// The sqlite interface does not report this with each statement, so this costs an extra
+    /// round-trip to the host to run a `SELECT changes()` query.
+    pub fn changes(&self) -> Result<u64, QueryError> {
+        self.scalar("SELECT changes()")
+    }
+
+    fn scalar<T: for<'a> TryFrom<&'a Value>>(&self, query: &str) -> Result<T, QueryError> {
+        let result = self.execute(query, &[])?;
+        let row = result.rows.first().ok_or(QueryError::NoRows)?;
+        row.get(0).ok_or_else(|| {
+            QueryError::Decode(FromRowError::Decode {
+                column: result.columns.first().cloned().unwrap_or_default(),
+                reason: format!("unexpected value {:?}", row.values.first()),
+            })
+        })
+    }
+
+    #[cfg(feature = "json")]
+    /// Execute a query and deserialize each row into a `T`.
+    ///
+    /// Struct fields are matched to columns by name, and tuples to columns by position.
+    ///
+    /// ```ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct User { id: i64, name: String }
+    ///
+    /// let users: Vec<User> = conn.query_as("SELECT id, name FROM users", &[])?;
+    /// ```
+    pub fn query_as<T: DeserializeOwned>(
+        &self,
+        statement: &str,
+        parameters: &[Value],
+    ) -> Result<Vec<T>, anyhow::Error> {
+        Ok(self.execute(statement, parameters)?.deserialize_rows()?)
+    }
 }
 
 impl sqlite::QueryResult {
@@ -18,6 +84,17 @@ impl sqlite::QueryResult {
             result: r,
         })
     }
+
+    #[cfg(feature = "json")]
+    /// Deserialize every row into a `T`. See [`Connection::query_as`].
+    pub fn deserialize_rows<'a, T: Deserialize<'a>>(&'a self) -> Result<Vec<T>, DeserializeError> {
+        self.rows().map(|row| row.deserialize()).collect()
+    }
+
+    /// Decode every row into a `T`, usually a struct deriving [`FromRow`].
+    pub fn rows_as<'a, T: FromRow<Row<'a>>>(&'a self) -> Result<Vec<T>, FromRowError> {
+        crate::rows::decode_all(self.rows())
+    }
 }
 
 /// A database row result
@@ -32,6 +109,28 @@ impl<'a> Row<'a> {
         let i = self.columns.iter().position(|c| c == column)?;
         self.result.get(i)
     }
+
+    #[cfg(feature = "json")]
+    /// Deserialize the row into a `T`. See [`Connection::query_as`].
+    pub fn deserialize<T: Deserialize<'a>>(&self) -> Result<T, DeserializeError> {
+        T::deserialize(crate::de::row::RowDeserializer {
+            columns: self.columns,
+            values: &self.result.values,
+        })
+    }
+}
+
+impl<'a> crate::rows::Row for Row<'a> {
+    type Value = Value;
+
+    fn get_value(&self, column: &str) -> Option<&Value> {
+        let i = self.columns.iter().position(|c| c == column)?;
+        self.result.values.get(i)
+    }
+
+    fn is_null(value: &Value) -> bool {
+        matches!(value, Value::Null)
+    }
 }
 
 impl sqlite::RowResult {
@@ -103,3 +202,247 @@ impl<'a> TryFrom<&'a Value> for &'a [u8] {
         }
     }
 }
+
+macro_rules! value_conversions {
+    ($($t:ty => $id:ident),*) => {
+        $(impl From<$t> for Value {
+            fn from(v: $t) -> Value {
+                Value::$id(v.into())
+            }
+        })*
+    };
+}
+
+value_conversions!(
+    i8 => Integer, i16 => Integer, i32 => Integer, i64 => Integer, u8 => Integer,
+    u16 => Integer, u32 => Integer, f32 => Real, f64 => Real, String => Text, &str => Text,
+    Vec<u8> => Blob, &[u8] => Blob
+);
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Value {
+        Value::Integer(v.into())
+    }
+}
+
+impl From<&String> for Value {
+    fn from(v: &String) -> Value {
+        Value::Text(v.clone())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Value {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+/// The format SQLite's date and time functions produce, e.g. `2024-01-31 12:34:56.789`.
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// Dates and times are stored as ISO-8601 text in SQLite's `YYYY-MM-DD HH:MM:SS.SSS` format,
+/// in UTC. Integers are read as seconds since the Unix epoch.
+impl<'a> TryFrom<&'a Value> for chrono::NaiveDateTime {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(s) => chrono::NaiveDateTime::parse_from_str(s, DATETIME_FORMAT)
+                .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f"))
+                .or_else(|_| chrono::DateTime::parse_from_rfc3339(s).map(|d| d.naive_utc()))
+                .or_else(|_| {
+                    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .map(|d| d.and_time(chrono::NaiveTime::MIN))
+                })
+                .map_err(|_| ()),
+            Value::Integer(secs) => chrono::DateTime::from_timestamp(*secs, 0)
+                .map(|d| d.naive_utc())
+                .ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for chrono::DateTime<chrono::Utc> {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        chrono::NaiveDateTime::try_from(value).map(|d| d.and_utc())
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for chrono::NaiveDate {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(s) => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .or_else(|_| chrono::NaiveDateTime::try_from(value).map(|d| d.date()))
+                .map_err(|_| ()),
+            _ => chrono::NaiveDateTime::try_from(value).map(|d| d.date()),
+        }
+    }
+}
+
+impl From<chrono::NaiveDateTime> for Value {
+    fn from(v: chrono::NaiveDateTime) -> Value {
+        Value::Text(v.format(DATETIME_FORMAT).to_string())
+    }
+}
+
+impl From<chrono::DateTime<chrono::Utc>> for Value {
+    fn from(v: chrono::DateTime<chrono::Utc>) -> Value {
+        v.naive_utc().into()
+    }
+}
+
+impl From<chrono::NaiveDate> for Value {
+    fn from(v: chrono::NaiveDate) -> Value {
+        Value::Text(v.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// UUIDs are read from their text form, or from a 16 byte blob. They are stored as text;
+/// use `Value::Blob(uuid.as_bytes().to_vec())` for blob columns.
+#[cfg(feature = "uuid")]
+impl<'a> TryFrom<&'a Value> for uuid::Uuid {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(s) => s.parse().map_err(|_| ()),
+            Value::Blob(b) => uuid::Uuid::from_slice(b).map_err(|_| ()),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Value {
+    fn from(v: uuid::Uuid) -> Value {
+        Value::Text(v.to_string())
+    }
+}
+
+/// JSON documents are stored as text, as SQLite's JSON functions expect.
+#[cfg(feature = "json")]
+impl<'a> TryFrom<&'a Value> for serde_json::Value {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(s) => serde_json::from_str(s).map_err(|_| ()),
+            Value::Blob(b) => serde_json::from_slice(b).map_err(|_| ()),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Value> for Value {
+    fn from(v: serde_json::Value) -> Value {
+        Value::Text(v.to_string())
+    }
+}
+
+impl<T: DecodeValue<Value>> DecodeValue<Value> for Option<T> {
+    fn decode_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Null => Ok(None),
+            v => T::decode_value(v).map(Some),
+        }
+    }
+}
+
+macro_rules! decode_value {
+    ($($t:ty => $borrowed:ty),*) => {
+        $(impl DecodeValue<Value> for $t {
+            fn decode_value(value: &Value) -> Result<Self, String> {
+                <$borrowed>::try_from(value)
+                    .map(Into::into)
+                    .map_err(|()| format!("expected {} but got {value:?}", stringify!($t)))
+            }
+        })*
+    };
+}
+
+decode_value!(
+    bool => bool, u8 => u8, u16 => u16, u32 => u32, u64 => u64, i8 => i8, i16 => i16,
+    i32 => i32, i64 => i64, usize => usize, isize => isize, f64 => f64,
+    String => &str, Vec<u8> => &[u8], chrono::NaiveDateTime => chrono::NaiveDateTime,
+    chrono::DateTime<chrono::Utc> => chrono::DateTime<chrono::Utc>,
+    chrono::NaiveDate => chrono::NaiveDate
+);
+
+#[cfg(feature = "uuid")]
+decode_value!(uuid::Uuid => uuid::Uuid);
+
+#[cfg(feature = "json")]
+decode_value!(serde_json::Value => serde_json::Value);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chrono_round_trips() {
+        let datetime = chrono::NaiveDate::from_ymd_opt(2024, 1, 31)
+            .unwrap()
+            .and_hms_milli_opt(12, 34, 56, 789)
+            .unwrap();
+        let value = Value::from(datetime);
+        assert!(matches!(&value, Value::Text(s) if s == "2024-01-31 12:34:56.789"));
+        assert_eq!(chrono::NaiveDateTime::try_from(&value), Ok(datetime));
+        assert_eq!(
+            chrono::DateTime::<chrono::Utc>::try_from(&Value::from(datetime.and_utc())),
+            Ok(datetime.and_utc())
+        );
+
+        let date = datetime.date();
+        assert!(matches!(Value::from(date), Value::Text(s) if s == "2024-01-31"));
+        assert_eq!(chrono::NaiveDate::try_from(&Value::from(date)), Ok(date));
+        assert_eq!(chrono::NaiveDate::try_from(&value), Ok(date));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid() {
+        let id = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let value = Value::from(id);
+        assert!(matches!(&value, Value::Text(s) if s == "67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert_eq!(uuid::Uuid::try_from(&value), Ok(id));
+        assert_eq!(
+            uuid::Uuid::try_from(&Value::Blob(id.as_bytes().to_vec())),
+            Ok(id)
+        );
+        assert!(uuid::Uuid::try_from(&Value::Blob(vec![1, 2, 3])).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json() {
+        let doc = serde_json::json!({ "tags": ["a", "b"], "count": 2 });
+        let value = Value::from(doc.clone());
+        assert_eq!(serde_json::Value::try_from(&value), Ok(doc));
+        assert!(serde_json::Value::try_from(&Value::Text("{".to_owned())).is_err());
+    }
+
+    #[test]
+    fn chrono_from_sqlite_conventions() {
+        let epoch = chrono::DateTime::UNIX_EPOCH.naive_utc();
+        assert_eq!(
+            chrono::NaiveDateTime::try_from(&Value::Integer(0)),
+            Ok(epoch)
+        );
+        assert_eq!(
+            chrono::NaiveDateTime::try_from(&Value::Text("1970-01-01T00:00:00Z".to_owned())),
+            Ok(epoch)
+        );
+        assert_eq!(
+            chrono::NaiveDateTime::try_from(&Value::Text("1970-01-01".to_owned())),
+            Ok(epoch)
+        );
+        assert!(chrono::NaiveDateTime::try_from(&Value::Text("yesterday".to_owned())).is_err());
+        assert!(chrono::NaiveDate::try_from(&Value::Real(0.5)).is_err());
+    }
+}
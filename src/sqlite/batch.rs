@@ -0,0 +1,158 @@
+use super::{Connection, Error};
+
+impl Connection {
+    /// Execute a script of semicolon-separated statements, such as a schema definition.
+    ///
+    /// The `fermyon:spin/sqlite` interface executes one statement per call, so this is not a
+    /// single call to the host: the script is split into statements, each of which is a
+    /// separate round-trip, executed in order and stopping at the first error.
+    ///
+    /// The script is not atomic. Statements which succeeded before an error stay applied;
+    /// wrap the call in [`Connection::transaction`] if they should be rolled back.
+    pub fn execute_batch(&self, sql: &str) -> Result<(), Error> {
+        for statement in split_statements(sql) {
+            self.execute(statement, &[])?;
+        }
+        Ok(())
+    }
+}
+
+/// Split `sql` into its statements, without the trailing semicolons.
+///
+/// Semicolons inside string literals, quoted identifiers, comments and the bodies of
+/// `CREATE TRIGGER` statements do not end a statement.
+fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    // Words of the current statement, used to recognize trigger bodies.
+    let mut words = Vec::new();
+    // The number of open `BEGIN` and `CASE` blocks in a trigger.
+    let mut depth = 0usize;
+    // Whether the current statement has anything besides whitespace and comments.
+    let mut has_content = false;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        if !(c.is_ascii_whitespace() || c == b';' || starts_comment(&bytes[i..])) {
+            has_content = true;
+        }
+        match c {
+            quote @ (b'\'' | b'"' | b'`') => {
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == quote {
+                        // A doubled quote is an escaped quote.
+                        if bytes.get(i + 1) == Some(&quote) {
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'[' => i = find(bytes, i, b"]"),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => i = find(bytes, i, b"\n"),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = find(bytes, i + 2, b"*/"),
+            b';' => {
+                if depth == 0 {
+                    if has_content {
+                        statements.push(sql[start..i].trim());
+                    }
+                    start = i + 1;
+                    words.clear();
+                    has_content = false;
+                }
+                i += 1;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|c| !(c.is_ascii_alphanumeric() || *c == b'_'))
+                    .map_or(bytes.len(), |n| i + n);
+                let word = sql[i..end].to_ascii_uppercase();
+                if is_trigger(&words) {
+                    match word.as_str() {
+                        "BEGIN" | "CASE" => depth += 1,
+                        "END" => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                }
+                if words.len() < 3 {
+                    words.push(word);
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    if has_content {
+        statements.push(sql[start..].trim());
+    }
+    statements
+}
+
+/// The index just past the next occurrence of `needle` at or after `from`, or the end.
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> usize {
+    bytes[from..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map_or(bytes.len(), |n| from + n + needle.len())
+}
+
+fn is_trigger(words: &[String]) -> bool {
+    match words {
+        [create, trigger, ..] if create == "CREATE" && trigger == "TRIGGER" => true,
+        [create, temp, trigger] => {
+            create == "CREATE" && (temp == "TEMP" || temp == "TEMPORARY") && trigger == "TRIGGER"
+        }
+        _ => false,
+    }
+}
+
+fn starts_comment(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"--") || bytes.starts_with(b"/*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_statements() {
+        assert_eq!(
+            split_statements("CREATE TABLE a (x);\n INSERT INTO a VALUES (1) ;;"),
+            ["CREATE TABLE a (x)", "INSERT INTO a VALUES (1)"]
+        );
+        assert_eq!(split_statements("SELECT 1"), ["SELECT 1"]);
+        assert!(split_statements(" ; -- nothing\n").is_empty());
+    }
+
+    #[test]
+    fn ignores_quoted_and_commented_semicolons() {
+        assert_eq!(
+            split_statements(
+                "INSERT INTO a VALUES ('x;''y'); -- a; comment\n\
+                 SELECT \"b;\", [c;] /* ; */ FROM a"
+            ),
+            [
+                "INSERT INTO a VALUES ('x;''y')",
+                "-- a; comment\nSELECT \"b;\", [c;] /* ; */ FROM a"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_trigger_bodies_together() {
+        let trigger = "CREATE TEMP TRIGGER t AFTER INSERT ON a BEGIN \
+                       UPDATE b SET n = CASE WHEN n > 0 THEN n + 1 ELSE 1 END; \
+                       DELETE FROM c; END";
+        assert_eq!(
+            split_statements(&format!("{trigger}; SELECT 1;")),
+            [trigger, "SELECT 1"]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use super::{Connection, Error, QueryResult, Value};
+
+impl Connection {
+    /// Execute a query, fetching the results in chunks of at most `chunk_size` rows.
+    ///
+    /// The sqlite interface returns every row of a query at once, so large results are instead
+    /// fetched by running the query repeatedly with a `LIMIT` and `OFFSET`, one chunk per
+    /// call to [`Iterator::next`]. Only one chunk is held in memory at a time.
+    ///
+    /// The query should have an `ORDER BY` clause which gives a total order over the rows;
+    /// otherwise chunks may overlap or skip rows. Rows inserted or deleted while iterating can
+    /// also shift the chunk boundaries.
+    ///
+    /// ```ignore
+    /// for chunk in conn.query_chunked("SELECT id, body FROM events ORDER BY id", &[], 500) {
+    ///     for row in chunk?.rows() {
+    ///         process(row);
+    ///     }
+    /// }
+    /// ```
+    pub fn query_chunked<'a>(
+        &'a self,
+        query: &str,
+        parameters: &[Value],
+        chunk_size: usize,
+    ) -> Chunks<'a> {
+        Chunks {
+            conn: self,
+            statement: format!(
+                "SELECT * FROM ({}) LIMIT ? OFFSET ?",
+                query.trim().trim_end_matches(';')
+            ),
+            parameters: parameters.to_vec(),
+            chunk_size: chunk_size.max(1),
+            offset: 0,
+            done: false,
+        }
+    }
+}
+
+/// An iterator over the results of a query in chunks, returned by
+/// [`Connection::query_chunked`].
+pub struct Chunks<'a> {
+    conn: &'a Connection,
+    statement: String,
+    parameters: Vec<Value>,
+    chunk_size: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = Result<QueryResult, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut parameters = self.parameters.clone();
+        parameters.push(Value::Integer(self.chunk_size as i64));
+        parameters.push(Value::Integer(self.offset as i64));
+        let result = self.conn.execute(&self.statement, &parameters);
+        match &result {
+            Ok(chunk) if chunk.rows.is_empty() => {
+                self.done = true;
+                return None;
+            }
+            Ok(chunk) => {
+                self.offset += chunk.rows.len();
+                self.done = chunk.rows.len() < self.chunk_size;
+            }
+            Err(_) => self.done = true,
+        }
+        Some(result)
+    }
+}
@@ -0,0 +1,136 @@
+//! Deserialization of SQLite values, through the shared row deserializer.
+
+use serde::de::Visitor;
+
+use super::Value;
+use crate::de::row::{self, DeserializeError};
+
+impl row::Value for Value {
+    fn visit<'de, V: Visitor<'de>>(&'de self, visitor: V) -> Result<V::Value, DeserializeError> {
+        match self {
+            Value::Integer(i) => visitor.visit_i64(*i),
+            Value::Real(f) => visitor.visit_f64(*f),
+            Value::Text(s) => visitor.visit_borrowed_str(s),
+            Value::Blob(b) => visitor.visit_borrowed_bytes(b),
+            Value::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Blob(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// SQLite has no boolean type, so booleans are stored as integers.
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Integer(i) => Some(*i != 0),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::de::row::RowDeserializer;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        id: u32,
+        name: String,
+        active: bool,
+        score: f64,
+        role: Role,
+        avatar: Option<Vec<u8>>,
+    }
+
+    fn row<'a, T: Deserialize<'a>>(
+        columns: &'a [String],
+        values: &'a [Value],
+    ) -> Result<T, DeserializeError> {
+        T::deserialize(RowDeserializer { columns, values })
+    }
+
+    #[test]
+    fn struct_by_column_name() {
+        let columns =
+            ["name", "id", "active", "score", "role", "avatar", "extra"].map(String::from);
+        let values = [
+            Value::Text("alice".to_owned()),
+            Value::Integer(7),
+            Value::Integer(1),
+            Value::Integer(3),
+            Value::Text("admin".to_owned()),
+            Value::Null,
+            Value::Blob(vec![1]),
+        ];
+        let user: User = row(&columns, &values).unwrap();
+        assert_eq!(
+            user,
+            User {
+                id: 7,
+                name: "alice".to_owned(),
+                active: true,
+                score: 3.0,
+                role: Role::Admin,
+                avatar: None,
+            }
+        );
+
+        let values = [
+            Value::Text("bob".to_owned()),
+            Value::Integer(8),
+            Value::Integer(0),
+            Value::Real(0.5),
+            Value::Text("member".to_owned()),
+            Value::Blob(vec![1, 2]),
+            Value::Null,
+        ];
+        let user: User = row(&columns, &values).unwrap();
+        assert_eq!(user.role, Role::Member);
+        assert_eq!(user.avatar, Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn tuple_by_position() {
+        let columns = ["a", "b"].map(String::from);
+        let values = [Value::Integer(1), Value::Text("x".to_owned())];
+        let (a, b): (i64, &str) = row(&columns, &values).unwrap();
+        assert_eq!((a, b), (1, "x"));
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let columns = ["id".to_owned()];
+        let values = [Value::Text("seven".to_owned())];
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Id {
+            id: i64,
+        }
+        assert!(row::<Id>(&columns, &values).is_err());
+    }
+}
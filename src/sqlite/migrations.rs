@@ -0,0 +1,324 @@
+//! Schema migrations for SQLite databases.
+//!
+//! Migrations are an ordered list of named SQL scripts. Applied migrations are recorded in a
+//! `_migrations` table together with a checksum of the script, which makes it possible to
+//! detect scripts that were edited after being applied ("drift").
+//!
+//! [`apply`] applies pending migrations, typically at the start of each request; it is cheap
+//! when the schema is already up to date. [`include_migrations!`] embeds a directory of `.sql`
+//! files as a list of migrations.
+//!
+//! [`status`] reports applied, pending and drifted migrations. The resulting
+//! [`MigrationStatus`] implements [`IntoResponse`], responding `200 OK` when the schema is up
+//! to date and `503 Service Unavailable` otherwise, so it can be served directly from an admin
+//! route or used as a readiness check:
+//!
+//! ```ignore
+//! use spin_sdk::sqlite::{migrations::{self, Migration}, Connection};
+//!
+//! const MIGRATIONS: &[Migration] = migrations::include_migrations!("migrations");
+//!
+//! let conn = Connection::open_default()?;
+//! migrations::apply(&conn, MIGRATIONS)?;
+//!
+//! router.get("/admin/migrations", |_req, _params| {
+//!     let conn = Connection::open_default()?;
+//!     anyhow::Ok(migrations::status(&conn, MIGRATIONS)?)
+//! });
+//! ```
+//!
+//! [`apply`]: crate::sqlite::migrations::apply
+//! [`include_migrations!`]: crate::sqlite::migrations::include_migrations
+//! [`status`]: crate::sqlite::migrations::status
+//! [`MigrationStatus`]: crate::sqlite::migrations::MigrationStatus
+//! [`IntoResponse`]: crate::http::IntoResponse
+
+use std::fmt::Display;
+
+use super::{Connection, Value};
+use crate::http::{IntoResponse, Response};
+
+#[doc(inline)]
+pub use spin_macro::include_migrations;
+
+/// The name of the table used to track applied migrations.
+pub const MIGRATIONS_TABLE: &str = "_migrations";
+
+/// A named SQL migration script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration<'a> {
+    name: &'a str,
+    sql: &'a str,
+}
+
+impl<'a> Migration<'a> {
+    /// Create a migration from its unique name and SQL script.
+    pub const fn new(name: &'a str, sql: &'a str) -> Self {
+        Self { name, sql }
+    }
+
+    /// The unique name of the migration.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The SQL script of the migration.
+    pub fn sql(&self) -> &'a str {
+        self.sql
+    }
+
+    /// A checksum of the SQL script, used to detect drift.
+    pub fn checksum(&self) -> String {
+        crate::hash::fnv1a(self.sql.as_bytes())
+    }
+}
+
+/// A migration recorded in the migrations table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct AppliedMigration {
+    /// The name of the migration.
+    pub name: String,
+    /// The checksum of the script at the time it was applied.
+    pub checksum: String,
+    /// When the migration was applied, in seconds since the Unix epoch.
+    pub applied_at: i64,
+}
+
+/// The state of a database's schema relative to a list of migrations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct MigrationStatus {
+    /// Migrations which have been applied, in the order they were applied.
+    pub applied: Vec<AppliedMigration>,
+    /// The names of migrations which have not been applied yet.
+    pub pending: Vec<String>,
+    /// The names of applied migrations whose script has changed since it was applied.
+    pub drifted: Vec<String>,
+    /// The names of applied migrations which are not in the list of known migrations.
+    pub unknown: Vec<String>,
+}
+
+impl MigrationStatus {
+    /// Whether all migrations are applied and none of them have drifted.
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty() && self.drifted.is_empty()
+    }
+}
+
+impl Display for MigrationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "applied: {}", self.applied.len())?;
+        for (label, names) in [
+            ("pending", &self.pending),
+            ("drifted", &self.drifted),
+            ("unknown", &self.unknown),
+        ] {
+            writeln!(f, "{label}: {}", names.len())?;
+            for name in names {
+                writeln!(f, "- {name}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl IntoResponse for MigrationStatus {
+    fn into_response(self) -> Response {
+        let status = if self.is_up_to_date() { 200 } else { 503 };
+        #[cfg(feature = "json")]
+        let (content_type, body) = (
+            "application/json",
+            serde_json::to_vec(&self).expect("migration status should serialize"),
+        );
+        #[cfg(not(feature = "json"))]
+        let (content_type, body) = ("text/plain", self.to_string());
+        Response::builder()
+            .status(status)
+            .header("content-type", content_type)
+            .body(body)
+            .build()
+    }
+}
+
+/// An error working with migrations
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The migrations table contains a row which could not be read
+    #[error("invalid row in the {MIGRATIONS_TABLE} table: {0}")]
+    InvalidRow(String),
+    /// Applied migrations have changed since they were applied, so the schema may not match
+    /// what the migrations describe
+    #[error("migrations changed after being applied: {}", .0.join(", "))]
+    Drifted(Vec<String>),
+    /// A migration script failed; it was rolled back
+    #[error("migration {name} failed: {source}")]
+    Failed {
+        /// The name of the migration
+        name: String,
+        /// The error the script failed with
+        #[source]
+        source: super::Error,
+    },
+    /// A SQLite operation failed
+    #[error(transparent)]
+    Sqlite(#[from] super::Error),
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        anyhow::Error::from(self).into_response()
+    }
+}
+
+/// Report which of `migrations` have been applied to the database, which are pending, and
+/// which have drifted since they were applied.
+pub fn status(conn: &Connection, migrations: &[Migration]) -> Result<MigrationStatus, Error> {
+    Ok(compute_status(applied(conn)?, migrations))
+}
+
+/// Apply the pending `migrations` in order, returning the names of those applied.
+///
+/// Each migration runs in its own transaction together with its record in the migrations
+/// table, so a failed migration leaves no trace and can be retried. Applying is idempotent: if
+/// another instance applies a migration concurrently, it is not applied twice.
+///
+/// Nothing is applied if any applied migration has drifted.
+pub fn apply(conn: &Connection, migrations: &[Migration]) -> Result<Vec<String>, Error> {
+    let status = status(conn, migrations)?;
+    if !status.drifted.is_empty() {
+        return Err(Error::Drifted(status.drifted));
+    }
+    if status.pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    conn.execute(
+        &format!(
+            "CREATE TABLE IF NOT EXISTS {MIGRATIONS_TABLE} \
+             (name TEXT PRIMARY KEY, checksum TEXT NOT NULL, applied_at INTEGER NOT NULL)"
+        ),
+        &[],
+    )?;
+
+    let mut newly_applied = Vec::new();
+    for migration in migrations
+        .iter()
+        .filter(|m| status.pending.iter().any(|p| p == m.name))
+    {
+        let result = conn.transaction(|tx| {
+            tx.execute(
+                &format!(
+                    "INSERT INTO {MIGRATIONS_TABLE} (name, checksum, applied_at) \
+                     VALUES (?, ?, CAST(strftime('%s', 'now') AS INTEGER))"
+                ),
+                &[
+                    Value::Text(migration.name.to_owned()),
+                    Value::Text(migration.checksum()),
+                ],
+            )?;
+            tx.execute_batch(migration.sql)
+        });
+        match result {
+            Ok(()) => newly_applied.push(migration.name.to_owned()),
+            // Another instance may have applied the migration in the meantime.
+            Err(_) if is_applied(conn, migration)? => {}
+            Err(source) => {
+                return Err(Error::Failed {
+                    name: migration.name.to_owned(),
+                    source,
+                })
+            }
+        }
+    }
+    Ok(newly_applied)
+}
+
+fn is_applied(conn: &Connection, migration: &Migration) -> Result<bool, Error> {
+    Ok(applied(conn)?
+        .iter()
+        .any(|a| a.name == migration.name && a.checksum == migration.checksum()))
+}
+
+/// Return the migrations recorded in the migrations table, in the order they were applied.
+///
+/// Returns an empty list if the migrations table does not exist.
+pub fn applied(conn: &Connection) -> Result<Vec<AppliedMigration>, Error> {
+    let exists = conn.execute(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?",
+        &[Value::Text(MIGRATIONS_TABLE.to_owned())],
+    )?;
+    if exists.rows.is_empty() {
+        return Ok(Vec::new());
+    }
+    let result = conn.execute(
+        &format!("SELECT name, checksum, applied_at FROM {MIGRATIONS_TABLE} ORDER BY rowid"),
+        &[],
+    )?;
+    result
+        .rows()
+        .map(|row| {
+            let invalid = || Error::InvalidRow(format!("{:?}", row.result.values));
+            Ok(AppliedMigration {
+                name: row.get::<&str>("name").ok_or_else(invalid)?.to_owned(),
+                checksum: row.get::<&str>("checksum").ok_or_else(invalid)?.to_owned(),
+                applied_at: row.get("applied_at").ok_or_else(invalid)?,
+            })
+        })
+        .collect()
+}
+
+fn compute_status(applied: Vec<AppliedMigration>, migrations: &[Migration]) -> MigrationStatus {
+    let mut status = MigrationStatus::default();
+    for migration in migrations {
+        match applied.iter().find(|a| a.name == migration.name) {
+            Some(a) if a.checksum != migration.checksum() => {
+                status.drifted.push(migration.name.to_owned())
+            }
+            Some(_) => {}
+            None => status.pending.push(migration.name.to_owned()),
+        }
+    }
+    status.unknown = applied
+        .iter()
+        .filter(|a| !migrations.iter().any(|m| m.name == a.name))
+        .map(|a| a.name.clone())
+        .collect();
+    status.applied = applied;
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(migration: &Migration) -> AppliedMigration {
+        AppliedMigration {
+            name: migration.name.to_owned(),
+            checksum: migration.checksum(),
+            applied_at: 0,
+        }
+    }
+
+    #[test]
+    fn status() {
+        let first = Migration::new("0001", "CREATE TABLE a (id INTEGER)");
+        let second = Migration::new("0002", "CREATE TABLE b (id INTEGER)");
+
+        let status = compute_status(vec![], &[first, second]);
+        assert_eq!(status.pending, vec!["0001", "0002"]);
+        assert!(!status.is_up_to_date());
+
+        let status = compute_status(vec![applied(&first), applied(&second)], &[first, second]);
+        assert!(status.is_up_to_date());
+
+        let edited = Migration::new("0001", "CREATE TABLE a (id TEXT)");
+        let status = compute_status(vec![applied(&first)], &[edited, second]);
+        assert_eq!(status.drifted, vec!["0001"]);
+        assert_eq!(status.pending, vec!["0002"]);
+
+        let status = compute_status(vec![applied(&first), applied(&second)], &[first]);
+        assert_eq!(status.unknown, vec!["0002"]);
+        assert!(status.is_up_to_date());
+    }
+}
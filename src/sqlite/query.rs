@@ -0,0 +1,109 @@
+use std::marker::PhantomData;
+
+use super::{Connection, Error, QueryResult, Row, Value};
+use crate::rows::{FromRow, FromRowError};
+
+/// Create a [`Query`] whose parameters and result columns are checked at compile time.
+///
+/// The number of arguments must match the number of parameters in the statement. The result
+/// columns of a `SELECT`, or of the `RETURNING` clause of an `INSERT`, `UPDATE` or `DELETE`,
+/// are resolved against the schema of the crate, read from `schema.sql`
+/// next to `Cargo.toml`, or if there is none, from the `.sql` files in its `migrations`
+/// directory (see [`migrations::include_migrations!`](super::migrations::include_migrations)).
+/// Each row is decoded into a struct with a field per column:
+///
+/// ```ignore
+/// let user = sqlite::query!("SELECT id, name FROM users WHERE id = ?", id)
+///     .fetch_optional(&conn)?;
+/// if let Some(user) = user {
+///     println!("{}: {}", user.id, user.name);
+/// }
+/// ```
+///
+/// Column types follow SQLite's type affinity rules (`INTEGER` is `i64`, `TEXT` is `String`,
+/// and so on; `DATE` and `DATETIME` columns use `chrono` types, which requires a dependency on
+/// `chrono`), and columns without a `NOT NULL` constraint are `Option`s. A schema column can
+/// be given a different Rust type with a `-- type:` comment on its line:
+///
+/// ```sql
+/// CREATE TABLE users (
+///     id INTEGER PRIMARY KEY,
+///     name TEXT NOT NULL,
+///     created_at TEXT NOT NULL -- type: chrono::NaiveDateTime
+/// );
+/// ```
+///
+/// Result columns which are not plain column references, such as `COUNT(*)`, need an alias
+/// which names their type: `SELECT COUNT(*) AS "total: i64" FROM users`. Other statements,
+/// including an `INSERT ... SELECT` without `RETURNING`, return no rows and are run with
+/// [`Query::execute`].
+#[doc(inline)]
+pub use spin_macro::sqlite_query as query;
+
+/// A statement and its parameters, created with [`query!`].
+///
+/// `T` is the type each row of the result is decoded into, or `()` for statements which do not
+/// return rows.
+pub struct Query<T> {
+    sql: &'static str,
+    parameters: Vec<Value>,
+    row: PhantomData<fn() -> T>,
+}
+
+impl<T> Query<T> {
+    #[doc(hidden)]
+    pub fn __new(sql: &'static str, parameters: Vec<Value>) -> Self {
+        Self {
+            sql,
+            parameters,
+            row: PhantomData,
+        }
+    }
+
+    /// The SQL text of the statement.
+    pub fn sql(&self) -> &'static str {
+        self.sql
+    }
+
+    /// Execute the statement, returning the raw result.
+    pub fn execute(&self, conn: &Connection) -> Result<QueryResult, Error> {
+        conn.execute(self.sql, &self.parameters)
+    }
+}
+
+impl<T: for<'a> FromRow<Row<'a>>> Query<T> {
+    /// Execute the query and decode every row.
+    pub fn fetch_all(&self, conn: &Connection) -> Result<Vec<T>, QueryError> {
+        Ok(self.execute(conn)?.rows_as()?)
+    }
+
+    /// Execute the query and decode the first row, if there is one.
+    pub fn fetch_optional(&self, conn: &Connection) -> Result<Option<T>, QueryError> {
+        let result = self.execute(conn)?;
+        let row = result
+            .rows()
+            .next()
+            .map(|row| T::from_row(&row))
+            .transpose()?;
+        Ok(row)
+    }
+
+    /// Execute the query and decode the first row, failing if there is none.
+    pub fn fetch_one(&self, conn: &Connection) -> Result<T, QueryError> {
+        self.fetch_optional(conn)?.ok_or(QueryError::NoRows)
+    }
+}
+
+/// An error running a [`Query`].
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    /// The query returned no rows, but one was required
+    #[error("the query returned no rows")]
+    NoRows,
+    /// A row could not be decoded
+    #[error(transparent)]
+    Decode(#[from] FromRowError),
+    /// The query failed
+    #[error(transparent)]
+    Sqlite(#[from] Error),
+}
@@ -4,10 +4,15 @@ wasmtime::component::bindgen!({
     async: true
 });
 
+mod load;
+mod sqlite;
+mod vcr;
+
 use {
     anyhow::{anyhow, bail, Context, Result},
-    http_body_util::{combinators::BoxBody, BodyExt, Empty},
-    hyper::Request,
+    http_body_util::{combinators::BoxBody, BodyExt, Empty, Full},
+    hyper::{Request, Response},
+    sqlite::{Factory, SqliteFixture, SqliteView},
     std::{ops::Deref, sync::OnceLock},
     tokio::{
         fs,
@@ -15,12 +20,17 @@ use {
         sync::{oneshot, OnceCell},
         task,
     },
+    vcr::Cassette,
     wasmtime::{
-        component::{Component, Linker, ResourceTable},
+        component::{Component, Linker, Resource, ResourceTable},
         Config, Engine, Store,
     },
     wasmtime_wasi::preview2::{WasiCtx, WasiCtxBuilder, WasiView},
-    wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView},
+    wasmtime_wasi_http::{
+        body::HyperIncomingBody,
+        types::{default_send_request, HostFutureIncomingResponse, OutgoingRequest},
+        WasiHttpCtx, WasiHttpView,
+    },
     wit_component::ComponentEncoder,
 };
 
@@ -28,6 +38,8 @@ struct Ctx {
     table: ResourceTable,
     wasi: WasiCtx,
     wasi_http: WasiHttpCtx,
+    sqlite: SqliteFixture,
+    vcr: Option<Cassette>,
 }
 
 impl WasiHttpView for Ctx {
@@ -38,6 +50,26 @@ impl WasiHttpView for Ctx {
     fn table(&mut self) -> &mut ResourceTable {
         &mut self.table
     }
+
+    fn send_request(
+        &mut self,
+        request: OutgoingRequest,
+    ) -> wasmtime::Result<Resource<HostFutureIncomingResponse>> {
+        match self.vcr.clone() {
+            Some(cassette) => cassette.send_request(self, request),
+            None => default_send_request(self, request),
+        }
+    }
+}
+
+impl SqliteView for Ctx {
+    fn sqlite(&self) -> &SqliteFixture {
+        &self.sqlite
+    }
+
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
 }
 
 impl WasiView for Ctx {
@@ -93,6 +125,7 @@ fn store_and_linker() -> Result<(Store<Ctx>, Linker<Ctx>)> {
 
     wasmtime_wasi::preview2::command::add_to_linker(&mut linker)?;
     wasmtime_wasi_http::proxy::add_only_http_to_linker(&mut linker)?;
+    sqlite::add_to_linker(&mut linker)?;
 
     Ok((
         Store::new(
@@ -101,28 +134,28 @@ fn store_and_linker() -> Result<(Store<Ctx>, Linker<Ctx>)> {
                 table: ResourceTable::new(),
                 wasi: WasiCtxBuilder::new().inherit_stdio().build(),
                 wasi_http: WasiHttpCtx,
+                sqlite: SqliteFixture::new()?,
+                vcr: None,
             },
         ),
         linker,
     ))
 }
 
-#[tokio::test]
-async fn simple_http() -> Result<()> {
-    let component = Component::new(engine(), build_component("simple_http").await?)?;
-
-    let (mut store, linker) = store_and_linker()?;
-
-    let request = Request::get("/").body(BoxBody::new(Empty::new().map_err(|_| unreachable!())))?;
-
+/// Invoke the `wasi:http/incoming-handler` export of `component` with `request`.
+async fn handle_http(
+    mut store: Store<Ctx>,
+    linker: &Linker<Ctx>,
+    component: &Component,
+    request: Request<HyperIncomingBody>,
+) -> Result<Response<bytes::Bytes>> {
     let request = store.data_mut().new_incoming_request(request)?;
 
     let (response_tx, response_rx) = oneshot::channel();
     let response = store.data_mut().new_response_outparam(response_tx)?;
 
     let (proxy, _) =
-        wasmtime_wasi_http::proxy::Proxy::instantiate_async(&mut store, &component, &linker)
-            .await?;
+        wasmtime_wasi_http::proxy::Proxy::instantiate_async(&mut store, component, linker).await?;
 
     let handle = task::spawn(async move {
         proxy
@@ -144,17 +177,192 @@ async fn simple_http() -> Result<()> {
         }
     };
 
-    assert!(response.status().is_success());
-    assert_eq!(
-        response.into_body().collect().await?.to_bytes().deref(),
-        b"Hello, world!"
-    );
+    let (parts, body) = response.into_parts();
+    let body = body.collect().await?.to_bytes();
 
     handle
         .await
         .context("guest invocation panicked")?
         .context("guest invocation failed")?;
 
+    Ok(Response::from_parts(parts, body))
+}
+
+fn empty_request(uri: &str) -> Result<Request<HyperIncomingBody>> {
+    Ok(Request::get(uri).body(BoxBody::new(Empty::new().map_err(|_| unreachable!())))?)
+}
+
+#[tokio::test]
+async fn simple_http() -> Result<()> {
+    let component = Component::new(engine(), build_component("simple_http").await?)?;
+
+    let (store, linker) = store_and_linker()?;
+
+    let response = handle_http(store, &linker, &component, empty_request("/")?).await?;
+
+    assert!(response.status().is_success());
+    assert_eq!(response.body().deref(), b"Hello, world!");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timeout_stops_waiting_when_finished_early() -> Result<()> {
+    let component = Component::new(engine(), build_component("executor_timers").await?)?;
+
+    let (store, linker) = store_and_linker()?;
+
+    let response = handle_http(store, &linker, &component, empty_request("/")?).await?;
+
+    assert!(response.status().is_success());
+    assert_eq!(response.body().deref(), b"Ok(\"done\") 0");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "a load test; run it with `cargo test -- --ignored`"]
+async fn simple_http_load() -> Result<()> {
+    let component = Component::new(engine(), build_component("simple_http").await?)?;
+    let (_, linker) = store_and_linker()?;
+
+    let report = load::run(64, 8, || {
+        let (component, linker) = (component.clone(), linker.clone());
+        async move {
+            let (store, _) = store_and_linker()?;
+            let response = handle_http(store, &linker, &component, empty_request("/")?).await?;
+            if !response.status().is_success() {
+                bail!("unexpected status {}", response.status());
+            }
+            Ok(())
+        }
+    })
+    .await;
+
+    assert_eq!(report.succeeded, 64);
+    assert_eq!(report.failed, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn outbound_http_replay() -> Result<()> {
+    let component = Component::new(engine(), build_component("outbound_http").await?)?;
+
+    let (mut store, linker) = store_and_linker()?;
+    store.data_mut().vcr = Some(Cassette::load("test-cases/outbound-http/cassette.json")?);
+
+    let response = handle_http(store, &linker, &component, empty_request("/")?).await?;
+
+    assert!(response.status().is_success());
+    assert_eq!(response.body().deref(), b"HELLO FROM THE RECORDING");
+
+    Ok(())
+}
+
+struct User {
+    name: &'static str,
+    age: i64,
+}
+
+impl Factory for User {
+    const TABLE: &'static str = "users";
+
+    fn columns(&self) -> Vec<(&'static str, rusqlite::types::Value)> {
+        vec![
+            ("name", self.name.to_owned().into()),
+            ("age", self.age.into()),
+        ]
+    }
+}
+
+#[tokio::test]
+async fn sqlite_users() -> Result<()> {
+    let component = Component::new(engine(), build_component("sqlite_users").await?)?;
+
+    let (store, linker) = store_and_linker()?;
+
+    let db = store.data().sqlite().clone();
+    db.create_table(
+        "users",
+        "id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER NOT NULL",
+    )?;
+    db.insert(&User {
+        name: "stale",
+        age: 99,
+    })?;
+    db.truncate("users")?;
+    db.insert_all(&[
+        User {
+            name: "alice",
+            age: 31,
+        },
+        User {
+            name: "bob",
+            age: 25,
+        },
+        User {
+            name: "carol",
+            age: 47,
+        },
+    ])?;
+    assert_eq!(db.count("users")?, 3);
+
+    let response = handle_http(store, &linker, &component, empty_request("/?min_age=30")?).await?;
+
+    assert!(response.status().is_success());
+    assert_eq!(response.body().deref(), b"carol,alice");
+
+    // Three rows span two chunks.
+    let (mut store, linker) = store_and_linker()?;
+    store.data_mut().sqlite = db.clone();
+    let response = handle_http(store, &linker, &component, empty_request("/")?).await?;
+
+    assert!(response.status().is_success());
+    assert_eq!(response.body().deref(), b"carol,alice,bob");
+
+    let (mut store, linker) = store_and_linker()?;
+    store.data_mut().sqlite = db.clone();
+    let response = handle_http(store, &linker, &component, empty_request("/oldest")?).await?;
+
+    assert!(response.status().is_success());
+    assert_eq!(response.body().deref(), b"carol (47)");
+
+    // Each migration is recorded once, however many requests applied them.
+    assert_eq!(db.count("_migrations")?, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sqlite_transaction_rolls_back() -> Result<()> {
+    let component = Component::new(engine(), build_component("sqlite_users").await?)?;
+
+    let db = SqliteFixture::new()?;
+    db.create_table(
+        "users",
+        "id INTEGER PRIMARY KEY, name TEXT NOT NULL, age INTEGER NOT NULL",
+    )?;
+
+    let post = |body: &'static str| {
+        Request::post("/").body(BoxBody::new(
+            Full::new(bytes::Bytes::from(body)).map_err(|_| unreachable!()),
+        ))
+    };
+
+    let (mut store, linker) = store_and_linker()?;
+    store.data_mut().sqlite = db.clone();
+    let response = handle_http(store, &linker, &component, post("dave,52\nerin,x")?).await?;
+    assert_eq!(response.status(), 400);
+    assert_eq!(db.count("users")?, 0);
+
+    let (mut store, linker) = store_and_linker()?;
+    store.data_mut().sqlite = db.clone();
+    let response = handle_http(store, &linker, &component, post("dave,52\nerin,38")?).await?;
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.body().deref(), b"2");
+    assert_eq!(db.count("users")?, 2);
+
     Ok(())
 }
 
@@ -0,0 +1,119 @@
+//! A small load generator for measuring handler throughput and latency locally.
+//!
+//! [`run`] issues a fixed number of requests with bounded concurrency and summarizes their
+//! latencies in a [`LoadReport`]. Each request should instantiate the component in a fresh
+//! store, as Spin does, so the numbers include instantiation cost.
+
+use {
+    anyhow::Result,
+    std::{
+        fmt::Display,
+        future::Future,
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    tokio::{sync::Semaphore, task::JoinSet},
+};
+
+/// Latency and throughput statistics for a load run.
+#[derive(Debug, Clone)]
+pub struct LoadReport {
+    /// The number of requests which completed successfully.
+    pub succeeded: usize,
+    /// The number of requests which returned an error.
+    pub failed: usize,
+    /// The wall-clock duration of the whole run.
+    pub elapsed: Duration,
+    /// The latencies of successful requests, sorted ascending.
+    latencies: Vec<Duration>,
+}
+
+impl LoadReport {
+    /// Completed requests per second.
+    pub fn throughput(&self) -> f64 {
+        self.succeeded as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// The latency below which `p` percent of successful requests completed.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        percentile(&self.latencies, p)
+    }
+}
+
+impl Display for LoadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ok, {} failed in {:.2?} ({:.1} req/s)",
+            self.succeeded,
+            self.failed,
+            self.elapsed,
+            self.throughput()
+        )?;
+        for p in [50.0, 90.0, 99.0, 100.0] {
+            if let Some(latency) = self.percentile(p) {
+                write!(f, ", p{p}: {latency:.2?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Call `request` `total` times, with at most `concurrency` calls in flight at once.
+pub async fn run<F, Fut>(total: usize, concurrency: usize, request: F) -> LoadReport
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+    let start = Instant::now();
+
+    for _ in 0..total {
+        let permit = permits.clone().acquire_owned().await.unwrap();
+        let future = request();
+        tasks.spawn(async move {
+            let started = Instant::now();
+            let result = future.await;
+            drop(permit);
+            result.map(|()| started.elapsed())
+        });
+    }
+
+    let mut latencies = Vec::with_capacity(total);
+    let mut failed = 0;
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok(Ok(latency)) => latencies.push(latency),
+            Ok(Err(_)) | Err(_) => failed += 1,
+        }
+    }
+    latencies.sort();
+
+    LoadReport {
+        succeeded: latencies.len(),
+        failed,
+        elapsed: start.elapsed(),
+        latencies,
+    }
+}
+
+/// Nearest-rank percentile of sorted `latencies`.
+fn percentile(latencies: &[Duration], p: f64) -> Option<Duration> {
+    let rank = ((p / 100.0) * latencies.len() as f64).ceil() as usize;
+    latencies
+        .get(rank.clamp(1, latencies.len().max(1)) - 1)
+        .copied()
+}
+
+#[test]
+fn nearest_rank_percentiles() {
+    let latencies = (1..=10).map(Duration::from_millis).collect::<Vec<_>>();
+    assert_eq!(percentile(&latencies, 50.0), Some(Duration::from_millis(5)));
+    assert_eq!(
+        percentile(&latencies, 99.0),
+        Some(Duration::from_millis(10))
+    );
+    assert_eq!(percentile(&latencies, 0.0), Some(Duration::from_millis(1)));
+    assert_eq!(percentile(&[], 50.0), None);
+}
@@ -0,0 +1,186 @@
+//! An in-memory implementation of `fermyon:spin/sqlite` for tests, plus fixtures for seeding it.
+//!
+//! Each [`SqliteFixture`] owns its own ephemeral database, so tests which exercise database
+//! logic are isolated from one another and never touch the file system. Every database name a
+//! guest opens resolves to that same database.
+
+wasmtime::component::bindgen!({
+    path: "wit",
+    interfaces: "import fermyon:spin/sqlite@2.0.0;",
+    with: {
+        "fermyon:spin/sqlite@2.0.0/connection": Connection,
+    },
+});
+
+use {
+    self::fermyon::spin2_0_0::sqlite::{self, QueryResult, RowResult, Value},
+    anyhow::Result,
+    rusqlite::types::{ToSqlOutput, Value as SqlValue, ValueRef},
+    std::sync::{Arc, Mutex, MutexGuard},
+    wasmtime::component::{Linker, Resource, ResourceTable},
+};
+
+/// The host representation of an open `connection` resource.
+pub struct Connection;
+
+/// A row which can be inserted by [`SqliteFixture::insert`].
+pub trait Factory {
+    /// The table the row belongs to.
+    const TABLE: &'static str;
+
+    /// The column names and values of the row.
+    fn columns(&self) -> Vec<(&'static str, SqlValue)>;
+}
+
+/// An ephemeral, in-memory SQLite database.
+///
+/// Clones share the same database, so a test can keep a handle to inspect the database after
+/// the guest has run.
+#[derive(Clone)]
+pub struct SqliteFixture {
+    db: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl SqliteFixture {
+    /// Create a new, empty database.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            db: Arc::new(Mutex::new(rusqlite::Connection::open_in_memory()?)),
+        })
+    }
+
+    fn db(&self) -> MutexGuard<'_, rusqlite::Connection> {
+        self.db.lock().unwrap()
+    }
+
+    /// Create `table` with the given column definitions (e.g. `"id INTEGER PRIMARY KEY, name TEXT"`).
+    pub fn create_table(&self, table: &str, columns: &str) -> Result<&Self> {
+        self.db()
+            .execute_batch(&format!("CREATE TABLE {table} ({columns})"))?;
+        Ok(self)
+    }
+
+    /// Delete every row of `table`, keeping its schema.
+    pub fn truncate(&self, table: &str) -> Result<&Self> {
+        self.db().execute(&format!("DELETE FROM {table}"), [])?;
+        Ok(self)
+    }
+
+    /// Insert a row built by a [`Factory`], returning its rowid.
+    pub fn insert<F: Factory>(&self, row: &F) -> Result<i64> {
+        let (names, values): (Vec<_>, Vec<_>) = row.columns().into_iter().unzip();
+        let placeholders = vec!["?"; names.len()].join(", ");
+        let db = self.db();
+        db.execute(
+            &format!(
+                "INSERT INTO {} ({}) VALUES ({placeholders})",
+                F::TABLE,
+                names.join(", ")
+            ),
+            rusqlite::params_from_iter(values),
+        )?;
+        Ok(db.last_insert_rowid())
+    }
+
+    /// Insert every row in `rows`, returning their rowids.
+    pub fn insert_all<'a, F: Factory + 'a>(
+        &self,
+        rows: impl IntoIterator<Item = &'a F>,
+    ) -> Result<Vec<i64>> {
+        rows.into_iter().map(|row| self.insert(row)).collect()
+    }
+
+    /// Count the rows in `table`.
+    pub fn count(&self, table: &str) -> Result<i64> {
+        Ok(self
+            .db()
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })?)
+    }
+
+    fn execute(&self, statement: &str, parameters: Vec<Value>) -> rusqlite::Result<QueryResult> {
+        let db = self.db();
+        let mut statement = db.prepare(statement)?;
+        let columns = statement
+            .column_names()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        let mut rows =
+            statement.query(rusqlite::params_from_iter(parameters.iter().map(to_sql)))?;
+        let mut results = Vec::new();
+        while let Some(row) = rows.next()? {
+            let values = (0..columns.len())
+                .map(|i| row.get_ref(i).map(from_sql))
+                .collect::<rusqlite::Result<_>>()?;
+            results.push(RowResult { values });
+        }
+        Ok(QueryResult {
+            columns,
+            rows: results,
+        })
+    }
+}
+
+fn to_sql(value: &Value) -> ToSqlOutput<'_> {
+    ToSqlOutput::Borrowed(match value {
+        Value::Integer(i) => ValueRef::Integer(*i),
+        Value::Real(f) => ValueRef::Real(*f),
+        Value::Text(s) => ValueRef::Text(s.as_bytes()),
+        Value::Blob(b) => ValueRef::Blob(b),
+        Value::Null => ValueRef::Null,
+    })
+}
+
+fn from_sql(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Integer(i) => Value::Integer(i),
+        ValueRef::Real(f) => Value::Real(f),
+        ValueRef::Text(s) => Value::Text(String::from_utf8_lossy(s).into_owned()),
+        ValueRef::Blob(b) => Value::Blob(b.to_vec()),
+        ValueRef::Null => Value::Null,
+    }
+}
+
+/// Access to the state the host implementation needs.
+pub trait SqliteView {
+    /// The database guests connect to.
+    fn sqlite(&self) -> &SqliteFixture;
+
+    /// The table holding `connection` resources.
+    fn table(&mut self) -> &mut ResourceTable;
+}
+
+impl<T: SqliteView> sqlite::Host for T {}
+
+impl<T: SqliteView> sqlite::HostConnection for T {
+    fn open(
+        &mut self,
+        _database: String,
+    ) -> wasmtime::Result<Result<Resource<Connection>, sqlite::Error>> {
+        Ok(Ok(self.table().push(Connection)?))
+    }
+
+    fn execute(
+        &mut self,
+        _connection: Resource<Connection>,
+        statement: String,
+        parameters: Vec<Value>,
+    ) -> wasmtime::Result<Result<QueryResult, sqlite::Error>> {
+        Ok(self
+            .sqlite()
+            .execute(&statement, parameters)
+            .map_err(|e| sqlite::Error::Io(e.to_string())))
+    }
+
+    fn drop(&mut self, connection: Resource<Connection>) -> wasmtime::Result<()> {
+        self.table().delete(connection)?;
+        Ok(())
+    }
+}
+
+/// Add the in-memory `fermyon:spin/sqlite` implementation to `linker`.
+pub fn add_to_linker<T: SqliteView>(linker: &mut Linker<T>) -> Result<()> {
+    sqlite::add_to_linker(linker, |ctx| ctx)
+}
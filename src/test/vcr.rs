@@ -0,0 +1,167 @@
+//! Record/replay of outbound HTTP requests for tests.
+//!
+//! A [`Cassette`] intercepts the outbound requests a guest makes. When the cassette file does
+//! not exist yet (or `SPIN_SDK_VCR_RECORD` is set), requests are sent for real and the responses
+//! are appended to the file. Otherwise responses are replayed from the file, matching on method,
+//! URL and a hash of the request body, so tests are deterministic and never touch the network.
+
+use {
+    anyhow::{bail, Context, Result},
+    bytes::Bytes,
+    http_body_util::{BodyExt, Full},
+    serde::{Deserialize, Serialize},
+    std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    wasmtime::component::Resource,
+    wasmtime_wasi::preview2,
+    wasmtime_wasi_http::{
+        bindings::http::types::ErrorCode,
+        body::HyperOutgoingBody,
+        types::{HostFutureIncomingResponse, IncomingResponseInternal, OutgoingRequest},
+        WasiHttpView,
+    },
+};
+
+/// The environment variable which forces cassettes to be re-recorded.
+pub const RECORD_ENV: &str = "SPIN_SDK_VCR_RECORD";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Record,
+    Replay,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    uri: String,
+    body_hash: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+struct Inner {
+    path: PathBuf,
+    mode: Mode,
+    interactions: Vec<Interaction>,
+}
+
+/// A set of recorded HTTP interactions backed by a JSON file.
+#[derive(Clone)]
+pub struct Cassette(Arc<Mutex<Inner>>);
+
+impl Cassette {
+    /// Load the cassette at `path`, recording a new one if it does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let (mode, interactions) = if std::env::var_os(RECORD_ENV).is_some() || !path.exists() {
+            (Mode::Record, Vec::new())
+        } else {
+            let contents = std::fs::read(&path)
+                .with_context(|| format!("failed to read cassette {}", path.display()))?;
+            (Mode::Replay, serde_json::from_slice(&contents)?)
+        };
+        Ok(Self(Arc::new(Mutex::new(Inner {
+            path,
+            mode,
+            interactions,
+        }))))
+    }
+
+    /// Whether requests are being sent for real and recorded.
+    pub fn is_recording(&self) -> bool {
+        self.0.lock().unwrap().mode == Mode::Record
+    }
+
+    /// Handle an outbound request on behalf of `view`.
+    pub fn send_request(
+        &self,
+        view: &mut dyn WasiHttpView,
+        request: OutgoingRequest,
+    ) -> wasmtime::Result<Resource<HostFutureIncomingResponse>> {
+        let cassette = self.clone();
+        let between_bytes_timeout = request.between_bytes_timeout;
+        let handle = preview2::spawn(async move {
+            Ok(cassette
+                .respond(request.request)
+                .await
+                .map(|response| IncomingResponseInternal {
+                    resp: response
+                        .map(|body| Full::new(body).map_err(|never| match never {}).boxed()),
+                    worker: Arc::new(preview2::spawn(async {})),
+                    between_bytes_timeout,
+                })
+                .map_err(|e| ErrorCode::InternalError(Some(format!("{e:#}")))))
+        });
+        Ok(view.table().push(HostFutureIncomingResponse::new(handle))?)
+    }
+
+    async fn respond(
+        &self,
+        request: hyper::Request<HyperOutgoingBody>,
+    ) -> Result<hyper::Response<Bytes>> {
+        let (parts, body) = request.into_parts();
+        let body = body.collect().await?.to_bytes();
+        let method = parts.method.to_string();
+        let uri = parts.uri.to_string();
+        let body_hash = crate::hash::fnv1a(&body);
+
+        if !self.is_recording() {
+            let inner = self.0.lock().unwrap();
+            let Some(interaction) = inner
+                .interactions
+                .iter()
+                .find(|i| i.method == method && i.uri == uri && i.body_hash == body_hash)
+            else {
+                bail!(
+                    "no recorded response for {method} {uri} in {}; set {RECORD_ENV} to re-record",
+                    inner.path.display()
+                );
+            };
+            return interaction.to_response();
+        }
+
+        let mut outgoing = reqwest::Client::new()
+            .request(method.parse()?, &uri)
+            .timeout(Duration::from_secs(30))
+            .body(body.to_vec());
+        for (name, value) in &parts.headers {
+            outgoing = outgoing.header(name.as_str(), value.as_bytes());
+        }
+        let response = outgoing.send().await?;
+        let interaction = Interaction {
+            method,
+            uri,
+            body_hash,
+            status: response.status().as_u16(),
+            headers: response
+                .headers()
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_owned())))
+                .collect(),
+            body: String::from_utf8(response.bytes().await?.to_vec())
+                .context("only UTF-8 response bodies can be recorded")?,
+        };
+        let response = interaction.to_response()?;
+
+        let mut inner = self.0.lock().unwrap();
+        inner.interactions.push(interaction);
+        std::fs::write(&inner.path, serde_json::to_vec_pretty(&inner.interactions)?)?;
+
+        Ok(response)
+    }
+}
+
+impl Interaction {
+    fn to_response(&self) -> Result<hyper::Response<Bytes>> {
+        let mut builder = hyper::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        Ok(builder.body(Bytes::from(self.body.clone()))?)
+    }
+}
@@ -0,0 +1,118 @@
+//! Clocks for components, read through the `wasi:clocks` interfaces.
+//!
+//! ```ignore
+//! let started = time::Instant::now();
+//! let reply = handle(request).await;
+//! println!("handled at {} in {:?}", time::now().to_rfc3339(), started.elapsed());
+//! ```
+
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::wit::wasi::clocks0_2_0::{monotonic_clock, wall_clock};
+
+/// The current date and time, according to the host's wall clock.
+///
+/// The wall clock may jump, for example when the host's clock is corrected, so use an
+/// [`Instant`] to measure how long something takes.
+pub fn now() -> DateTime<Utc> {
+    let now = wall_clock::now();
+    DateTime::from_timestamp(now.seconds as i64, now.nanoseconds)
+        .expect("wall clock should be within the range of dates")
+}
+
+/// A point in time measured by the host's monotonic clock, for timing within a component.
+///
+/// Instants only ever increase, but have no relation to the date and time. They are like
+/// `std::time::Instant`, but read the clock directly rather than through the WASI adapter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// The current instant.
+    pub fn now() -> Self {
+        Self(monotonic_clock::now())
+    }
+
+    /// The time elapsed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+
+    /// The time elapsed from `earlier` to this instant, or zero if `earlier` is later.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0))
+    }
+
+    /// The instant `duration` after this one, or `None` if it cannot be represented.
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let nanos = u64::try_from(duration.as_nanos()).ok()?;
+        self.0.checked_add(nanos).map(Self)
+    }
+
+    /// The instant `duration` before this one, or `None` if it cannot be represented.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        let nanos = u64::try_from(duration.as_nanos()).ok()?;
+        self.0.checked_sub(nanos).map(Self)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    /// # Panics
+    ///
+    /// Panics if the result cannot be represented.
+    fn add(self, duration: Duration) -> Instant {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    /// # Panics
+    ///
+    /// Panics if the result cannot be represented.
+    fn sub(self, duration: Duration) -> Instant {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_between_instants() {
+        let start = Instant(1_000);
+        let end = start + Duration::from_nanos(500);
+        assert_eq!(end - start, Duration::from_nanos(500));
+        assert_eq!(start - end, Duration::ZERO);
+        assert_eq!(start.checked_sub(Duration::from_secs(1)), None);
+    }
+}
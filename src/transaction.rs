@@ -0,0 +1,112 @@
+//! The transaction guard shared by the database modules.
+
+use std::cell::Cell;
+
+/// A connection which can run the statements that control a transaction.
+pub(crate) trait Execute {
+    /// The error running a statement.
+    type Error;
+
+    /// The statement which begins a transaction.
+    const BEGIN: &'static str = "BEGIN";
+
+    /// Run `statement`, which takes no parameters.
+    fn execute_statement(&self, statement: &str) -> Result<(), Self::Error>;
+}
+
+/// An open transaction on a connection, which is rolled back when dropped unless it has been
+/// committed or rolled back.
+pub(crate) struct Guard<'a, C: Execute> {
+    conn: &'a C,
+    finished: Cell<bool>,
+}
+
+impl<'a, C: Execute> Guard<'a, C> {
+    /// Begin a transaction on `conn`.
+    pub(crate) fn begin(conn: &'a C) -> Result<Self, C::Error> {
+        conn.execute_statement(C::BEGIN)?;
+        Ok(Self {
+            conn,
+            finished: Cell::new(false),
+        })
+    }
+
+    /// The connection the transaction is open on.
+    pub(crate) fn conn(&self) -> &'a C {
+        self.conn
+    }
+
+    /// Commit the transaction, or roll it back if committing fails.
+    pub(crate) fn commit(self) -> Result<(), C::Error> {
+        self.finish("COMMIT")
+    }
+
+    /// Roll back the transaction.
+    pub(crate) fn rollback(self) -> Result<(), C::Error> {
+        self.finish("ROLLBACK")
+    }
+
+    fn finish(&self, statement: &str) -> Result<(), C::Error> {
+        // If this fails, the transaction is still open, and is rolled back when dropped.
+        self.conn.execute_statement(statement)?;
+        self.finished.set(true);
+        Ok(())
+    }
+}
+
+impl<C: Execute> Drop for Guard<'_, C> {
+    fn drop(&mut self) {
+        if !self.finished.get() {
+            // There is nobody to report a failure to, and the database discards the
+            // transaction when the connection closes anyway.
+            let _ = self.conn.execute_statement("ROLLBACK");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// A connection which records the statements run on it, failing any in `failing`.
+    #[derive(Default)]
+    struct Recorder {
+        statements: RefCell<Vec<String>>,
+        failing: &'static [&'static str],
+    }
+
+    impl Execute for Recorder {
+        type Error = ();
+
+        fn execute_statement(&self, statement: &str) -> Result<(), ()> {
+            self.statements.borrow_mut().push(statement.to_owned());
+            if self.failing.contains(&statement) {
+                return Err(());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rolls_back_unless_committed() {
+        let conn = Recorder::default();
+        Guard::begin(&conn).unwrap().commit().unwrap();
+        drop(Guard::begin(&conn).unwrap());
+        assert_eq!(
+            *conn.statements.borrow(),
+            ["BEGIN", "COMMIT", "BEGIN", "ROLLBACK"]
+        );
+    }
+
+    #[test]
+    fn rolls_back_when_commit_fails() {
+        let conn = Recorder {
+            failing: &["COMMIT"],
+            ..Default::default()
+        };
+        assert!(Guard::begin(&conn).unwrap().commit().is_err());
+        assert_eq!(*conn.statements.borrow(), ["BEGIN", "COMMIT", "ROLLBACK"]);
+    }
+}
@@ -0,0 +1,240 @@
+use std::any::type_name;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::wit::wasi::config::store;
+
+#[doc(inline)]
+pub use super::wit::v2::variables::{get, Error};
+
+mod secret;
+pub use secret::{get_secret, Secret};
+
+/// Get an application variable, or `None` if it is not defined.
+///
+/// Other errors, such as an invalid name or a failing provider, are still returned, so that a
+/// misconfiguration is not mistaken for an unset option.
+pub fn get_optional(name: &str) -> Result<Option<String>, Error> {
+    match get(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::Undefined(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Get an application variable, or `default` if it is not defined.
+///
+/// ```ignore
+/// let log_level = variables::get_or("log_level", "info")?;
+/// ```
+pub fn get_or(name: &str, default: impl Into<String>) -> Result<String, Error> {
+    Ok(get_optional(name)?.unwrap_or_else(|| default.into()))
+}
+
+/// The names of the application variables available to the component.
+///
+/// The Spin `variables` interface cannot list variables, so they are listed through the
+/// `wasi:config/store` interface, which serves the same variables on hosts that implement it.
+/// Hosts which do not implement it return an error.
+pub fn keys() -> Result<Vec<String>, Error> {
+    let mut keys = store::get_all()
+        .map_err(|e| match e {
+            store::Error::Upstream(message) => Error::Provider(message),
+            store::Error::Io(message) => Error::Other(message),
+        })?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+    keys.sort();
+    Ok(keys)
+}
+
+/// Get an application variable and parse it as a `T`, such as a port number or a flag.
+///
+/// ```ignore
+/// let port: u16 = variables::get_as("smtp_port")?;
+/// let verbose: bool = variables::get_as("verbose")?;
+/// ```
+pub fn get_as<T>(name: &str) -> Result<T, TypedError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    get_parsed(name, str::parse)
+}
+
+/// Get an application variable and convert it with `parse`, for values which have no
+/// [`FromStr`] implementation or need a different format.
+///
+/// ```ignore
+/// let timeout = variables::get_parsed("timeout_secs", |s| s.parse().map(Duration::from_secs))?;
+/// ```
+pub fn get_parsed<T, E: Display>(
+    name: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, TypedError> {
+    let value = get(name)?;
+    parse(value.trim()).map_err(|e| TypedError::Parse {
+        name: name.to_owned(),
+        expected: type_name::<T>(),
+        reason: e.to_string(),
+    })
+}
+
+/// An error getting a typed application variable.
+#[derive(Debug, thiserror::Error)]
+pub enum TypedError {
+    /// The variable could not be read.
+    #[error(transparent)]
+    Get(#[from] Error),
+    /// The variable's value could not be parsed. The value itself is not included, as it may be
+    /// a secret.
+    #[error("variable `{name}` is not a valid {expected}: {reason}")]
+    Parse {
+        /// The name of the variable.
+        name: String,
+        /// The name of the type it was parsed as.
+        expected: &'static str,
+        /// Why it could not be parsed.
+        reason: String,
+    },
+}
+
+/// Get the application variables named by the fields of `T` and deserialize them into it.
+///
+/// ```ignore
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     api_url: String,
+///     api_token: String,
+///     #[serde(default = "default_retries")]
+///     retries: u32,
+///     region: Option<String>,
+/// }
+///
+/// let config: Config = variables::get_config()?;
+/// ```
+///
+/// Each field is read from the variable with the field's name, after any `#[serde(rename)]`,
+/// and parsed from its text according to the field's type. Variables which are not defined
+/// are left out, so `Option` fields and fields with `#[serde(default)]` can be omitted from
+/// the manifest. `T` must be a struct of such fields; nested and flattened structs are not
+/// supported.
+#[cfg(feature = "json")]
+pub fn get_config<T: serde::de::DeserializeOwned>() -> Result<T, ConfigError> {
+    get_config_prefixed("")
+}
+
+/// Like [`get_config`], but reading each field from the variable named by `prefix` followed by
+/// the field's name, so that several structs can be configured from one manifest.
+///
+/// ```ignore
+/// let primary: DbConfig = variables::get_config_prefixed("primary_db_")?;
+/// ```
+#[cfg(feature = "json")]
+pub fn get_config_prefixed<T: serde::de::DeserializeOwned>(prefix: &str) -> Result<T, ConfigError> {
+    let mut fields = Vec::new();
+    for field in config::field_names::<T>()? {
+        match get(&format!("{prefix}{field}")) {
+            Ok(value) => fields.push((field.to_string(), value)),
+            Err(Error::Undefined(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    crate::de::from_fields(&fields).map_err(|e| ConfigError::Invalid(e.to_string()))
+}
+
+/// An error getting configuration with [`get_config`].
+#[cfg(feature = "json")]
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// A variable could not be read.
+    #[error(transparent)]
+    Get(#[from] Error),
+    /// The variables do not make a valid configuration, for example because one is missing or
+    /// could not be parsed.
+    #[error("invalid configuration: {0}")]
+    Invalid(String),
+}
+
+#[cfg(feature = "json")]
+mod config {
+    use serde::de::{self, Deserializer, Visitor};
+
+    use super::ConfigError;
+
+    /// The names of the fields of the struct `T`.
+    pub(super) fn field_names<T: de::DeserializeOwned>(
+    ) -> Result<&'static [&'static str], ConfigError> {
+        match T::deserialize(FieldNames) {
+            Err(Probe::Fields(fields)) => Ok(fields),
+            Err(Probe::Other(reason)) => Err(ConfigError::Invalid(reason)),
+            Ok(_) => Err(ConfigError::Invalid(
+                "the configuration type must be a struct".to_owned(),
+            )),
+        }
+    }
+
+    /// A deserializer which fails with the field names of the struct it is asked for.
+    struct FieldNames;
+
+    #[derive(Debug, thiserror::Error)]
+    enum Probe {
+        #[error("struct fields")]
+        Fields(&'static [&'static str]),
+        #[error("{0}")]
+        Other(String),
+    }
+
+    impl de::Error for Probe {
+        fn custom<T: std::fmt::Display>(msg: T) -> Self {
+            Probe::Other(msg.to_string())
+        }
+    }
+
+    impl<'de> Deserializer<'de> for FieldNames {
+        type Error = Probe;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probe> {
+            Err(Probe::Other(
+                "the configuration type must be a struct".to_owned(),
+            ))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Probe> {
+            Err(Probe::Fields(fields))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn finds_field_names() {
+            #[derive(serde::Deserialize)]
+            #[allow(dead_code)]
+            struct Config {
+                api_url: String,
+                #[serde(rename = "timeout_secs")]
+                timeout: u64,
+            }
+            assert_eq!(
+                field_names::<Config>().unwrap(),
+                ["api_url", "timeout_secs"]
+            );
+            assert!(field_names::<Vec<String>>().is_err());
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use zeroize::Zeroize;
+
+use super::{get, Error};
+
+/// A sensitive value, such as a password or API token, which is kept out of logs.
+///
+/// `Secret` prints as `[REDACTED]` with both `{}` and `{:?}`, so it can sit in configuration
+/// structs and error messages without leaking. The value is only reachable through
+/// [`expose`](Self::expose), which makes each use easy to find, and its memory is overwritten
+/// with zeros when the `Secret` is dropped.
+///
+/// ```ignore
+/// let token = variables::get_secret("api_token")?;
+/// request.header("authorization", format!("Bearer {}", token.expose()));
+/// ```
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    /// Wrap a sensitive value.
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// The sensitive value.
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Get an application variable holding a sensitive value.
+///
+/// The copy of the value made by the host bindings is handed over to the `Secret` rather than
+/// copied again, but the host itself may keep its own copies.
+pub fn get_secret(name: &str) -> Result<Secret<String>, Error> {
+    get(name).map(Secret::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_value() {
+        let secret = Secret::new("hunter2".to_owned());
+        assert_eq!(format!("{secret} {secret:?}"), "[REDACTED] [REDACTED]");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+}
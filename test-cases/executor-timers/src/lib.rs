@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use spin_sdk::{
+    http::{IntoResponse, Response},
+    http_component,
+};
+
+/// Finish a `timeout` well before its deadline, and report how many pollables are still being
+/// waited on afterwards.
+#[http_component]
+fn timers(_req: http::Request<()>) -> anyhow::Result<impl IntoResponse> {
+    let output = spin_executor::run(spin_sdk::timeout(Duration::from_secs(30), async {
+        spin_sdk::yield_now().await;
+        "done"
+    }));
+    let registered = spin_executor::__registered_pollables();
+    Ok(Response::new(200, format!("{output:?} {registered}")))
+}
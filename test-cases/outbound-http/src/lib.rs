@@ -0,0 +1,12 @@
+use spin_sdk::{
+    http::{IntoResponse, Request, Response},
+    http_component,
+};
+
+/// Fetches a greeting from an upstream service and shouts it back.
+#[http_component]
+async fn shout(_req: Request) -> anyhow::Result<impl IntoResponse> {
+    let upstream: Response = spin_sdk::http::send(Request::get("http://vcr.test/greeting")).await?;
+    let greeting = String::from_utf8(upstream.into_body())?;
+    Ok(Response::new(200, greeting.to_uppercase()))
+}
@@ -0,0 +1,6 @@
+-- Tests may create the table up front to seed it with fixtures.
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    age INTEGER NOT NULL
+);
@@ -0,0 +1 @@
+CREATE INDEX users_age ON users (age);
@@ -0,0 +1,79 @@
+use spin_sdk::{
+    http::{Method, Request, Response},
+    http_component,
+    sqlite::{
+        self,
+        migrations::{self, Migration},
+        Connection, Error, Value,
+    },
+};
+
+const MIGRATIONS: &[Migration] = migrations::include_migrations!("migrations");
+
+/// `GET` lists the users older than the `min_age` query parameter, oldest first.
+///
+/// `POST` inserts one user per `name,age` line of the body, atomically: if any line is invalid
+/// no users are inserted. The response body is the id of the last user inserted.
+///
+/// `GET /oldest` responds with the name and age of the oldest user.
+#[http_component]
+fn handle(req: Request) -> anyhow::Result<Response> {
+    let conn = Connection::open_default()?;
+    migrations::apply(&conn, MIGRATIONS)?;
+    match req.method() {
+        Method::Post => add_users(&conn, req.body()),
+        Method::Get if req.path() == "/oldest" => oldest_user(&conn),
+        _ => list_users(&conn, req.query()),
+    }
+}
+
+fn list_users(conn: &Connection, query: &str) -> anyhow::Result<Response> {
+    let min_age: i64 = query.strip_prefix("min_age=").unwrap_or("0").parse()?;
+    let mut names = Vec::new();
+    // Small chunks, so that listing exercises more than one chunk.
+    for chunk in conn.query_chunked(
+        "SELECT name FROM users WHERE age >= ? ORDER BY age DESC, id",
+        &[Value::Integer(min_age)],
+        2,
+    ) {
+        let chunk = chunk?;
+        names.extend(
+            chunk
+                .rows()
+                .filter_map(|row| row.get::<&str>("name").map(ToOwned::to_owned)),
+        );
+    }
+    Ok(Response::new(200, names.join(",")))
+}
+
+fn add_users(conn: &Connection, body: &[u8]) -> anyhow::Result<Response> {
+    let result = conn.transaction(|_| {
+        for line in std::str::from_utf8(body)?.lines() {
+            let (name, age) = line
+                .split_once(',')
+                .ok_or_else(|| anyhow::anyhow!("expected `name,age`, got `{line}`"))?;
+            sqlite::query!(
+                "INSERT INTO users (name, age) VALUES (?, ?)",
+                name,
+                age.parse::<i64>()?
+            )
+            .execute(conn)?;
+            anyhow::ensure!(conn.changes()? == 1, "expected one row to be inserted");
+        }
+        Ok(conn.last_insert_rowid()?)
+    });
+    Ok(match result {
+        Ok(id) => Response::new(201, id.to_string()),
+        Err(e) if e.is::<Error>() => return Err(e),
+        Err(e) => Response::new(400, e.to_string()),
+    })
+}
+
+fn oldest_user(conn: &Connection) -> anyhow::Result<Response> {
+    let oldest = sqlite::query!("SELECT name, age FROM users ORDER BY age DESC LIMIT ?", 1)
+        .fetch_optional(conn)?;
+    Ok(match oldest {
+        Some(user) => Response::new(200, format!("{} ({})", user.name, user.age)),
+        None => Response::new(404, ()),
+    })
+}